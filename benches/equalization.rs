@@ -0,0 +1,133 @@
+//! Scalar path is measured by building without SIMD features:
+//!
+//! ```text
+//! cargo bench --no-default-features --features rayon -- --save-baseline scalar
+//! cargo bench -- --baseline scalar
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use histogram_equalization::{
+    clahe_gray, clahe_gray_sliding_u16, clahe_gray_u16, hist_equal_gray, ClaheGridSize,
+    EqualizationColorSpace, EqualizationMethod, Equalizer,
+};
+
+const WIDTH: u32 = 1920;
+const HEIGHT: u32 = 1080;
+
+fn gray_image() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .map(|i| {
+            let x = i % WIDTH;
+            let y = i / WIDTH;
+            ((x * 7 + y * 13) % 97
+                + if (x / 64 + y / 64).is_multiple_of(2) {
+                    0
+                } else {
+                    120
+                }) as u8
+        })
+        .collect()
+}
+
+/// 12 bit noise, neighbour pixels are far apart so the sliding window can't move ranks
+/// between close values
+fn noise_u16(width: u32, height: u32) -> Vec<u16> {
+    (0..width * height)
+        .map(|i| (i.wrapping_mul(2654435761) >> 20) as u16)
+        .collect()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let gray = gray_image();
+    let gray16: Vec<u16> = gray.iter().map(|&v| (v as u16) << 2).collect();
+    let rgb: Vec<u8> = gray.iter().flat_map(|&v| [v, v / 2, 255 - v]).collect();
+
+    c.bench_function("clahe_gray", |b| {
+        let mut dst = vec![0u8; gray.len()];
+        b.iter(|| {
+            clahe_gray(
+                black_box(&gray),
+                WIDTH,
+                &mut dst,
+                WIDTH,
+                WIDTH,
+                HEIGHT,
+                2f32,
+                ClaheGridSize::new(8, 8),
+            )
+            .unwrap();
+        })
+    });
+
+    c.bench_function("clahe_gray_u16 10 bit", |b| {
+        let mut dst = vec![0u16; gray16.len()];
+        b.iter(|| {
+            clahe_gray_u16(
+                black_box(&gray16),
+                WIDTH,
+                &mut dst,
+                WIDTH,
+                WIDTH,
+                HEIGHT,
+                10,
+                2f32,
+                ClaheGridSize::new(8, 8),
+            )
+            .unwrap();
+        })
+    });
+
+    c.bench_function("clahe_gray_sliding_u16 12 bit noise", |b| {
+        let (width, height) = (WIDTH / 4, HEIGHT / 4);
+        let noise = noise_u16(width, height);
+        let mut dst = vec![0u16; noise.len()];
+        b.iter(|| {
+            clahe_gray_sliding_u16(
+                black_box(&noise),
+                width,
+                &mut dst,
+                width,
+                width,
+                height,
+                12,
+                2f32,
+                ClaheGridSize::new(8, 8),
+            )
+            .unwrap();
+        })
+    });
+
+    c.bench_function("hist_equal_gray", |b| {
+        let mut dst = vec![0u8; gray.len()];
+        b.iter(|| {
+            hist_equal_gray(black_box(&gray), WIDTH, &mut dst, WIDTH, WIDTH, HEIGHT).unwrap();
+        })
+    });
+
+    for (name, color_space) in [
+        ("clahe_yuv_rgb", EqualizationColorSpace::Yuv),
+        ("clahe_hsv_rgb", EqualizationColorSpace::Hsv),
+    ] {
+        c.bench_function(name, |b| {
+            let equalizer = Equalizer::new()
+                .with_color_space(color_space)
+                .with_method(EqualizationMethod::Clahe);
+            let mut dst = vec![0u8; rgb.len()];
+            b.iter(|| {
+                equalizer
+                    .apply(
+                        black_box(&rgb),
+                        WIDTH * 3,
+                        &mut dst,
+                        WIDTH * 3,
+                        WIDTH,
+                        HEIGHT,
+                    )
+                    .unwrap();
+            })
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,7 +1,9 @@
-use image::{DynamicImage, EncodableLayout, GenericImageView, ImageReader};
+use image::{DynamicImage, EncodableLayout, ImageReader};
 use std::time::Instant;
 
-use histogram_equalization::{clahe_hsv_rgb, clahe_lab_rgb, clahe_luv_rgb, clahe_oklab_rgb, clahe_yuv_rgb, ClaheGridSize};
+use histogram_equalization::{
+    ClaheGridSize, EqualizationColorSpace, EqualizationMethod, Equalizer, ImageConfiguration,
+};
 
 fn main() {
     let img: DynamicImage = ImageReader::open("assets/asset_1.jpg")
@@ -11,7 +13,7 @@ fn main() {
 
     let img = img.to_rgb8();
     let dimensions = img.dimensions();
-    let mut src_bytes = img.as_bytes();
+    let src_bytes = img.as_bytes();
 
     let channels = 3;
 
@@ -20,17 +22,22 @@ fn main() {
 
     let start_time = Instant::now();
 
-    clahe_oklab_rgb(
-        src_bytes,
-        stride as u32,
-        &mut dst_bytes,
-        stride as u32,
-        dimensions.0,
-        dimensions.1,
-        1.3f32,
-        ClaheGridSize::new(8, 8),
-        256,
-    );
+    Equalizer::new()
+        .with_color_space(EqualizationColorSpace::Oklab)
+        .with_method(EqualizationMethod::Clahe)
+        .with_threshold(1.3f32)
+        .with_grid_size(ClaheGridSize::new(8, 8))
+        .with_bins_count(256)
+        .with_layout(ImageConfiguration::Rgb)
+        .apply(
+            src_bytes,
+            stride as u32,
+            &mut dst_bytes,
+            stride as u32,
+            dimensions.0,
+            dimensions.1,
+        )
+        .unwrap();
 
     println!("exec time {:?}", start_time.elapsed());
 
@@ -0,0 +1,50 @@
+use crate::dispatch::blerp_block_scalar;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Interpolates 8 pixels at once, operations order is the same as in scalar `blerp`
+/// so results are identical
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn avx_blerp_block(
+    c00: &[f32],
+    c10: &[f32],
+    c01: &[f32],
+    c11: &[f32],
+    tx: &[f32],
+    ty: f32,
+    dst: &mut [f32],
+) {
+    let length = dst.len();
+    let v_ones = _mm256_set1_ps(1f32);
+    let v_ty = _mm256_set1_ps(ty);
+    let v_ty1 = _mm256_set1_ps(1f32 - ty);
+
+    let mut x = 0usize;
+    while x + 8 <= length {
+        let v_tx = _mm256_loadu_ps(tx.as_ptr().add(x));
+        let v_tx1 = _mm256_sub_ps(v_ones, v_tx);
+        let top = _mm256_add_ps(
+            _mm256_mul_ps(_mm256_loadu_ps(c00.as_ptr().add(x)), v_tx1),
+            _mm256_mul_ps(_mm256_loadu_ps(c10.as_ptr().add(x)), v_tx),
+        );
+        let bottom = _mm256_add_ps(
+            _mm256_mul_ps(_mm256_loadu_ps(c01.as_ptr().add(x)), v_tx1),
+            _mm256_mul_ps(_mm256_loadu_ps(c11.as_ptr().add(x)), v_tx),
+        );
+        let v = _mm256_add_ps(_mm256_mul_ps(top, v_ty1), _mm256_mul_ps(bottom, v_ty));
+        _mm256_storeu_ps(dst.as_mut_ptr().add(x), v);
+        x += 8;
+    }
+
+    blerp_block_scalar(
+        &c00[x..length],
+        &c10[x..length],
+        &c01[x..length],
+        &c11[x..length],
+        &tx[x..length],
+        ty,
+        &mut dst[x..],
+    );
+}
@@ -0,0 +1,55 @@
+use crate::dispatch::count_row_scalar;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Clamps 32 samples at once and counts them into sub-histograms
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn avx_count_row_u8(
+    row: &[u8],
+    max_bin: usize,
+    bins: usize,
+    lanes: usize,
+    histograms: &mut [u32],
+) {
+    let lane_mask = lanes - 1;
+    let v_max = _mm256_set1_epi8(max_bin.min(u8::MAX as usize) as u8 as i8);
+    let mut values = [0u8; 32];
+
+    let mut chunks = row.chunks_exact(32);
+    for chunk in &mut chunks {
+        let v = _mm256_min_epu8(_mm256_loadu_si256(chunk.as_ptr() as *const __m256i), v_max);
+        _mm256_storeu_si256(values.as_mut_ptr() as *mut __m256i, v);
+        for (i, &value) in values.iter().enumerate() {
+            *histograms.get_unchecked_mut((i & lane_mask) * bins + value as usize) += 1;
+        }
+    }
+
+    count_row_scalar(chunks.remainder(), max_bin, bins, lanes, histograms);
+}
+
+/// Clamps 16 samples at once and counts them into sub-histograms
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn avx_count_row_u16(
+    row: &[u16],
+    max_bin: usize,
+    bins: usize,
+    lanes: usize,
+    histograms: &mut [u32],
+) {
+    let lane_mask = lanes - 1;
+    let v_max = _mm256_set1_epi16(max_bin.min(u16::MAX as usize) as u16 as i16);
+    let mut values = [0u16; 16];
+
+    let mut chunks = row.chunks_exact(16);
+    for chunk in &mut chunks {
+        let v = _mm256_min_epu16(_mm256_loadu_si256(chunk.as_ptr() as *const __m256i), v_max);
+        _mm256_storeu_si256(values.as_mut_ptr() as *mut __m256i, v);
+        for (i, &value) in values.iter().enumerate() {
+            *histograms.get_unchecked_mut((i & lane_mask) * bins + value as usize) += 1;
+        }
+    }
+
+    count_row_scalar(chunks.remainder(), max_bin, bins, lanes, histograms);
+}
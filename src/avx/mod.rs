@@ -0,0 +1,5 @@
+mod blerp;
+mod histogram;
+
+pub(crate) use blerp::avx_blerp_block;
+pub(crate) use histogram::{avx_count_row_u16, avx_count_row_u8};
@@ -0,0 +1,408 @@
+use crate::clahe_gray_impl::clahe_gray_impl;
+use crate::error::{check_bit_depth, HistogramError};
+use crate::hist_support::{AheImplementation, ClaheMode, ClaheOptions};
+use crate::ClaheGridSize;
+
+/// Performs CLAHE on single channel (gray) image
+///
+/// For optimization purposes gray histogram bins always 256
+///
+/// # Arguments
+///
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_gray(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_gray_impl::<u8, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        256,
+    )
+}
+
+/// Performs AHE on single channel (gray) image
+///
+/// For optimization purposes gray histogram bins always 256
+///
+/// # Arguments
+///
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_gray(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_gray_impl::<u8, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        256,
+    )
+}
+
+/// Performs CLAHE on single channel (gray) high bit depth image
+///
+/// Histogram bins always `1 << bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_gray_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_bit_depth(bit_depth)?;
+    clahe_gray_impl::<u16, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        1usize << bit_depth,
+    )
+}
+
+/// Performs AHE on single channel (gray) high bit depth image
+///
+/// Histogram bins always `1 << bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_gray_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_bit_depth(bit_depth)?;
+    clahe_gray_impl::<u16, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        1usize << bit_depth,
+    )
+}
+
+/// Performs CLAHE on single channel (gray) image with the same result as `cv::CLAHE::apply`
+///
+/// # Arguments
+///
+/// * `threshold` - OpenCV clip limit, zero or less disables clipping
+/// * `grid_size` - OpenCV tile grid size - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_gray_opencv(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_gray_impl::<u8, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions {
+            mode: ClaheMode::OpenCv,
+            ..Default::default()
+        },
+        256,
+    )
+}
+
+/// Performs CLAHE on single channel (gray) 16 bit image with the same result as
+/// `cv::CLAHE::apply` on `CV_16UC1`
+///
+/// Histogram bins always 65536 as in OpenCV
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - OpenCV clip limit, zero or less disables clipping
+/// * `grid_size` - OpenCV tile grid size - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_gray_opencv_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_gray_impl::<u16, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions {
+            mode: ClaheMode::OpenCv,
+            ..Default::default()
+        },
+        65536,
+    )
+}
+
+/// Performs sliding window CLAHE on single channel (gray) image, every pixel is mapped by
+/// the clipped histogram of the window centered on it
+///
+/// For optimization purposes gray histogram bins always 256
+///
+/// # Arguments
+///
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Window is the tile of this grid, `width / grid.w` by `height / grid.h`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_gray_sliding(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_gray_impl::<u8, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions {
+            mode: ClaheMode::SlidingWindow,
+            ..Default::default()
+        },
+        256,
+    )
+}
+
+/// Performs sliding window AHE on single channel (gray) image, every pixel is mapped by
+/// the histogram of the window centered on it
+///
+/// For optimization purposes gray histogram bins always 256
+///
+/// # Arguments
+///
+/// * `grid_size` - Window is the tile of this grid, `width / grid.w` by `height / grid.h`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_gray_sliding(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_gray_impl::<u8, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions {
+            mode: ClaheMode::SlidingWindow,
+            ..Default::default()
+        },
+        256,
+    )
+}
+
+/// Performs sliding window CLAHE on single channel (gray) high bit depth image
+///
+/// Histogram bins always `1 << bit_depth`, window histogram is clipped for every pixel,
+/// so high bit depths are considerably slower
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Window is the tile of this grid, `width / grid.w` by `height / grid.h`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_gray_sliding_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_bit_depth(bit_depth)?;
+    clahe_gray_impl::<u16, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions {
+            mode: ClaheMode::SlidingWindow,
+            ..Default::default()
+        },
+        1usize << bit_depth,
+    )
+}
+
+/// Performs sliding window AHE on single channel (gray) high bit depth image
+///
+/// Histogram bins always `1 << bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Window is the tile of this grid, `width / grid.w` by `height / grid.h`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_gray_sliding_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_bit_depth(bit_depth)?;
+    clahe_gray_impl::<u16, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions {
+            mode: ClaheMode::SlidingWindow,
+            ..Default::default()
+        },
+        1usize << bit_depth,
+    )
+}
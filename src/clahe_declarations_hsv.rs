@@ -1,10 +1,16 @@
+use crate::clahe_call_proxy::clahe_impl_u16_proxy;
 use crate::clahe_impl::clahe_impl_u16;
-use crate::hist_support::AheImplementation;
-use crate::ClaheGridSize;
-use colorutils_rs::{
-    bgra_to_hsl, bgra_to_hsv, hsl_to_bgra, hsl_to_rgb, hsl_to_rgba, hsv_to_bgra, hsv_to_rgb,
-    hsv_to_rgba, rgb_to_hsl, rgb_to_hsv, rgba_to_hsl, rgba_to_hsv,
+use crate::context::EqualizerContext;
+use crate::error::HistogramError;
+use crate::hist_support::{AheImplementation, ClaheOptions};
+use crate::hsv::{
+    bgr_to_hsl, bgr_to_hsv, bgra_to_hsl, bgra_to_hsv, generic_image_to_hsv, hsl_to_bgr,
+    hsl_to_bgra, hsl_to_rgb, hsl_to_rgba, hsv_to_bgr, hsv_to_bgra, hsv_to_generic_image,
+    hsv_to_rgb, hsv_to_rgba, rgb_to_hsl, rgb_to_hsv, rgba_to_hsl, rgba_to_hsv, HsvTarget,
 };
+use crate::image_configuration::ImageConfiguration;
+use crate::ClaheGridSize;
+use colorutils_rs::TransferFunction;
 
 /// Converts image to HSV, performs CLAHE and reverts back into RGB
 ///
@@ -14,10 +20,11 @@ use colorutils_rs::{
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_hsv_rgb(
     src: &[u8],
     src_stride: u32,
@@ -28,11 +35,67 @@ pub fn clahe_hsv_rgb(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
+    clahe_impl_u16::<3, 2, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        rgb_to_hsv,
+        hsv_to_rgb,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSV, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_hsv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<3, 2, { AheImplementation::Clahe as u8 }>(
-        src, src_stride, dst, dst_stride, width, height, threshold, grid_size, bins_count,
-        rgb_to_hsv, hsv_to_rgb,
-    );
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bgr_to_hsv,
+        hsv_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs AHE and reverts back into RGB
@@ -42,10 +105,11 @@ pub fn clahe_hsv_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_hsv_rgb(
     src: &[u8],
     src_stride: u32,
@@ -55,11 +119,65 @@ pub fn ahe_hsv_rgb(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<3, 2, { AheImplementation::Ahe as u8 }>(
-        src, src_stride, dst, dst_stride, width, height, 0f32, grid_size, bins_count, rgb_to_hsv,
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        rgb_to_hsv,
         hsv_to_rgb,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSV, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_hsv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16::<3, 2, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bgr_to_hsv,
+        hsv_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs CLAHE and reverts back into RGB
@@ -70,10 +188,11 @@ pub fn ahe_hsv_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_hsl_rgb(
     src: &[u8],
     src_stride: u32,
@@ -84,11 +203,67 @@ pub fn clahe_hsl_rgb(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
+    clahe_impl_u16::<3, 2, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        rgb_to_hsl,
+        hsl_to_rgb,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSL, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_hsl_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<3, 2, { AheImplementation::Clahe as u8 }>(
-        src, src_stride, dst, dst_stride, width, height, threshold, grid_size, bins_count,
-        rgb_to_hsl, hsl_to_rgb,
-    );
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bgr_to_hsl,
+        hsl_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs AHE and reverts back into RGB
@@ -98,10 +273,11 @@ pub fn clahe_hsl_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_hsl_rgb(
     src: &[u8],
     src_stride: u32,
@@ -111,11 +287,65 @@ pub fn ahe_hsl_rgb(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<3, 2, { AheImplementation::Ahe as u8 }>(
-        src, src_stride, dst, dst_stride, width, height, 0f32, grid_size, bins_count, rgb_to_hsl,
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        rgb_to_hsl,
         hsl_to_rgb,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSV, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_hsl_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16::<3, 2, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bgr_to_hsl,
+        hsl_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs CLAHE and reverts back into RGBA
@@ -126,10 +356,11 @@ pub fn ahe_hsl_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_hsv_rgba(
     src: &[u8],
     src_stride: u32,
@@ -140,7 +371,7 @@ pub fn clahe_hsv_rgba(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
@@ -150,10 +381,14 @@ pub fn clahe_hsv_rgba(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         rgba_to_hsv,
         hsv_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs AHE and reverts back into RGBA
@@ -163,10 +398,11 @@ pub fn clahe_hsv_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_hsv_rgba(
     src: &[u8],
     src_stride: u32,
@@ -176,7 +412,7 @@ pub fn ahe_hsv_rgba(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
@@ -186,10 +422,14 @@ pub fn ahe_hsv_rgba(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         rgba_to_hsv,
         hsv_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs CLAHE and reverts back into RGBA
@@ -200,10 +440,11 @@ pub fn ahe_hsv_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_hsl_rgba(
     src: &[u8],
     src_stride: u32,
@@ -214,7 +455,7 @@ pub fn clahe_hsl_rgba(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
@@ -224,10 +465,14 @@ pub fn clahe_hsl_rgba(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         rgba_to_hsl,
         hsl_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs CLAHE and reverts back into RGBA
@@ -237,10 +482,11 @@ pub fn clahe_hsl_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_hsl_rgba(
     src: &[u8],
     src_stride: u32,
@@ -250,7 +496,7 @@ pub fn ahe_hsl_rgba(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
@@ -260,10 +506,14 @@ pub fn ahe_hsl_rgba(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         rgba_to_hsl,
         hsl_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs CLAHE and reverts back into BGRA
@@ -274,10 +524,11 @@ pub fn ahe_hsl_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_hsv_bgra(
     src: &[u8],
     src_stride: u32,
@@ -288,7 +539,7 @@ pub fn clahe_hsv_bgra(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
@@ -298,13 +549,18 @@ pub fn clahe_hsv_bgra(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         bgra_to_hsv,
         hsv_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs AHE and reverts back into BGRA
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_hsv_bgra(
     src: &[u8],
     src_stride: u32,
@@ -314,7 +570,7 @@ pub fn ahe_hsv_bgra(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
@@ -324,10 +580,14 @@ pub fn ahe_hsv_bgra(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         bgra_to_hsv,
         hsv_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs CLAHE and reverts back into BGRA
@@ -338,10 +598,11 @@ pub fn ahe_hsv_bgra(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_hsl_bgra(
     src: &[u8],
     src_stride: u32,
@@ -352,7 +613,7 @@ pub fn clahe_hsl_bgra(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
@@ -362,10 +623,14 @@ pub fn clahe_hsl_bgra(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         bgra_to_hsl,
         hsl_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs AHE and reverts back into BGRA
@@ -375,10 +640,11 @@ pub fn clahe_hsl_bgra(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_hsl_bgra(
     src: &[u8],
     src_stride: u32,
@@ -388,7 +654,7 @@ pub fn ahe_hsl_bgra(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 2, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
@@ -398,8 +664,764 @@ pub fn ahe_hsl_bgra(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         bgra_to_hsl,
         hsl_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs CLAHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsv_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsv_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs AHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsv_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsv_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs CLAHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsl_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsl_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs AHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsl_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsl_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs CLAHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsv_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs AHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsv_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs CLAHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsl_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs AHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsl_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs CLAHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsv_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs AHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsv_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs CLAHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_hsl_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs AHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_hsl_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
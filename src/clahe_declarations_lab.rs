@@ -1,7 +1,15 @@
+use crate::clahe_call_proxy::clahe_impl_u16_proxy;
 use crate::clahe_impl::clahe_impl_u16;
-use crate::hist_support::AheImplementation;
-use crate::lab::{bgra_to_lab, lab_to_bgra, lab_to_rgb, lab_to_rgba, rgb_to_lab, rgba_to_lab};
+use crate::context::EqualizerContext;
+use crate::error::HistogramError;
+use crate::hist_support::{AheImplementation, ClaheOptions};
+use crate::image_configuration::ImageConfiguration;
+use crate::lab::{
+    bgr_to_lab, bgra_to_lab, generic_image_to_lab_planar, lab_planar_to_generic_image, lab_to_bgr,
+    lab_to_bgra, lab_to_rgb, lab_to_rgba, rgb_to_lab, rgba_to_lab,
+};
 use crate::ClaheGridSize;
+use colorutils_rs::TransferFunction;
 
 /// Converts image to LAB, performs CLAHE and reverts back into RGB
 ///
@@ -11,10 +19,11 @@ use crate::ClaheGridSize;
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_lab_rgb(
     src: &[u8],
     src_stride: u32,
@@ -25,11 +34,67 @@ pub fn clahe_lab_rgb(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<3, 0, { AheImplementation::Clahe as u8 }>(
-        src, src_stride, dst, dst_stride, width, height, threshold, grid_size, bins_count,
-        rgb_to_lab, lab_to_rgb,
-    );
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        rgb_to_lab,
+        lab_to_rgb,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LAB, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_lab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16::<3, 0, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bgr_to_lab,
+        lab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs AHE and reverts back into RGB
@@ -39,10 +104,11 @@ pub fn clahe_lab_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_lab_rgb(
     src: &[u8],
     src_stride: u32,
@@ -52,11 +118,65 @@ pub fn ahe_lab_rgb(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<3, 0, { AheImplementation::Ahe as u8 }>(
-        src, src_stride, dst, dst_stride, width, height, 0f32, grid_size, bins_count, rgb_to_lab,
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        rgb_to_lab,
         lab_to_rgb,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LAB, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_lab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16::<3, 0, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bgr_to_lab,
+        lab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs CLAHE and reverts back into RGBA
@@ -67,10 +187,11 @@ pub fn ahe_lab_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_lab_rgba(
     src: &[u8],
     src_stride: u32,
@@ -81,7 +202,7 @@ pub fn clahe_lab_rgba(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 0, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
@@ -91,10 +212,14 @@ pub fn clahe_lab_rgba(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         rgba_to_lab,
         lab_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs AHE and reverts back into RGBA
@@ -104,10 +229,11 @@ pub fn clahe_lab_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_lab_rgba(
     src: &[u8],
     src_stride: u32,
@@ -117,7 +243,7 @@ pub fn ahe_lab_rgba(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 0, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
@@ -127,10 +253,14 @@ pub fn ahe_lab_rgba(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         rgba_to_lab,
         lab_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs CLAHE and reverts back into BGRA
@@ -141,10 +271,11 @@ pub fn ahe_lab_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_lab_bgra(
     src: &[u8],
     src_stride: u32,
@@ -155,7 +286,7 @@ pub fn clahe_lab_bgra(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 0, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
@@ -165,10 +296,14 @@ pub fn clahe_lab_bgra(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         bgra_to_lab,
         lab_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs AHE and reverts back into RGB
@@ -178,10 +313,11 @@ pub fn clahe_lab_bgra(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_lab_bgra(
     src: &[u8],
     src_stride: u32,
@@ -191,7 +327,7 @@ pub fn ahe_lab_bgra(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     clahe_impl_u16::<4, 0, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
@@ -201,8 +337,776 @@ pub fn ahe_lab_bgra(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
         bgra_to_lab,
         lab_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs CLAHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Rgb as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Bgr as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs AHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Rgb as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Bgr as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs CLAHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Rgba as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs AHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Rgba as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs CLAHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Bgra as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs AHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Bgra as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs CLAHE and reverts back into RGB
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_rgb_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Rgb as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs CLAHE and reverts back into BGR
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_bgr_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Bgr as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs AHE and reverts back into RGB
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_rgb_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Rgb as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs AHE and reverts back into BGR
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_bgr_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Bgr as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs CLAHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_rgba_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Rgba as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs AHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_rgba_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Rgba as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs CLAHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_lab_bgra_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Bgra as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to LAB, performs AHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_lab_bgra_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        generic_image_to_lab_planar::<f32, { ImageConfiguration::Bgra as u8 }>,
+        lab_planar_to_generic_image::<f32, { ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
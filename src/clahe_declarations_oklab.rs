@@ -1,9 +1,13 @@
 use crate::clahe_call_proxy::clahe_impl_u16_proxy;
-use crate::hist_support::AheImplementation;
+use crate::context::EqualizerContext;
+use crate::error::HistogramError;
+use crate::hist_support::{AheImplementation, ClaheOptions};
 use crate::oklab::{
-    bgra_to_oklab, oklab_to_bgra, oklab_to_rgb, oklab_to_rgba, rgb_to_oklab, rgba_to_oklab,
+    bgr_to_oklab, bgra_to_oklab, oklab_to_bgr, oklab_to_bgra, oklab_to_rgb, oklab_to_rgba,
+    rgb_to_oklab, rgba_to_oklab,
 };
 use crate::ClaheGridSize;
+use colorutils_rs::TransferFunction;
 
 /// Converts image to oklab, performs CLAHE and reverts back into RGB
 ///
@@ -13,10 +17,11 @@ use crate::ClaheGridSize;
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_oklab_rgb(
     src: &[u8],
     src_stride: u32,
@@ -27,8 +32,8 @@ pub fn clahe_oklab_rgb(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
-    clahe_impl_u16_proxy::<3, { AheImplementation::Clahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 3, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -37,10 +42,61 @@ pub fn clahe_oklab_rgb(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
+        8,
+        TransferFunction::Srgb,
         rgb_to_oklab,
         oklab_to_rgb,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_oklab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        8,
+        TransferFunction::Srgb,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to oklab, performs AHE and reverts back into RGB
@@ -50,10 +106,11 @@ pub fn clahe_oklab_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_oklab_rgb(
     src: &[u8],
     src_stride: u32,
@@ -63,8 +120,8 @@ pub fn ahe_oklab_rgb(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
-    clahe_impl_u16_proxy::<3, { AheImplementation::Ahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 3, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -73,10 +130,59 @@ pub fn ahe_oklab_rgb(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
+        8,
+        TransferFunction::Srgb,
         rgb_to_oklab,
         oklab_to_rgb,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_oklab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        8,
+        TransferFunction::Srgb,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to oklab, performs CLAHE and reverts back into RGBA
@@ -87,10 +193,11 @@ pub fn ahe_oklab_rgb(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_oklab_rgba(
     src: &[u8],
     src_stride: u32,
@@ -101,8 +208,8 @@ pub fn clahe_oklab_rgba(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
-    clahe_impl_u16_proxy::<4, { AheImplementation::Clahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 4, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -111,10 +218,16 @@ pub fn clahe_oklab_rgba(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
+        8,
+        TransferFunction::Srgb,
         rgba_to_oklab,
         oklab_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to oklab, performs AHE and reverts back into RGBA
@@ -124,10 +237,11 @@ pub fn clahe_oklab_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_oklab_rgba(
     src: &[u8],
     src_stride: u32,
@@ -137,8 +251,8 @@ pub fn ahe_oklab_rgba(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
-    clahe_impl_u16_proxy::<4, { AheImplementation::Ahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 4, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -147,10 +261,16 @@ pub fn ahe_oklab_rgba(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
+        8,
+        TransferFunction::Srgb,
         rgba_to_oklab,
         oklab_to_rgba,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to oklab, performs CLAHE and reverts back into BGRA
@@ -161,10 +281,11 @@ pub fn ahe_oklab_rgba(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_oklab_bgra(
     src: &[u8],
     src_stride: u32,
@@ -175,8 +296,8 @@ pub fn clahe_oklab_bgra(
     threshold: f32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
-    clahe_impl_u16_proxy::<4, { AheImplementation::Clahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 4, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -185,10 +306,16 @@ pub fn clahe_oklab_bgra(
         height,
         threshold,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
+        8,
+        TransferFunction::Srgb,
         bgra_to_oklab,
         oklab_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to oklab, performs AHE and reverts back into RGB
@@ -198,10 +325,11 @@ pub fn clahe_oklab_bgra(
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 /// * `bins_count` - Histogram bins, default is 128
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_oklab_bgra(
     src: &[u8],
     src_stride: u32,
@@ -211,8 +339,772 @@ pub fn ahe_oklab_bgra(
     height: u32,
     grid_size: ClaheGridSize,
     bins_count: usize,
-) {
-    clahe_impl_u16_proxy::<4, { AheImplementation::Ahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u8, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        8,
+        TransferFunction::Srgb,
+        bgra_to_oklab,
+        oklab_to_bgra,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs CLAHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        rgb_to_oklab,
+        oklab_to_rgb,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs CLAHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs AHE and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        rgb_to_oklab,
+        oklab_to_rgb,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs AHE and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs CLAHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        rgba_to_oklab,
+        oklab_to_rgba,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs AHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        rgba_to_oklab,
+        oklab_to_rgba,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs CLAHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        bgra_to_oklab,
+        oklab_to_bgra,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs AHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        bgra_to_oklab,
+        oklab_to_bgra,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs CLAHE and reverts back into RGB
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_rgb_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        rgb_to_oklab,
+        oklab_to_rgb,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs CLAHE and reverts back into BGR
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_bgr_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs AHE and reverts back into RGB
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_rgb_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        rgb_to_oklab,
+        oklab_to_rgb,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs AHE and reverts back into BGR
+///
+/// Image components are expected to be encoded with `transfer_function`
+/// Lightness above reference white is equalized up to the peak lightness of the image
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_bgr_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs CLAHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_rgba_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        rgba_to_oklab,
+        oklab_to_rgba,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs AHE and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_rgba_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        rgba_to_oklab,
+        oklab_to_rgba,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs CLAHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_oklab_bgra_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        bins_count,
+        16,
+        transfer_function,
+        bgra_to_oklab,
+        oklab_to_bgra,
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts floating point image to oklab, performs AHE and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+/// * `bins_count` - Histogram bins, default is 128
+/// * `transfer_function` - Transfer function of the image, `TransferFunction::Linear` for linear light
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_oklab_bgra_f32(
+    src: &[f32],
+    src_stride: u32,
+    dst: &mut [f32],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    transfer_function: TransferFunction,
+) -> Result<(), HistogramError> {
+    clahe_impl_u16_proxy::<f32, 4, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -221,8 +1113,14 @@ pub fn ahe_oklab_bgra(
         height,
         0f32,
         grid_size,
+        ClaheOptions::default(),
         bins_count,
+        16,
+        transfer_function,
         bgra_to_oklab,
         oklab_to_bgra,
-    );
+        None,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
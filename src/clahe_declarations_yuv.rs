@@ -1,32 +1,16 @@
-use yuv::{
-    bgra_to_ycgco444, rgb_to_ycgco444, rgba_to_ycgco444, ycgco444_alpha_to_bgra,
-    ycgco444_alpha_to_rgba, ycgco444_to_rgb, YuvError, YuvPlanarImage, YuvPlanarImageWithAlpha,
-    YuvRange,
-};
+use yuv::YuvRange;
 
 use crate::clahe_yuv_impl::clahe_yuv_impl;
-use crate::hist_support::AheImplementation;
+use crate::context::YuvScratch;
+use crate::error::{check_yuv_bit_depth, HistogramError};
+use crate::hist_support::{AheImplementation, ClaheOptions};
+use crate::image_configuration::ImageConfiguration;
+use crate::yuv_support::{
+    image_to_yuv444, image_to_yuv444_u16, yuv444_to_image, yuv444_to_image_u16, YuvMatrix,
+    YuvOptions,
+};
 use crate::ClaheGridSize;
 
-pub(crate) fn ycgco444_skip_alpha_to_rgb(
-    image_with_alpha: &YuvPlanarImageWithAlpha<u8>,
-    rgba: &mut [u8],
-    rgba_stride: u32,
-    range: YuvRange,
-) -> Result<(), YuvError> {
-    let image = YuvPlanarImage {
-        y_plane: image_with_alpha.y_plane,
-        y_stride: image_with_alpha.y_stride,
-        u_plane: image_with_alpha.u_plane,
-        u_stride: image_with_alpha.u_stride,
-        v_plane: image_with_alpha.v_plane,
-        v_stride: image_with_alpha.v_stride,
-        width: image_with_alpha.width,
-        height: image_with_alpha.height,
-    };
-    ycgco444_to_rgb(&image, rgba, rgba_stride, range)
-}
-
 /// Converts image to YUV, performs CLAHE and reverts back into RGB.
 ///
 /// For optimization purposes YUV histogram bins always 256
@@ -36,10 +20,11 @@ pub(crate) fn ycgco444_skip_alpha_to_rgb(
 /// * `threshold` - Level of clipping histogram ~[0, 10]
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_yuv_rgb(
     src: &[u8],
     src_stride: u32,
@@ -49,8 +34,51 @@ pub fn clahe_yuv_rgb(
     height: u32,
     threshold: f32,
     grid_size: ClaheGridSize,
-) {
-    clahe_yuv_impl::<3, { AheImplementation::Clahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Rgb as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts image to YUV, performs CLAHE and reverts back into BGR.
+///
+/// For optimization purposes YUV histogram bins always 256
+///
+/// # Arguments
+///
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_yuv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 3, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -59,9 +87,14 @@ pub fn clahe_yuv_rgb(
         height,
         threshold,
         grid_size,
-        rgb_to_ycgco444,
-        ycgco444_skip_alpha_to_rgb,
-    );
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Bgr as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
 
 /// Converts image to YUV, performs AHE and reverts back into RGB
@@ -72,10 +105,11 @@ pub fn clahe_yuv_rgb(
 ///
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_yuv_rgb(
     src: &[u8],
     src_stride: u32,
@@ -84,8 +118,49 @@ pub fn ahe_yuv_rgb(
     width: u32,
     height: u32,
     grid_size: ClaheGridSize,
-) {
-    clahe_yuv_impl::<3, { AheImplementation::Ahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Rgb as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts image to YUV, performs AHE and reverts back into BGR
+///
+/// For optimization purposes YUV histogram bins always 256
+///
+/// # Arguments
+///
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_yuv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 3, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -94,9 +169,14 @@ pub fn ahe_yuv_rgb(
         height,
         0f32,
         grid_size,
-        rgb_to_ycgco444,
-        ycgco444_skip_alpha_to_rgb,
-    );
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Bgr as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
 
 /// Converts image to YUV, performs CLAHE and reverts back into RGBA
@@ -108,10 +188,11 @@ pub fn ahe_yuv_rgb(
 /// * `threshold` - Level of clipping histogram ~[0, 10]
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_yuv_rgba(
     src: &[u8],
     src_stride: u32,
@@ -121,8 +202,8 @@ pub fn clahe_yuv_rgba(
     height: u32,
     threshold: f32,
     grid_size: ClaheGridSize,
-) {
-    clahe_yuv_impl::<4, { AheImplementation::Clahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 4, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -131,9 +212,14 @@ pub fn clahe_yuv_rgba(
         height,
         threshold,
         grid_size,
-        rgba_to_ycgco444,
-        ycgco444_alpha_to_rgba,
-    );
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Rgba as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
 
 /// Converts image to YUV, performs AHE and reverts back into RGB
@@ -144,10 +230,11 @@ pub fn clahe_yuv_rgba(
 ///
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_yuv_rgba(
     src: &[u8],
     src_stride: u32,
@@ -156,8 +243,8 @@ pub fn ahe_yuv_rgba(
     width: u32,
     height: u32,
     grid_size: ClaheGridSize,
-) {
-    clahe_yuv_impl::<4, { AheImplementation::Ahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 4, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -166,9 +253,14 @@ pub fn ahe_yuv_rgba(
         height,
         0f32,
         grid_size,
-        rgba_to_ycgco444,
-        ycgco444_alpha_to_rgba,
-    );
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Rgba as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
 
 /// Converts image to YUV, performs CLAHE and reverts back into BGRA
@@ -180,10 +272,11 @@ pub fn ahe_yuv_rgba(
 /// * `threshold` - Level of clipping histogram ~[0, 10]
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn clahe_yuv_bgra(
     src: &[u8],
     src_stride: u32,
@@ -193,8 +286,8 @@ pub fn clahe_yuv_bgra(
     height: u32,
     threshold: f32,
     grid_size: ClaheGridSize,
-) {
-    clahe_yuv_impl::<4, { AheImplementation::Clahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 4, { AheImplementation::Clahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -203,9 +296,14 @@ pub fn clahe_yuv_bgra(
         height,
         threshold,
         grid_size,
-        bgra_to_ycgco444,
-        ycgco444_alpha_to_bgra,
-    );
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Bgra as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
 
 /// Converts image to YUV, performs AHE and reverts back into RGB. For optimization purposes YUV histogram bins always 256
@@ -216,10 +314,11 @@ pub fn clahe_yuv_bgra(
 ///
 /// * `grid_size` - Grid for constructing histograms - default is (8,8)
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn ahe_yuv_bgra(
     src: &[u8],
     src_stride: u32,
@@ -228,8 +327,436 @@ pub fn ahe_yuv_bgra(
     width: u32,
     height: u32,
     grid_size: ClaheGridSize,
-) {
-    clahe_yuv_impl::<4, { AheImplementation::Ahe as u8 }>(
+) -> Result<(), HistogramError> {
+    clahe_yuv_impl::<u8, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Bgra as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth RGB image to YUV, performs CLAHE and reverts back into RGB.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_yuv_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Rgb as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth BGR image to YUV, performs CLAHE and reverts back into BGR.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_yuv_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 3, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Bgr as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth RGBA image to YUV, performs CLAHE and reverts back into RGBA.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_yuv_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Rgba as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth BGRA image to YUV, performs CLAHE and reverts back into BGRA.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn clahe_yuv_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 4, { AheImplementation::Clahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Bgra as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth RGB image to YUV, performs AHE and reverts back into RGB.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_yuv_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Rgb as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Rgb as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth BGR image to YUV, performs AHE and reverts back into BGR.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_yuv_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 3, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Bgr as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Bgr as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth RGBA image to YUV, performs AHE and reverts back into RGBA.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_yuv_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 4, { AheImplementation::Ahe as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Rgba as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Rgba as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth BGRA image to YUV, performs AHE and reverts back into BGRA.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn ahe_yuv_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    clahe_yuv_impl::<u16, 4, { AheImplementation::Ahe as u8 }>(
         src,
         src_stride,
         dst,
@@ -238,7 +765,16 @@ pub fn ahe_yuv_bgra(
         height,
         0f32,
         grid_size,
-        bgra_to_ycgco444,
-        ycgco444_alpha_to_bgra,
-    );
+        ClaheOptions::default(),
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Bgra as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Bgra as u8 }>,
+        None,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
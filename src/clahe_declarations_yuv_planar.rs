@@ -0,0 +1,315 @@
+use yuv::{YuvBiPlanarImageMut, YuvChromaSubsampling, YuvPlanarImageMut, YuvRange};
+
+use crate::clahe_tiles::TileScratch;
+use crate::error::{check_yuv_bit_depth, HistogramError};
+use crate::hist_support::ClaheOptions;
+use crate::yuv_planar_impl::{equalize_yuv_biplanar_impl, equalize_yuv_planar_impl};
+use crate::{ClaheGridSize, EqualizationMethod};
+
+/// Performs CLAHE of luma plane of planar (I420, I422, I444) YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_yuv_planar(
+    image: &mut YuvPlanarImageMut<u8>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    equalize_yuv_planar_impl(
+        image,
+        subsampling,
+        range,
+        8,
+        EqualizationMethod::Clahe,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
+
+/// Performs AHE of luma plane of planar (I420, I422, I444) YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_yuv_planar(
+    image: &mut YuvPlanarImageMut<u8>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    equalize_yuv_planar_impl(
+        image,
+        subsampling,
+        range,
+        8,
+        EqualizationMethod::Ahe,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
+
+/// Performs CLAHE of luma plane of bi-planar (NV12, NV16, NV24) YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_yuv_biplanar(
+    image: &mut YuvBiPlanarImageMut<u8>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    equalize_yuv_biplanar_impl(
+        image,
+        subsampling,
+        range,
+        8,
+        EqualizationMethod::Clahe,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
+
+/// Performs AHE of luma plane of bi-planar (NV12, NV16, NV24) YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_yuv_biplanar(
+    image: &mut YuvBiPlanarImageMut<u8>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    equalize_yuv_biplanar_impl(
+        image,
+        subsampling,
+        range,
+        8,
+        EqualizationMethod::Ahe,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
+
+/// Performs CLAHE of luma plane of high bit depth planar (I010, I012, I410, I412) YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_yuv_planar_u16(
+    image: &mut YuvPlanarImageMut<u16>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, None)?;
+    equalize_yuv_planar_impl(
+        image,
+        subsampling,
+        range,
+        bit_depth,
+        EqualizationMethod::Clahe,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
+
+/// Performs AHE of luma plane of high bit depth planar (I010, I012, I410, I412) YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_yuv_planar_u16(
+    image: &mut YuvPlanarImageMut<u16>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, None)?;
+    equalize_yuv_planar_impl(
+        image,
+        subsampling,
+        range,
+        bit_depth,
+        EqualizationMethod::Ahe,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
+
+/// Performs CLAHE of luma plane of high bit depth bi-planar YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+/// * `threshold` - Level of clipping histogram ~[0, 10]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn clahe_yuv_biplanar_u16(
+    image: &mut YuvBiPlanarImageMut<u16>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    bit_depth: u32,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, None)?;
+    equalize_yuv_biplanar_impl(
+        image,
+        subsampling,
+        range,
+        bit_depth,
+        EqualizationMethod::Clahe,
+        threshold,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
+
+/// Performs AHE of luma plane of high bit depth bi-planar YUV frame in place,
+/// chroma is left untouched
+///
+/// Histogram has a bin for every luma level of `range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+/// * `range` - Range of the frame, with limited range histograms span only its luma levels
+/// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+/// * `grid_size` - Grid for constructing histograms - default is (8,8)
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+/// width, height, strides and subsampling, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn ahe_yuv_biplanar_u16(
+    image: &mut YuvBiPlanarImageMut<u16>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    bit_depth: u32,
+    grid_size: ClaheGridSize,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, None)?;
+    equalize_yuv_biplanar_impl(
+        image,
+        subsampling,
+        range,
+        bit_depth,
+        EqualizationMethod::Ahe,
+        0f32,
+        grid_size,
+        ClaheOptions::default(),
+        None,
+        &mut TileScratch::default(),
+    )
+}
@@ -0,0 +1,197 @@
+use crate::clahe_opencv::clahe_opencv_in_place;
+use crate::clahe_sliding::clahe_sliding_in_place;
+use crate::clahe_tiles::{interpolate_row, make_tile_luts, TileScratch};
+use crate::dispatch::{blerp_block_handler, HistogramSample};
+use crate::error::{
+    check_bins_count, check_destination, check_grid_size, check_image_size, check_source,
+    HistogramError,
+};
+use crate::hist_support::{AheImplementation, ClaheMode, ClaheOptions};
+use crate::mask::HistogramMask;
+use crate::video_equalizer::LutHistory;
+use crate::ClaheGridSize;
+use num_traits::{AsPrimitive, FromPrimitive};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
+
+#[allow(dead_code)]
+pub(crate) fn clahe_gray_impl<
+    T: Copy
+        + Ord
+        + FromPrimitive
+        + Default
+        + HistogramSample
+        + AsPrimitive<f32>
+        + Send
+        + Sync
+        + 'static,
+    const IMPLEMENTATION: u8,
+>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    threshold: f32,
+    clahe_grid_size: ClaheGridSize,
+    clahe_options: ClaheOptions,
+    bins_count: usize,
+) -> Result<(), HistogramError>
+where
+    f32: AsPrimitive<T>,
+    u64: AsPrimitive<T>,
+{
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, 1)?;
+    check_destination(dst, dst_stride, width, height, 1)?;
+    check_grid_size(clahe_grid_size)?;
+    check_bins_count(bins_count)?;
+
+    for (dst, src) in dst
+        .chunks_mut(dst_stride as usize)
+        .zip(src.chunks(src_stride as usize))
+        .take(height as usize)
+    {
+        dst[..width as usize].copy_from_slice(&src[..width as usize]);
+    }
+
+    clahe_gray_in_place::<T, IMPLEMENTATION>(
+        dst,
+        dst_stride as usize,
+        width as usize,
+        height as usize,
+        threshold,
+        clahe_grid_size,
+        clahe_options,
+        bins_count,
+        None,
+        None,
+        &mut TileScratch::default(),
+    );
+
+    Ok(())
+}
+
+/// Performs AHE or CLAHE of single channel `plane` in place, sizes must be already checked.
+///
+/// Histograms count only pixels of `mask` when it is set, except [ClaheMode::OpenCv]
+/// which doesn't support masks. Tile LUTs are blended with `history` of previous frames
+/// when it is set, only [ClaheMode::Native] has tile LUTs. Buffers of every mode are kept
+/// in `scratch`.
+pub(crate) fn clahe_gray_in_place<
+    T: Copy
+        + Ord
+        + FromPrimitive
+        + Default
+        + HistogramSample
+        + AsPrimitive<f32>
+        + Send
+        + Sync
+        + 'static,
+    const IMPLEMENTATION: u8,
+>(
+    plane: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+    threshold: f32,
+    clahe_grid_size: ClaheGridSize,
+    clahe_options: ClaheOptions,
+    bins_count: usize,
+    mask: Option<HistogramMask<'_>>,
+    history: Option<&mut LutHistory>,
+    scratch: &mut TileScratch<T>,
+) where
+    f32: AsPrimitive<T>,
+    u64: AsPrimitive<T>,
+{
+    let implementation: AheImplementation = IMPLEMENTATION.into();
+
+    if clahe_options.mode == ClaheMode::OpenCv {
+        let threshold = if implementation == AheImplementation::Clahe {
+            threshold
+        } else {
+            0f32
+        };
+        clahe_opencv_in_place::<T, 0, 1>(
+            plane,
+            stride,
+            width,
+            height,
+            threshold,
+            clahe_grid_size,
+            bins_count,
+            &mut scratch.opencv,
+        );
+        return;
+    }
+
+    if clahe_options.mode == ClaheMode::SlidingWindow {
+        clahe_sliding_in_place::<T, 0, 1>(
+            plane,
+            stride,
+            width,
+            height,
+            implementation,
+            threshold,
+            clahe_grid_size,
+            bins_count,
+            clahe_options.clip_redistribution,
+            mask,
+            &mut scratch.sliding,
+        );
+        return;
+    }
+
+    let TileScratch {
+        tiles,
+        luts,
+        histograms,
+        column_weights,
+        ..
+    } = scratch;
+    tiles.reset(width as u32, height as u32, clahe_grid_size);
+    make_tile_luts::<0, 1, T>(
+        plane,
+        stride as u32,
+        tiles,
+        bins_count,
+        implementation,
+        threshold,
+        clahe_options.clip_redistribution,
+        mask,
+        histograms,
+        luts,
+    );
+
+    let max_bins = bins_count - 1;
+    if let Some(history) = history {
+        history.smooth(luts.luts_mut(), max_bins);
+    }
+
+    tiles.column_weights(bins_count, column_weights);
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = plane.par_chunks_mut(stride).take(height).enumerate();
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = plane.chunks_mut(stride).take(height).enumerate();
+    }
+    let blerp = blerp_block_handler();
+    iter.for_each(|(y, row)| {
+        interpolate_row::<T, 0, 1>(
+            &mut row[..width],
+            luts,
+            column_weights,
+            tiles.vertical_weight(y as u32),
+            max_bins,
+            blerp,
+        );
+    });
+}
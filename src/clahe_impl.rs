@@ -1,8 +1,20 @@
-use crate::hist_support::{
-    blerp, cdf, clip_hist_clahe, make_histogram_region, minmax, AheImplementation, ClaheGridSize,
-    ImageHistogram,
+use crate::clahe_opencv::clahe_opencv_in_place;
+use crate::clahe_sliding::clahe_sliding_in_place;
+use crate::clahe_tiles::{interpolate_row, make_tile_luts, TileScratch};
+use crate::context::{working_buffer, EqualizerContext};
+use crate::dispatch::blerp_block_handler;
+use crate::error::{
+    check_bins_count, check_destination, check_grid_size, check_image_size, check_source,
+    HistogramError,
 };
+use crate::hist_support::{AheImplementation, ClaheGridSize, ClaheMode, ClaheOptions};
+use crate::mask::HistogramMask;
+use crate::video_equalizer::LutHistory;
 use colorutils_rs::{BufferStore, ColorError, ImageBuffer, ImageBufferMut};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
 
 #[allow(dead_code)]
 pub(crate) fn clahe_impl_u16<
@@ -18,6 +30,7 @@ pub(crate) fn clahe_impl_u16<
     height: u32,
     threshold: f32,
     clahe_grid_size: ClaheGridSize,
+    clahe_options: ClaheOptions,
     bins_count: usize,
     destructuring: fn(
         &ImageBuffer<'_, u8>,
@@ -29,20 +42,23 @@ pub(crate) fn clahe_impl_u16<
         &mut ImageBufferMut<'_, u8>,
         f32,
     ) -> Result<(), ColorError>,
-) {
-    if bins_count <= 1 {
-        panic!("Bins count must be more than one");
-    }
-    if clahe_grid_size.w == 0 || clahe_grid_size.h == 0 {
-        panic!("zero sized grid is not accepted");
-    }
+    mask: Option<HistogramMask<'_>>,
+    history: Option<&mut LutHistory>,
+    context: &mut EqualizerContext,
+) -> Result<(), HistogramError> {
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, CHANNELS)?;
+    check_destination(dst, dst_stride, width, height, CHANNELS)?;
+    check_grid_size(clahe_grid_size)?;
+    check_bins_count(bins_count)?;
     let implementation: AheImplementation = IMPLEMENTATION.into();
-    let horizontal_tile_size = width / clahe_grid_size.w;
-    let vertical_tile_size = height / clahe_grid_size.h;
-    let tiles_horizontal = width / horizontal_tile_size;
-    let tiles_vertical = height / vertical_tile_size;
 
-    let mut hsv_image: Vec<u16> = vec![0u16; width as usize * height as usize * CHANNELS];
+    let EqualizerContext {
+        image,
+        tiles: scratch,
+        ..
+    } = context;
+    let hsv_image = working_buffer(image, width as usize * height as usize * CHANNELS);
     let hsv_stride = width as usize * CHANNELS;
 
     let src_image = ImageBuffer {
@@ -53,120 +69,94 @@ pub(crate) fn clahe_impl_u16<
         channels: CHANNELS as u32,
     };
     let mut dst_image_hsv = ImageBufferMut {
-        data: BufferStore::Borrowed(&mut hsv_image),
+        data: BufferStore::Borrowed(hsv_image),
         stride: hsv_stride as u32,
         width,
         height,
-        channels: 3,
+        channels: CHANNELS as u32,
     };
 
-    _ = destructuring(&src_image, &mut dst_image_hsv, (bins_count - 1) as f32);
-
-    let mut histograms: Vec<Vec<ImageHistogram>> = vec![];
-
-    let max_bins = bins_count - 1;
-
-    for h in 0..tiles_vertical {
-        let mut regions_hist: Vec<ImageHistogram> = vec![];
-        for w in 0..tiles_horizontal {
-            let start_x = w * horizontal_tile_size;
-            let start_y = h * vertical_tile_size;
-            let mut end_x = (w + 1) * horizontal_tile_size;
-            if w + 1 == tiles_horizontal {
-                end_x = width;
-            }
-            let mut end_y = (h + 1) * vertical_tile_size;
-            if h + 1 == tiles_vertical {
-                end_y = height;
-            }
-
-            let mut region_hist = make_histogram_region::<CHANNEL_POSITION, CHANNELS, u16>(
-                &hsv_image,
-                hsv_stride as u32,
-                start_x,
-                end_x,
-                start_y,
-                end_y,
-                bins_count,
-            );
-
-            let mut bins = region_hist.bins;
-            if implementation == AheImplementation::Clahe {
-                clip_hist_clahe(
-                    &mut bins,
-                    threshold,
-                    (end_x - start_x) as usize,
-                    (end_y - start_y) as usize,
-                );
-            }
-            cdf(&mut bins);
-
-            let (min_bin, _) = minmax(&bins);
-
-            let distance_r =
-                1f64 / ((end_y - start_y) as f64 * (end_x - start_x) as f64 - min_bin as f64);
-
-            if distance_r != 0f64 {
-                for i in 0..bins_count {
-                    unsafe {
-                        *bins.get_unchecked_mut(i) = (max_bins as f64
-                            * (*bins.get_unchecked(i) as f64 - min_bin as f64)
-                            * distance_r)
-                            .round()
-                            .min(max_bins as f64)
-                            .max(0f64) as u64;
-                    }
-                }
-            }
-
-            region_hist.bins = bins;
-
-            regions_hist.push(region_hist);
+    destructuring(&src_image, &mut dst_image_hsv, (bins_count - 1) as f32)?;
+
+    if clahe_options.mode == ClaheMode::OpenCv {
+        let threshold = if implementation == AheImplementation::Clahe {
+            threshold
+        } else {
+            0f32
+        };
+        clahe_opencv_in_place::<u16, CHANNEL_POSITION, CHANNELS>(
+            hsv_image,
+            hsv_stride,
+            width as usize,
+            height as usize,
+            threshold,
+            clahe_grid_size,
+            bins_count,
+            &mut scratch.opencv,
+        );
+    } else if clahe_options.mode == ClaheMode::SlidingWindow {
+        clahe_sliding_in_place::<u16, CHANNEL_POSITION, CHANNELS>(
+            hsv_image,
+            hsv_stride,
+            width as usize,
+            height as usize,
+            implementation,
+            threshold,
+            clahe_grid_size,
+            bins_count,
+            clahe_options.clip_redistribution,
+            mask,
+            &mut scratch.sliding,
+        );
+    } else {
+        let TileScratch {
+            tiles,
+            luts,
+            histograms,
+            column_weights,
+            ..
+        } = scratch;
+        tiles.reset(width, height, clahe_grid_size);
+        make_tile_luts::<CHANNEL_POSITION, CHANNELS, u16>(
+            hsv_image,
+            hsv_stride as u32,
+            tiles,
+            bins_count,
+            implementation,
+            threshold,
+            clahe_options.clip_redistribution,
+            mask,
+            histograms,
+            luts,
+        );
+
+        let max_bins = bins_count - 1;
+        if let Some(history) = history {
+            history.smooth(luts.luts_mut(), max_bins);
         }
-        histograms.push(regions_hist);
-    }
-
-    let mut hsv_offset = 0usize;
-
-    let max_bins = bins_count - 1;
-
-    for y in 0usize..height as usize {
-        for x in 0usize..width as usize {
-            let c_x_f =
-                (x as f32 - horizontal_tile_size as f32 / 2f32) / horizontal_tile_size as f32;
-            let r_y_f = (y as f32 - vertical_tile_size as f32 / 2f32) / vertical_tile_size as f32;
-
-            let x1 = (x as f32 - ((c_x_f as i64) as f32 + 0.5f32) * horizontal_tile_size as f32)
-                / horizontal_tile_size as f32;
-            let y1 = (y as f32 - ((r_y_f as i64) as f32 + 0.5f32) * vertical_tile_size as f32)
-                / vertical_tile_size as f32;
-
-            let px = x * CHANNELS;
 
-            let value = unsafe { *hsv_image.get_unchecked(hsv_offset + px + CHANNEL_POSITION) }
-                .min(max_bins as u16) as usize;
+        tiles.column_weights(bins_count, column_weights);
 
-            let r_y = r_y_f.max(0f32) as i64;
-            let c_x = c_x_f.max(0f32) as i64;
-
-            let r = (r_y as usize).min(tiles_vertical as usize - 1usize);
-            let c = (c_x as usize).min(tiles_horizontal as usize - 1usize);
-            let bin1 = histograms[r][c].bins[value] as f32;
-            let bin2 =
-                histograms[r][(c + 1).min(tiles_horizontal as usize - 1usize)].bins[value] as f32;
-            let bin3 =
-                histograms[(r + 1).min(tiles_vertical as usize - 1usize)][c].bins[value] as f32;
-            let bin4 = histograms[(r + 1).min(tiles_vertical as usize - 1usize)]
-                [(c + 1).min(tiles_horizontal as usize - 1usize)]
-            .bins[value] as f32;
-            let interpolated = blerp(bin1, bin2, bin3, bin4, x1, y1);
-            unsafe {
-                *hsv_image.get_unchecked_mut(hsv_offset + px + CHANNEL_POSITION) =
-                    interpolated.min(max_bins as f32).max(0f32) as u16;
-            }
+        let iter;
+        #[cfg(feature = "rayon")]
+        {
+            iter = hsv_image.par_chunks_exact_mut(hsv_stride).enumerate();
         }
-
-        hsv_offset += hsv_stride;
+        #[cfg(not(feature = "rayon"))]
+        {
+            iter = hsv_image.chunks_exact_mut(hsv_stride).enumerate();
+        }
+        let blerp = blerp_block_handler();
+        iter.for_each(|(y, hsv_row)| {
+            interpolate_row::<u16, CHANNEL_POSITION, CHANNELS>(
+                hsv_row,
+                luts,
+                column_weights,
+                tiles.vertical_weight(y as u32),
+                max_bins,
+                blerp,
+            );
+        });
     }
 
     let mut dst_image = ImageBufferMut {
@@ -177,12 +167,14 @@ pub(crate) fn clahe_impl_u16<
         channels: CHANNELS as u32,
     };
     let src_image_hsv = ImageBuffer {
-        data: std::borrow::Cow::Borrowed(&hsv_image),
+        data: std::borrow::Cow::Borrowed(hsv_image),
         stride: hsv_stride as u32,
         width,
         height,
-        channels: 3,
+        channels: CHANNELS as u32,
     };
 
-    _ = structuring(&src_image_hsv, &mut dst_image, (bins_count - 1) as f32);
+    structuring(&src_image_hsv, &mut dst_image, (bins_count - 1) as f32)?;
+
+    Ok(())
 }
@@ -0,0 +1,206 @@
+use crate::context::working_buffer;
+use crate::hist_support::ClaheGridSize;
+use num_traits::AsPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
+
+/// Buffers of OpenCV compatible CLAHE kept between calls: tile LUTs, histogram of the tile
+/// being counted and interpolation of every column
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OpenCvScratch {
+    luts: Vec<f32>,
+    bins: Vec<u32>,
+    x_offsets: Vec<(usize, usize)>,
+    x_weights: Vec<(f32, f32)>,
+}
+
+/// Index of BORDER_REFLECT_101 padding: `gfedcb|abcdefgh|gfedcba`
+#[inline]
+fn reflect_101(index: usize, len: usize) -> usize {
+    if len == 1 {
+        return 0;
+    }
+    let period = 2 * (len - 1);
+    let index = index % period;
+    if index >= len {
+        period - index
+    } else {
+        index
+    }
+}
+
+/// OpenCV `cvRound` of a float, ties are rounded to even
+#[inline]
+fn cv_round(value: f32) -> f32 {
+    value.round_ties_even()
+}
+
+/// OpenCV CLAHE clipping: excess is added in a single batch and the residual is spread
+/// with a constant step starting from the first bin
+fn clip_hist_opencv(bins: &mut [u32], clip_limit: u32) {
+    let mut clipped = 0u32;
+    for bin in bins.iter_mut() {
+        if *bin > clip_limit {
+            clipped += *bin - clip_limit;
+            *bin = clip_limit;
+        }
+    }
+
+    let hist_size = bins.len() as u32;
+    let redist_batch = clipped / hist_size;
+    let mut residual = clipped - redist_batch * hist_size;
+
+    for bin in bins.iter_mut() {
+        *bin += redist_batch;
+    }
+
+    if let Some(residual_step) = hist_size.checked_div(residual) {
+        let residual_step = residual_step.max(1) as usize;
+        let mut i = 0usize;
+        while i < bins.len() && residual > 0 {
+            bins[i] += 1;
+            i += residual_step;
+            residual -= 1;
+        }
+    }
+}
+
+/// Performs CLAHE in place on the `CHANNEL` of interleaved `plane` with the same semantics
+/// as `cv::CLAHE::apply`.
+///
+/// When image size is not a multiple of the grid, tile histograms are taken from the image
+/// padded at right and bottom by `BORDER_REFLECT_101` exactly as OpenCV does, clip limit is
+/// `max(threshold * tile_area / bins_count, 1)` and disabled when `threshold <= 0`,
+/// LUT is scaled by `(bins_count - 1) / tile_area` and interpolated between tile centers.
+pub(crate) fn clahe_opencv_in_place<
+    T: Copy + Into<usize> + Send + Sync + 'static,
+    const CHANNEL: usize,
+    const CHANNELS: usize,
+>(
+    plane: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    scratch: &mut OpenCvScratch,
+) where
+    f32: AsPrimitive<T>,
+{
+    let tiles_x = grid_size.w as usize;
+    let tiles_y = grid_size.h as usize;
+
+    let (padded_width, padded_height) =
+        if width.is_multiple_of(tiles_x) && height.is_multiple_of(tiles_y) {
+            (width, height)
+        } else {
+            (
+                width + tiles_x - width % tiles_x,
+                height + tiles_y - height % tiles_y,
+            )
+        };
+
+    let tile_width = padded_width / tiles_x;
+    let tile_height = padded_height / tiles_y;
+    let tile_area = tile_width * tile_height;
+
+    let max_value = (bins_count - 1) as f32;
+    let lut_scale = (bins_count - 1) as f32 / tile_area as f32;
+
+    let clip_limit = if threshold > 0f32 {
+        Some(((threshold as f64 * tile_area as f64 / bins_count as f64) as u32).max(1))
+    } else {
+        None
+    };
+
+    let OpenCvScratch {
+        luts,
+        bins,
+        x_offsets,
+        x_weights,
+    } = scratch;
+    let luts = working_buffer(luts, tiles_x * tiles_y * bins_count);
+    let bins = working_buffer(bins, bins_count);
+
+    for (tile, lut) in luts.chunks_exact_mut(bins_count).enumerate() {
+        let tx = tile % tiles_x;
+        let ty = tile / tiles_x;
+
+        bins.fill(0);
+        for y in ty * tile_height..(ty + 1) * tile_height {
+            let row = reflect_101(y, height) * stride;
+            for x in tx * tile_width..(tx + 1) * tile_width {
+                let px = row + reflect_101(x, width) * CHANNELS + CHANNEL;
+                let value: usize = plane[px].into();
+                bins[value.min(bins_count - 1)] += 1;
+            }
+        }
+
+        if let Some(clip_limit) = clip_limit {
+            clip_hist_opencv(bins, clip_limit);
+        }
+
+        let mut sum = 0u32;
+        for (dst, &count) in lut.iter_mut().zip(bins.iter()) {
+            sum += count;
+            *dst = cv_round(sum as f32 * lut_scale).min(max_value).max(0f32);
+        }
+    }
+
+    let inv_tw = 1f32 / tile_width as f32;
+    let inv_th = 1f32 / tile_height as f32;
+
+    let x_offsets = working_buffer(x_offsets, width);
+    let x_weights = working_buffer(x_weights, width);
+    for (x, (offsets, weights)) in x_offsets.iter_mut().zip(x_weights.iter_mut()).enumerate() {
+        let txf = x as f32 * inv_tw - 0.5f32;
+        let tx1 = txf.floor() as i64;
+        let tx2 = tx1 + 1;
+        let xa = txf - tx1 as f32;
+        let xa1 = 1f32 - xa;
+        let tx1 = tx1.max(0) as usize;
+        let tx2 = (tx2.min(tiles_x as i64 - 1)) as usize;
+        *offsets = (tx1 * bins_count, tx2 * bins_count);
+        *weights = (xa, xa1);
+    }
+
+    let (luts, x_offsets, x_weights) = (&luts[..], &x_offsets[..], &x_weights[..]);
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = plane.par_chunks_mut(stride).take(height).enumerate();
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = plane.chunks_mut(stride).take(height).enumerate();
+    }
+    iter.for_each(|(y, row)| {
+        let tyf = y as f32 * inv_th - 0.5f32;
+        let ty1 = tyf.floor() as i64;
+        let ty2 = ty1 + 1;
+        let ya = tyf - ty1 as f32;
+        let ya1 = 1f32 - ya;
+        let ty1 = ty1.max(0) as usize;
+        let ty2 = (ty2.min(tiles_y as i64 - 1)) as usize;
+
+        let lut_plane1 = &luts[ty1 * tiles_x * bins_count..(ty1 + 1) * tiles_x * bins_count];
+        let lut_plane2 = &luts[ty2 * tiles_x * bins_count..(ty2 + 1) * tiles_x * bins_count];
+
+        for ((px, &(ind1, ind2)), &(xa, xa1)) in row
+            .chunks_exact_mut(CHANNELS)
+            .take(width)
+            .zip(x_offsets.iter())
+            .zip(x_weights.iter())
+        {
+            let value: usize = px[CHANNEL].into();
+            let value = value.min(bins_count - 1);
+            let res = (lut_plane1[ind1 + value] * xa1 + lut_plane1[ind2 + value] * xa) * ya1
+                + (lut_plane2[ind1 + value] * xa1 + lut_plane2[ind2 + value] * xa) * ya;
+            px[CHANNEL] = cv_round(res).min(max_value).max(0f32).as_();
+        }
+    });
+}
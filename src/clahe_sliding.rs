@@ -0,0 +1,569 @@
+use crate::hist_support::{
+    clip_hist_clahe, clip_limit, AheImplementation, ClaheGridSize, ClipRedistribution,
+};
+use crate::mask::HistogramMask;
+use num_traits::AsPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
+
+/// Buffers of sliding window equalization kept between calls: copy of the source and
+/// buffers of every band of rows
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SlidingScratch<T> {
+    source: Vec<T>,
+    bands: Vec<BandScratch>,
+}
+
+/// Window histogram of a band of rows and the copy clipped by `clip_hist_clahe`
+#[derive(Debug, Clone, Default)]
+struct BandScratch {
+    window: WindowHistogram,
+    clipped: Vec<u64>,
+}
+
+/// Half of the tile size of the grid along an axis, grid larger than the image is reduced
+/// to one pixel per tile as in tiled modes
+#[inline]
+fn window_radius(size: usize, grid: u32) -> usize {
+    let tiles = (grid as usize).min(size).max(1);
+    size / tiles / 2
+}
+
+/// Level trees of every block are kept while they take at most this many entries,
+/// larger clip limits clip a copy of the window histogram
+const MAX_LEVEL_ENTRIES: usize = 1 << 20;
+
+/// Window histogram with counts kept up to date on every change of a bin, so the rank
+/// of the centre pixel doesn't need a pass over the bins.
+///
+/// Bins are grouped into blocks of about square root of bins count, ranks of a value far
+/// from the previous one are summed from block counts and the bins of a single block.
+#[derive(Debug, Clone, Default)]
+struct WindowHistogram {
+    bins: Vec<u32>,
+    /// Log2 of bins in a block
+    block_shift: u32,
+    /// Samples in every block
+    blocks: Vec<u32>,
+    /// Samples in every block with bins clipped to `limit`
+    clipped_blocks: Vec<u32>,
+    /// Count of bins at each clipped count in `[0, limit]`, kept only while `track_levels`
+    levels: Vec<u32>,
+    /// Fenwick tree over clipped counts for every block: count of bins at each clipped count
+    level_counts: Vec<u32>,
+    /// Same as `level_counts` for the sum of clipped counts
+    level_sums: Vec<u32>,
+    /// Levels are kept for iterative redistribution with clip limit below bins count,
+    /// above it clipping a copy of the histogram is cheaper
+    track_levels: bool,
+    /// Centre value the ranks are counted up to
+    value: usize,
+    /// Samples at or below `value`
+    rank: u64,
+    /// Clip limit the clipped sums are kept for, 0 when they aren't kept
+    limit: u64,
+    /// Sum of bins clipped to `limit` at or below `value`
+    clipped_rank: u64,
+    /// Samples above `limit` over all bins
+    excess: u64,
+    /// Sum of the whole bins above `limit`, what uniform redistribution gives back
+    clipped_bins: u64,
+}
+
+impl WindowHistogram {
+    /// Empties the window for `bins_count` bins
+    fn reset(&mut self, bins_count: usize) {
+        self.bins.clear();
+        self.bins.resize(bins_count, 0);
+        self.block_shift = bins_count.next_power_of_two().trailing_zeros().div_ceil(2);
+        let blocks_count = bins_count.div_ceil(1 << self.block_shift);
+        self.blocks.clear();
+        self.blocks.resize(blocks_count, 0);
+        self.clipped_blocks.clear();
+        self.clipped_blocks.resize(blocks_count, 0);
+        self.track_levels = false;
+        self.value = 0;
+        self.rank = 0;
+        self.limit = 0;
+        self.clipped_rank = 0;
+        self.excess = 0;
+        self.clipped_bins = 0;
+    }
+
+    #[inline]
+    fn add(&mut self, bin: usize) {
+        let old = self.bins[bin] as u64;
+        self.bins[bin] += 1;
+        let block = bin >> self.block_shift;
+        self.blocks[block] += 1;
+        let counted = bin <= self.value;
+        self.rank += counted as u64;
+        let limit = self.limit;
+        if limit != 0 {
+            if old < limit {
+                self.clipped_rank += counted as u64;
+                self.clipped_blocks[block] += 1;
+                if self.track_levels {
+                    self.move_level(block, old as usize, old as usize + 1);
+                }
+            } else {
+                self.excess += 1;
+                // Bin reaching above the limit is given back whole by uniform redistribution
+                self.clipped_bins += if old == limit { old + 1 } else { 1 };
+            }
+        }
+    }
+
+    #[inline]
+    fn remove(&mut self, bin: usize) {
+        let old = self.bins[bin] as u64;
+        self.bins[bin] -= 1;
+        let block = bin >> self.block_shift;
+        self.blocks[block] -= 1;
+        let counted = bin <= self.value;
+        self.rank -= counted as u64;
+        let limit = self.limit;
+        if limit != 0 {
+            if old <= limit {
+                self.clipped_rank -= counted as u64;
+                self.clipped_blocks[block] -= 1;
+                if self.track_levels {
+                    self.move_level(block, old as usize, old as usize - 1);
+                }
+            } else {
+                self.excess -= 1;
+                self.clipped_bins -= if old == limit + 1 { old } else { 1 };
+            }
+        }
+    }
+
+    #[inline]
+    fn move_level(&mut self, block: usize, from: usize, to: usize) {
+        self.levels[from] -= 1;
+        self.levels[to] += 1;
+        self.update_level(
+            block,
+            from,
+            1u32.wrapping_neg(),
+            (from as u32).wrapping_neg(),
+        );
+        self.update_level(block, to, 1, to as u32);
+    }
+
+    /// Adds wrapping `count` and `sum` at `level` of the level trees of `block`
+    #[inline]
+    fn update_level(&mut self, block: usize, level: usize, count: u32, sum: u32) {
+        let size = self.limit as usize + 1;
+        let counts = &mut self.level_counts[block * size..(block + 1) * size];
+        let sums = &mut self.level_sums[block * size..(block + 1) * size];
+        let mut i = level + 1;
+        while i <= size {
+            counts[i - 1] = counts[i - 1].wrapping_add(count);
+            sums[i - 1] = sums[i - 1].wrapping_add(sum);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Count of bins and sum of their clipped counts in `block` with clipped count at most `level`
+    #[inline]
+    fn block_levels(&self, block: usize, level: usize) -> (u64, u64) {
+        let size = self.limit as usize + 1;
+        let counts = &self.level_counts[block * size..(block + 1) * size];
+        let sums = &self.level_sums[block * size..(block + 1) * size];
+        let (mut count, mut sum) = (0u64, 0u64);
+        let mut i = level + 1;
+        while i > 0 {
+            count += counts[i - 1] as u64;
+            sum += sums[i - 1] as u64;
+            i &= i - 1;
+        }
+        (count, sum)
+    }
+
+    /// Count of bins at or below `value` and sum of their clipped counts, for bins with
+    /// clipped count at most `level`
+    fn levels_below(&self, level: u64) -> (u64, u64) {
+        let block = self.value >> self.block_shift;
+        let level = level.min(self.limit);
+        let (mut count, mut sum) = (0u64, 0u64);
+        for full_block in 0..block {
+            let (block_count, block_sum) = self.block_levels(full_block, level as usize);
+            count += block_count;
+            sum += block_sum;
+        }
+        for &v in self.bins[block << self.block_shift..=self.value].iter() {
+            let clipped = (v as u64).min(self.limit);
+            if clipped <= level {
+                count += 1;
+                sum += clipped;
+            }
+        }
+        (count, sum)
+    }
+
+    /// Starts keeping clipped sums for `limit`, recounts them when the limit changes
+    fn set_limit(&mut self, limit: u64, iterative: bool) {
+        if limit == self.limit {
+            return;
+        }
+        self.limit = limit;
+        let level_entries = self.blocks.len() * (limit as usize + 1);
+        self.track_levels =
+            iterative && limit < self.bins.len() as u64 && level_entries <= MAX_LEVEL_ENTRIES;
+        self.clipped_rank = self.bins[..=self.value]
+            .iter()
+            .map(|&v| (v as u64).min(limit))
+            .sum();
+        for (clipped, bins) in self
+            .clipped_blocks
+            .iter_mut()
+            .zip(self.bins.chunks(1 << self.block_shift))
+        {
+            *clipped = bins.iter().map(|&v| v.min(limit as u32)).sum();
+        }
+        self.excess = 0;
+        self.clipped_bins = 0;
+        for &v in self.bins.iter() {
+            let v = v as u64;
+            if v > limit {
+                self.excess += v - limit;
+                self.clipped_bins += v;
+            }
+        }
+        if self.track_levels {
+            self.levels.clear();
+            self.levels.resize(limit as usize + 1, 0);
+            self.level_counts.clear();
+            self.level_counts.resize(level_entries, 0);
+            self.level_sums.clear();
+            self.level_sums.resize(level_entries, 0);
+            for i in 0..self.bins.len() {
+                let level = (self.bins[i] as u64).min(limit) as usize;
+                self.levels[level] += 1;
+                self.update_level(i >> self.block_shift, level, 1, level as u32);
+            }
+        }
+    }
+
+    /// Moves ranks to `value`, nearby values are reached bin by bin and farther ones are
+    /// summed from blocks, so a move costs at most two blocks and the count of blocks
+    fn move_to(&mut self, value: usize) {
+        let limit = self.limit;
+        let block_size = 1usize << self.block_shift;
+        if value.abs_diff(self.value) > block_size {
+            let block = value >> self.block_shift;
+            let start = block << self.block_shift;
+            self.rank = self.blocks[..block].iter().map(|&v| v as u64).sum::<u64>()
+                + self.bins[start..=value]
+                    .iter()
+                    .map(|&v| v as u64)
+                    .sum::<u64>();
+            self.clipped_rank = if limit != 0 {
+                self.clipped_blocks[..block]
+                    .iter()
+                    .map(|&v| v as u64)
+                    .sum::<u64>()
+                    + self.bins[start..=value]
+                        .iter()
+                        .map(|&v| (v as u64).min(limit))
+                        .sum::<u64>()
+            } else {
+                0
+            };
+        } else if value > self.value {
+            for &v in self.bins[self.value + 1..=value].iter() {
+                self.rank += v as u64;
+                self.clipped_rank += (v as u64).min(limit);
+            }
+        } else {
+            for &v in self.bins[value + 1..=self.value].iter() {
+                self.rank -= v as u64;
+                self.clipped_rank -= (v as u64).min(limit);
+            }
+        }
+        self.value = value;
+    }
+
+    /// First bin and cumulative count at `value` of the histogram redistributed by
+    /// `clip_hist_iterative`, replayed on the levels without clipping a copy of the bins.
+    ///
+    /// Every round raises free bins by the same batch up to the limit, so after the rounds
+    /// a bin holds `min(limit, clipped + raised)` and free bins are those with clipped count
+    /// below `limit - raised`. Residual of the last round goes to every `step`-th free bin,
+    /// or to every `step`-th bin on top of a batch when all bins are full.
+    fn iterative_cdf(&self) -> (u64, u64) {
+        let limit = self.limit;
+        let bins_count = self.bins.len() as u64;
+        let mut excess = self.excess;
+        let mut raised = 0u64;
+        // Step and count of the residual spread over free bins
+        let mut residual: Option<(u64, u64)> = None;
+        // Batch and residual given back to all bins once they are full
+        let mut overflow: Option<(u64, u64)> = None;
+        while excess > 0 {
+            let threshold = limit.saturating_sub(raised) as usize;
+            let free_bins: u64 = self.levels[..threshold].iter().map(|&v| v as u64).sum();
+            if free_bins == 0 {
+                overflow = Some((excess / bins_count, excess % bins_count));
+                break;
+            }
+            let batch = excess / free_bins;
+            if batch == 0 {
+                residual = Some(((free_bins / excess).max(1), excess));
+                break;
+            }
+            for (level, &count) in self.levels[..threshold].iter().enumerate() {
+                excess -= count as u64 * batch.min((threshold - level) as u64);
+            }
+            raised += batch;
+        }
+
+        let first_level = (self.bins[0] as u64).min(limit);
+        let mut first = (first_level + raised).min(limit);
+        // Bins at or below `limit - raised` were raised whole, bins above it are full
+        let (raised_count, raised_sum) = self.levels_below(limit.saturating_sub(raised));
+        let mut cdf_value =
+            raised_sum + raised * raised_count + limit * (self.value as u64 + 1 - raised_count);
+
+        if let Some((step, count)) = residual {
+            let threshold = limit - raised;
+            let (free_below, _) = self.levels_below(threshold - 1);
+            cdf_value += free_below.div_ceil(step).min(count);
+            first += (first_level < threshold) as u64;
+        }
+        if let Some((batch, remainder)) = overflow {
+            let step = (bins_count / remainder.max(1)).max(1);
+            let value = self.value as u64;
+            first += batch + (remainder > 0) as u64;
+            cdf_value += (value + 1) * batch + (value / step + 1).min(remainder);
+        }
+        (first, cdf_value)
+    }
+}
+
+/// Performs AHE or CLAHE in place on the `CHANNEL` of interleaved `plane`, mapping of every
+/// pixel is taken from the histogram of the window centered on it, without interpolation.
+///
+/// Window spans `2 * radius + 1` pixels on each axis where radius is half of the grid tile
+/// size, windows are cut by image borders. Rows of a band are walked in alternating
+/// directions, the window histogram is moved along the row by adding the entering column
+/// and removing the leaving one and down to the next row by the entering and leaving rows,
+/// so it is built from scratch once per band. Rank of the centre pixel is found from blocks
+/// of bins and the excess above the clip limit is updated with every bin, so each step costs
+/// the window height and about square root of bins count.
+/// Iterative redistribution is replayed on counts of bins per clipped level, only clip
+/// limits at or above the bins count clip a copy of the window histogram.
+/// Clip limit and normalization are the same as in tiles, computed from the window area
+/// or from the count of masked pixels in the window when `mask` is set.
+pub(crate) fn clahe_sliding_in_place<
+    T: Copy + Into<usize> + Send + Sync + 'static,
+    const CHANNEL: usize,
+    const CHANNELS: usize,
+>(
+    plane: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+    implementation: AheImplementation,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    clip_redistribution: ClipRedistribution,
+    mask: Option<HistogramMask<'_>>,
+    scratch: &mut SlidingScratch<T>,
+) where
+    f32: AsPrimitive<T>,
+{
+    let SlidingScratch { source, bands } = scratch;
+    source.clear();
+    source.extend_from_slice(plane);
+    let source = &source[..];
+
+    let radius_x = window_radius(width, grid_size.w);
+    let radius_y = window_radius(height, grid_size.h);
+    let max_bins = bins_count - 1;
+    let is_clahe = implementation == AheImplementation::Clahe;
+    let iterative = clip_redistribution == ClipRedistribution::Iterative;
+
+    // Rows are split into a band per thread, each band reuses its own buffers
+    let bands_count;
+    #[cfg(feature = "rayon")]
+    {
+        bands_count = rayon::current_num_threads().clamp(1, height);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        bands_count = 1;
+    }
+    let band_rows = height.div_ceil(bands_count);
+    bands.resize_with(bands_count, BandScratch::default);
+    for band in bands.iter_mut() {
+        band.window.reset(bins_count);
+        if is_clahe && iterative {
+            band.clipped.resize(bins_count, 0);
+        }
+    }
+
+    let sample = |x: usize, y: usize| -> usize {
+        Into::<usize>::into(source[y * stride + x * CHANNELS + CHANNEL]).min(max_bins)
+    };
+    let is_counted = |x: usize, y: usize| -> bool { mask.is_none_or(|mask| mask.contains(x, y)) };
+
+    // Adds or removes masked samples of the column between `top` and `bottom`, returns their count
+    let update_column =
+        |window: &mut WindowHistogram, x: usize, top: usize, bottom: usize, add: bool| -> usize {
+            let mut count = 0usize;
+            for yy in top..=bottom {
+                if is_counted(x, yy) {
+                    if add {
+                        window.add(sample(x, yy));
+                    } else {
+                        window.remove(sample(x, yy));
+                    }
+                    count += 1;
+                }
+            }
+            count
+        };
+    // Same as `update_column` for the row between `left` and `right`
+    let update_row =
+        |window: &mut WindowHistogram, y: usize, left: usize, right: usize, add: bool| -> usize {
+            let mut count = 0usize;
+            for xx in left..=right {
+                if is_counted(xx, y) {
+                    if add {
+                        window.add(sample(xx, y));
+                    } else {
+                        window.remove(sample(xx, y));
+                    }
+                    count += 1;
+                }
+            }
+            count
+        };
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = plane
+            .par_chunks_mut(stride * band_rows)
+            .zip(bands.par_iter_mut())
+            .enumerate();
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = plane
+            .chunks_mut(stride * band_rows)
+            .zip(bands.iter_mut())
+            .enumerate();
+    }
+    iter.for_each(|(band, (band_plane, band_scratch))| {
+        let BandScratch { window, clipped } = band_scratch;
+        let first_row = band * band_rows;
+        let rows = band_rows.min(height.saturating_sub(first_row));
+        // Left, right, top and bottom of the counted window, none before the first pixel
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        let mut samples = 0usize;
+        for (i, row) in band_plane.chunks_mut(stride).take(rows).enumerate() {
+            let y = first_row + i;
+            let top = y.saturating_sub(radius_y);
+            let bottom = (y + radius_y).min(height - 1);
+            let window_height = bottom - top + 1;
+
+            for step in 0..width {
+                let x = if i % 2 == 0 { step } else { width - 1 - step };
+                let left = x.saturating_sub(radius_x);
+                let right = (x + radius_x).min(width - 1);
+
+                match bounds {
+                    None => {
+                        for yy in top..=bottom {
+                            samples += update_row(window, yy, left, right, true);
+                        }
+                    }
+                    Some((last_left, last_right, last_top, last_bottom)) => {
+                        for yy in last_bottom + 1..=bottom {
+                            samples += update_row(window, yy, last_left, last_right, true);
+                        }
+                        for yy in last_top..top {
+                            samples -= update_row(window, yy, last_left, last_right, false);
+                        }
+                        for xx in (last_right + 1..=right).chain(left..last_left) {
+                            samples += update_column(window, xx, top, bottom, true);
+                        }
+                        for xx in (last_left..left).chain(right + 1..=last_right) {
+                            samples -= update_column(window, xx, top, bottom, false);
+                        }
+                    }
+                }
+                bounds = Some((left, right, top, bottom));
+                let pixel = &mut row[x * CHANNELS..(x + 1) * CHANNELS];
+
+                // Pixels without masked neighbours keep their values
+                if samples == 0 {
+                    continue;
+                }
+
+                let window_width = right - left + 1;
+                let (clip_width, clip_height) = if mask.is_some() {
+                    (samples, 1)
+                } else {
+                    (window_width, window_height)
+                };
+                let area = samples as u64;
+                let value = sample(x, y);
+                window.move_to(value);
+
+                let (cdf_first, cdf_value) = if is_clahe {
+                    let limit = clip_limit(threshold, clip_width, clip_height, bins_count);
+                    window.set_limit(limit, iterative);
+                    match clip_redistribution {
+                        ClipRedistribution::Uniform => {
+                            let mean_excess =
+                                (window.clipped_bins as f64 / bins_count as f64) as u64;
+                            (
+                                (window.bins[0] as u64).min(limit) + mean_excess,
+                                window.clipped_rank + (value as u64 + 1) * mean_excess,
+                            )
+                        }
+                        // Nothing to redistribute, clipped histogram is the window one
+                        ClipRedistribution::Iterative if window.excess == 0 => {
+                            (window.bins[0] as u64, window.rank)
+                        }
+                        ClipRedistribution::Iterative if window.track_levels => {
+                            window.iterative_cdf()
+                        }
+                        ClipRedistribution::Iterative => {
+                            for (dst, &src) in clipped.iter_mut().zip(window.bins.iter()) {
+                                *dst = src as u64;
+                            }
+                            clip_hist_clahe(
+                                clipped,
+                                threshold,
+                                clip_width,
+                                clip_height,
+                                clip_redistribution,
+                            );
+                            (clipped[0], clipped[..=value].iter().sum::<u64>())
+                        }
+                    }
+                } else {
+                    (window.bins[0] as u64, window.rank)
+                };
+
+                let mapped = if area > cdf_first {
+                    (max_bins as f64 * (cdf_value as f64 - cdf_first as f64)
+                        / (area as f64 - cdf_first as f64))
+                        .round()
+                        .min(max_bins as f64)
+                        .max(0f64)
+                } else {
+                    0f64
+                };
+                pixel[CHANNEL] = (mapped as f32).as_();
+            }
+        }
+    });
+}
@@ -0,0 +1,423 @@
+use crate::clahe_opencv::OpenCvScratch;
+use crate::clahe_sliding::SlidingScratch;
+use crate::dispatch::{BlerpBlock, HistogramSample};
+use crate::hist_support::{
+    cdf, clip_hist_clahe, count_histogram_region, count_histogram_region_masked, minmax,
+    AheImplementation, ClaheGridSize, ClipRedistribution, HistogramScratch, HISTOGRAM_LANES,
+};
+use crate::mask::HistogramMask;
+use num_traits::AsPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::slice::ParallelSliceMut;
+
+/// Neighbouring tiles of a pixel along one axis and weight of the second one
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct TileWeight {
+    pub(crate) first: usize,
+    pub(crate) second: usize,
+    pub(crate) weight: f32,
+}
+
+/// Split of the image into CLAHE tiles shared by all cores.
+///
+/// Image is divided into balanced tiles, sizes along an axis differ at most by one pixel,
+/// so remainder is spread over the grid instead of enlarging the last tile.
+/// Grid larger than the image is reduced to one pixel per tile.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClaheTiles {
+    x_bounds: Vec<u32>,
+    y_bounds: Vec<u32>,
+}
+
+impl ClaheTiles {
+    /// Splits `width` x `height` image reusing bounds of the previous split,
+    /// `grid_size` must be non zero, see [crate::error::check_grid_size]
+    pub(crate) fn reset(&mut self, width: u32, height: u32, grid_size: ClaheGridSize) {
+        balanced_bounds(&mut self.x_bounds, width, grid_size.w);
+        balanced_bounds(&mut self.y_bounds, height, grid_size.h);
+    }
+
+    #[inline]
+    pub(crate) fn tiles_horizontal(&self) -> usize {
+        self.x_bounds.len() - 1
+    }
+
+    #[inline]
+    pub(crate) fn tiles_vertical(&self) -> usize {
+        self.y_bounds.len() - 1
+    }
+
+    /// Returns `(start_x, end_x, start_y, end_y)` of the tile
+    #[inline]
+    pub(crate) fn tile_bounds(&self, w: usize, h: usize) -> (u32, u32, u32, u32) {
+        (
+            self.x_bounds[w],
+            self.x_bounds[w + 1],
+            self.y_bounds[h],
+            self.y_bounds[h + 1],
+        )
+    }
+
+    /// Fills `weights` of every column of the image, tiles are given as offsets of their LUTs
+    /// in a row of [TileLuts], so they are resolved once per image
+    pub(crate) fn column_weights(&self, bins_count: usize, weights: &mut Vec<TileWeight>) {
+        let width = *self.x_bounds.last().unwrap();
+        weights.clear();
+        weights.extend((0..width).map(|x| {
+            let weight = axis_weight(&self.x_bounds, x);
+            TileWeight {
+                first: weight.first * bins_count,
+                second: weight.second * bins_count,
+                weight: weight.weight,
+            }
+        }));
+    }
+
+    #[inline]
+    pub(crate) fn vertical_weight(&self, y: u32) -> TileWeight {
+        axis_weight(&self.y_bounds, y)
+    }
+}
+
+/// LUTs of all tiles in one contiguous buffer, row by row of tiles.
+///
+/// Entries have the sample type since mapped values never exceed `bins_count - 1`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TileLuts<T> {
+    luts: Vec<T>,
+    row_length: usize,
+    /// Tiles without masked pixels, their LUTs are taken from the nearest masked tile
+    unmasked: Vec<bool>,
+}
+
+impl<T> TileLuts<T> {
+    /// LUTs of the `h` row of tiles, LUT of a tile starts at its column offset,
+    /// see [ClaheTiles::column_weights]
+    #[inline]
+    pub(crate) fn tiles_row(&self, h: usize) -> &[T] {
+        &self.luts[h * self.row_length..(h + 1) * self.row_length]
+    }
+
+    /// All LUTs, tile by tile
+    #[inline]
+    pub(crate) fn luts_mut(&mut self) -> &mut [T] {
+        &mut self.luts
+    }
+}
+
+/// Buffers of tiled equalization kept between calls, so steady state processing of images
+/// of the same size doesn't allocate
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TileScratch<T> {
+    pub(crate) tiles: ClaheTiles,
+    pub(crate) luts: TileLuts<T>,
+    pub(crate) histograms: HistogramScratch,
+    pub(crate) column_weights: Vec<TileWeight>,
+    pub(crate) sliding: SlidingScratch<T>,
+    pub(crate) opencv: OpenCvScratch,
+}
+
+/// Builds equalization LUT of every tile into `luts`, tiles are processed in parallel
+/// with `rayon` feature, each of them is counted into its own histogram of `histograms`.
+///
+/// Each tile histogram is clipped when `implementation` is CLAHE, accumulated and normalized
+/// by its minimum to `[0, bins_count - 1]`. With `mask` tiles count only masked pixels,
+/// tiles without them take the LUT of the nearest masked tile, so pixels at the edge of
+/// the mask aren't interpolated towards unchanged values.
+pub(crate) fn make_tile_luts<
+    const CHANNEL: usize,
+    const CHANNELS: usize,
+    T: Copy + Default + HistogramSample + Send + Sync + 'static,
+>(
+    image: &[T],
+    stride: u32,
+    tiles: &ClaheTiles,
+    bins_count: usize,
+    implementation: AheImplementation,
+    threshold: f32,
+    clip_redistribution: ClipRedistribution,
+    mask: Option<HistogramMask<'_>>,
+    histograms: &mut HistogramScratch,
+    luts: &mut TileLuts<T>,
+) where
+    u64: AsPrimitive<T>,
+{
+    let max_bins = bins_count - 1;
+    let tiles_horizontal = tiles.tiles_horizontal();
+
+    // Returns false for tiles without masked pixels
+    let make_lut = |tile: usize, lut: &mut [T], bins: &mut [u64], sub_histograms: &mut [u32]| {
+        let (start_x, end_x, start_y, end_y) =
+            tiles.tile_bounds(tile % tiles_horizontal, tile / tiles_horizontal);
+
+        let tile_width = (end_x - start_x) as usize;
+        let tile_height = (end_y - start_y) as usize;
+
+        // Masked tiles are clipped and normalized by count of masked pixels,
+        // tiles without them keep values as is until they are filled from masked ones
+        let (clip_width, clip_height) = match mask {
+            Some(mask) => {
+                count_histogram_region_masked::<CHANNEL, CHANNELS, T>(
+                    image,
+                    stride,
+                    start_x,
+                    end_x,
+                    start_y,
+                    end_y,
+                    bins,
+                    sub_histograms,
+                    mask,
+                );
+                let samples = bins.iter().sum::<u64>() as usize;
+                if samples == 0 {
+                    for (i, dst) in lut.iter_mut().enumerate() {
+                        *dst = (i as u64).as_();
+                    }
+                    return false;
+                }
+                (samples, 1)
+            }
+            None => {
+                count_histogram_region::<CHANNEL, CHANNELS, T>(
+                    image,
+                    stride,
+                    start_x,
+                    end_x,
+                    start_y,
+                    end_y,
+                    bins,
+                    sub_histograms,
+                );
+                (tile_width, tile_height)
+            }
+        };
+
+        if implementation == AheImplementation::Clahe {
+            clip_hist_clahe(
+                bins,
+                threshold,
+                clip_width,
+                clip_height,
+                clip_redistribution,
+            );
+        }
+        cdf(bins);
+
+        let (min_bin, _) = minmax(bins);
+
+        let distance_r = 1f64 / (clip_height as f64 * clip_width as f64 - min_bin as f64);
+
+        if distance_r != 0f64 {
+            for bin in bins.iter_mut() {
+                *bin = (max_bins as f64 * (*bin as f64 - min_bin as f64) * distance_r)
+                    .round()
+                    .min(max_bins as f64)
+                    .max(0f64) as u64;
+            }
+        }
+
+        for (dst, &bin) in lut.iter_mut().zip(bins.iter()) {
+            *dst = bin.min(max_bins as u64).as_();
+        }
+        true
+    };
+
+    let tiles_count = tiles_horizontal * tiles.tiles_vertical();
+    let (bins, sub_histograms) = histograms.regions(tiles_count, bins_count);
+    luts.luts.resize(tiles_count * bins_count, T::default());
+    luts.row_length = tiles_horizontal * bins_count;
+    luts.unmasked.clear();
+    luts.unmasked.resize(tiles_count, false);
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = luts
+            .luts
+            .par_chunks_exact_mut(bins_count)
+            .zip(bins.par_chunks_exact_mut(bins_count))
+            .zip(sub_histograms.par_chunks_exact_mut(HISTOGRAM_LANES * bins_count))
+            .zip(luts.unmasked.par_iter_mut())
+            .enumerate();
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = luts
+            .luts
+            .chunks_exact_mut(bins_count)
+            .zip(bins.chunks_exact_mut(bins_count))
+            .zip(sub_histograms.chunks_exact_mut(HISTOGRAM_LANES * bins_count))
+            .zip(luts.unmasked.iter_mut())
+            .enumerate();
+    }
+    iter.for_each(|(tile, (((lut, bins), sub_histograms), unmasked))| {
+        *unmasked = !make_lut(tile, lut, bins, sub_histograms);
+    });
+
+    if mask.is_some() {
+        fill_unmasked_tiles(luts, tiles_horizontal, bins_count);
+    }
+}
+
+/// Copies into every tile without masked pixels the LUT of the nearest masked tile,
+/// the first one in row order when several are at the same distance.
+/// LUTs are kept as is when no tile has masked pixels.
+fn fill_unmasked_tiles<T: Copy>(
+    luts: &mut TileLuts<T>,
+    tiles_horizontal: usize,
+    bins_count: usize,
+) {
+    let TileLuts { luts, unmasked, .. } = luts;
+    if unmasked.iter().all(|&x| x) {
+        return;
+    }
+    for tile in 0..unmasked.len() {
+        if !unmasked[tile] {
+            continue;
+        }
+        let (x, y) = (tile % tiles_horizontal, tile / tiles_horizontal);
+        let nearest = (0..unmasked.len())
+            .filter(|&source| !unmasked[source])
+            .min_by_key(|&source| {
+                let dx = (source % tiles_horizontal).abs_diff(x);
+                let dy = (source / tiles_horizontal).abs_diff(y);
+                dx * dx + dy * dy
+            })
+            .unwrap();
+        luts.copy_within(
+            nearest * bins_count..(nearest + 1) * bins_count,
+            tile * bins_count,
+        );
+    }
+}
+
+/// Pixels gathered per call of the interpolation handler
+const INTERPOLATION_BLOCK: usize = 64;
+
+/// Replaces `CHANNEL` of every pixel in `row` with bilinear interpolation between LUTs
+/// of the neighbouring tiles.
+///
+/// LUT values are gathered into blocks and interpolated by `blerp`, which is selected once
+/// by [crate::dispatch::blerp_block_handler]. `row` must contain exactly one image row.
+pub(crate) fn interpolate_row<T, const CHANNEL: usize, const CHANNELS: usize>(
+    row: &mut [T],
+    luts: &TileLuts<T>,
+    column_weights: &[TileWeight],
+    vertical: TileWeight,
+    max_bins: usize,
+    blerp: BlerpBlock,
+) where
+    T: Copy + Into<usize> + AsPrimitive<f32>,
+    f32: AsPrimitive<T>,
+{
+    let top_luts = luts.tiles_row(vertical.first);
+    let bottom_luts = luts.tiles_row(vertical.second);
+
+    let mut c00 = [0f32; INTERPOLATION_BLOCK];
+    let mut c10 = [0f32; INTERPOLATION_BLOCK];
+    let mut c01 = [0f32; INTERPOLATION_BLOCK];
+    let mut c11 = [0f32; INTERPOLATION_BLOCK];
+    let mut tx = [0f32; INTERPOLATION_BLOCK];
+    let mut interpolated = [0f32; INTERPOLATION_BLOCK];
+
+    for (pixels, weights) in row
+        .chunks_mut(INTERPOLATION_BLOCK * CHANNELS)
+        .zip(column_weights.chunks(INTERPOLATION_BLOCK))
+    {
+        let length = weights.len();
+        for (i, (pixel, column)) in pixels.chunks_exact(CHANNELS).zip(weights).enumerate() {
+            let value = Into::<usize>::into(pixel[CHANNEL]).min(max_bins);
+            c00[i] = top_luts[column.first + value].as_();
+            c10[i] = top_luts[column.second + value].as_();
+            c01[i] = bottom_luts[column.first + value].as_();
+            c11[i] = bottom_luts[column.second + value].as_();
+            tx[i] = column.weight;
+        }
+
+        blerp(
+            &c00[..length],
+            &c10[..length],
+            &c01[..length],
+            &c11[..length],
+            &tx[..length],
+            vertical.weight,
+            &mut interpolated[..length],
+        );
+
+        for (pixel, &value) in pixels.chunks_exact_mut(CHANNELS).zip(interpolated.iter()) {
+            pixel[CHANNEL] = value.min(max_bins as f32).max(0f32).as_();
+        }
+    }
+}
+
+/// Fills `bounds` so tile `i` spans `[i * size / tiles, (i + 1) * size / tiles)`
+fn balanced_bounds(bounds: &mut Vec<u32>, size: u32, tiles: u32) {
+    let tiles = tiles.min(size).max(1) as u64;
+    bounds.clear();
+    bounds.extend((0..=tiles).map(|i| (i * size as u64 / tiles) as u32));
+}
+
+/// Pixel is interpolated between centers of the nearest tile at or before it and the next one.
+/// Before the first center the first pair is used, after the last center the last tile only.
+fn axis_weight(bounds: &[u32], position: u32) -> TileWeight {
+    let tiles = bounds.len() - 1;
+    let center = |i: usize| (bounds[i] + bounds[i + 1]) as f32 / 2f32;
+    let position = position as f32;
+
+    let first = (1..tiles)
+        .take_while(|&i| center(i) <= position)
+        .last()
+        .unwrap_or(0);
+    let second = (first + 1).min(tiles - 1);
+    let weight = if second == first {
+        0f32
+    } else {
+        (position - center(first)) / (center(second) - center(first))
+    };
+    TileWeight {
+        first,
+        second,
+        weight,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_bounds(bounds: &[u32], size: u32) {
+        assert_eq!(bounds.first(), Some(&0));
+        assert_eq!(bounds.last(), Some(&size));
+        let sizes: Vec<u32> = bounds.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(sizes.iter().all(|&s| s > 0), "{bounds:?}");
+        let min = *sizes.iter().min().unwrap();
+        let max = *sizes.iter().max().unwrap();
+        assert!(max - min <= 1, "{bounds:?}");
+    }
+
+    #[test]
+    fn bounds_are_balanced() {
+        let mut bounds = Vec::new();
+        balanced_bounds(&mut bounds, 1000, 64);
+        assert_eq!(bounds.len(), 65);
+        check_bounds(&bounds, 1000);
+
+        for size in 1..200 {
+            for tiles in 1..40 {
+                balanced_bounds(&mut bounds, size, tiles);
+                check_bounds(&bounds, size);
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_above_size_are_clamped() {
+        let mut bounds = Vec::new();
+        balanced_bounds(&mut bounds, 5, 64);
+        assert_eq!(bounds, [0, 1, 2, 3, 4, 5]);
+        balanced_bounds(&mut bounds, 1, 8);
+        assert_eq!(bounds, [0, 1]);
+    }
+}
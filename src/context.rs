@@ -0,0 +1,65 @@
+use crate::clahe_tiles::TileScratch;
+
+/// Working buffers of [crate::Equalizer] reused between calls of
+/// [crate::Equalizer::apply_with_context].
+///
+/// Buffers grow to the largest image processed with the context and are kept, so after the
+/// first frame equalization of frames of the same or smaller size doesn't allocate. This holds
+/// for every color space, histogram equalization and AHE and CLAHE in every [crate::ClaheMode].
+///
+/// With `rayon` feature work queued into the pool from outside of it is allocated by rayon,
+/// calls made from inside the pool, e.g. within `rayon::ThreadPool::install`, avoid that.
+///
+/// Context isn't bound to an equalizer, size or layout, the same one may be used with
+/// different configurations.
+///
+/// # Example
+///
+/// ```
+/// use histogram_equalization::{Equalizer, EqualizerContext};
+///
+/// let (width, height) = (64u32, 64u32);
+/// let equalizer = Equalizer::new();
+/// let mut context = EqualizerContext::new();
+/// let frame = vec![127u8; width as usize * height as usize * 3];
+/// let mut dst = vec![0u8; frame.len()];
+/// for _ in 0..3 {
+///     equalizer
+///         .apply_with_context(&mut context, &frame, width * 3, &mut dst, width * 3, width, height)
+///         .unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EqualizerContext {
+    /// Interleaved image or lightness plane of color space conversion
+    pub(crate) image: Vec<u16>,
+    /// Color planes other than lightness of planar conversion
+    pub(crate) color_planes: Vec<f32>,
+    pub(crate) tiles: TileScratch<u16>,
+    pub(crate) yuv: YuvScratch<u8>,
+    /// Straight alpha copy of premultiplied source
+    pub(crate) straight: Vec<u8>,
+}
+
+impl EqualizerContext {
+    /// Creates context without buffers, they are allocated by the first call
+    pub fn new() -> Self {
+        EqualizerContext::default()
+    }
+}
+
+/// Buffers of equalization through YUV 4:4:4, planes are stored one after another
+#[derive(Debug, Clone, Default)]
+pub(crate) struct YuvScratch<T> {
+    pub(crate) planes: Vec<T>,
+    pub(crate) alpha: Vec<T>,
+    pub(crate) tiles: TileScratch<T>,
+}
+
+/// Resizes `buffer` to `length` values keeping its allocation when it's large enough,
+/// content is unspecified and must be overwritten by the caller
+#[inline]
+pub(crate) fn working_buffer<T: Copy + Default>(buffer: &mut Vec<T>, length: usize) -> &mut [T] {
+    buffer.resize(length, T::default());
+    &mut buffer[..length]
+}
@@ -0,0 +1,321 @@
+//! Selection of SIMD row handlers at runtime, scalar handlers are used when no extension is
+//! available or the matching cargo feature is disabled.
+
+/// Counts planar row samples into `lanes` interleaved sub-histograms of `bins` each,
+/// samples are clamped to `max_bin`
+pub(crate) type RowCounter<T> = fn(&[T], usize, usize, usize, &mut [u32]);
+
+/// Bilinear interpolation of a block: `c00`, `c10`, `c01`, `c11`, horizontal weights,
+/// vertical weight and destination
+pub(crate) type BlerpBlock = fn(&[f32], &[f32], &[f32], &[f32], &[f32], f32, &mut [f32]);
+
+/// Sample type which may be counted into histogram with SIMD
+pub(crate) trait HistogramSample: Copy + Into<usize> {
+    fn row_counter() -> RowCounter<Self>;
+}
+
+/// Scalar [RowCounter], rows are walked in fixed chunks of 16 samples so the lane of every
+/// sample is known at compile time
+#[inline(always)]
+pub(crate) fn count_row_scalar<T: Copy + Into<usize>>(
+    row: &[T],
+    max_bin: usize,
+    bins: usize,
+    lanes: usize,
+    histograms: &mut [u32],
+) {
+    let lane_mask = lanes - 1;
+    let mut chunks = row.chunks_exact(16);
+    for chunk in &mut chunks {
+        for (i, &value) in chunk.iter().enumerate() {
+            let value: usize = value.into();
+            unsafe {
+                *histograms.get_unchecked_mut((i & lane_mask) * bins + value.min(max_bin)) += 1;
+            }
+        }
+    }
+    // Chunks are a multiple of lanes count, so the remainder starts again from the first lane
+    for (i, &value) in chunks.remainder().iter().enumerate() {
+        let value: usize = value.into();
+        unsafe {
+            *histograms.get_unchecked_mut((i & lane_mask) * bins + value.min(max_bin)) += 1;
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn blerp_block_scalar(
+    c00: &[f32],
+    c10: &[f32],
+    c01: &[f32],
+    c11: &[f32],
+    tx: &[f32],
+    ty: f32,
+    dst: &mut [f32],
+) {
+    let ty1 = 1f32 - ty;
+    for (((((dst, &c00), &c10), &c01), &c11), &tx) in dst
+        .iter_mut()
+        .zip(c00.iter())
+        .zip(c10.iter())
+        .zip(c01.iter())
+        .zip(c11.iter())
+        .zip(tx.iter())
+    {
+        let tx1 = 1f32 - tx;
+        let top = c00 * tx1 + c10 * tx;
+        let bottom = c01 * tx1 + c11 * tx;
+        *dst = top * ty1 + bottom * ty;
+    }
+}
+
+fn count_row_u8_scalar(row: &[u8], max_bin: usize, bins: usize, lanes: usize, hist: &mut [u32]) {
+    count_row_scalar(row, max_bin, bins, lanes, hist);
+}
+
+fn count_row_u16_scalar(row: &[u16], max_bin: usize, bins: usize, lanes: usize, hist: &mut [u32]) {
+    count_row_scalar(row, max_bin, bins, lanes, hist);
+}
+
+impl HistogramSample for u8 {
+    fn row_counter() -> RowCounter<u8> {
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            return |row, max_bin, bins, lanes, hist| unsafe {
+                crate::neon::neon_count_row_u8(row, max_bin, bins, lanes, hist)
+            };
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "avx"))]
+        {
+            if std::arch::is_x86_feature_detected!("avx2") {
+                return |row, max_bin, bins, lanes, hist| unsafe {
+                    crate::avx::avx_count_row_u8(row, max_bin, bins, lanes, hist)
+                };
+            }
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+        {
+            if std::arch::is_x86_feature_detected!("sse4.1") {
+                return |row, max_bin, bins, lanes, hist| unsafe {
+                    crate::sse::sse_count_row_u8(row, max_bin, bins, lanes, hist)
+                };
+            }
+        }
+        #[allow(unreachable_code)]
+        count_row_u8_scalar
+    }
+}
+
+impl HistogramSample for u16 {
+    fn row_counter() -> RowCounter<u16> {
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            return |row, max_bin, bins, lanes, hist| unsafe {
+                crate::neon::neon_count_row_u16(row, max_bin, bins, lanes, hist)
+            };
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "avx"))]
+        {
+            if std::arch::is_x86_feature_detected!("avx2") {
+                return |row, max_bin, bins, lanes, hist| unsafe {
+                    crate::avx::avx_count_row_u16(row, max_bin, bins, lanes, hist)
+                };
+            }
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+        {
+            if std::arch::is_x86_feature_detected!("sse4.1") {
+                return |row, max_bin, bins, lanes, hist| unsafe {
+                    crate::sse::sse_count_row_u16(row, max_bin, bins, lanes, hist)
+                };
+            }
+        }
+        #[allow(unreachable_code)]
+        count_row_u16_scalar
+    }
+}
+
+/// Selects the fastest available bilinear block interpolation
+pub(crate) fn blerp_block_handler() -> BlerpBlock {
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        return |c00, c10, c01, c11, tx, ty, dst| unsafe {
+            crate::neon::neon_blerp_block(c00, c10, c01, c11, tx, ty, dst)
+        };
+    }
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "avx"))]
+    {
+        if std::arch::is_x86_feature_detected!("avx2") {
+            return |c00, c10, c01, c11, tx, ty, dst| unsafe {
+                crate::avx::avx_blerp_block(c00, c10, c01, c11, tx, ty, dst)
+            };
+        }
+    }
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            return |c00, c10, c01, c11, tx, ty, dst| unsafe {
+                crate::sse::sse_blerp_block(c00, c10, c01, c11, tx, ty, dst)
+            };
+        }
+    }
+    #[allow(unreachable_code)]
+    |c00, c10, c01, c11, tx, ty, dst| blerp_block_scalar(c00, c10, c01, c11, tx, ty, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random(seed: u64) -> impl FnMut() -> u64 {
+        let mut state = seed;
+        move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        }
+    }
+
+    /// Dispatched handler and every kernel available on this CPU
+    fn blerp_kernels() -> Vec<(&'static str, BlerpBlock)> {
+        #[allow(unused_mut)]
+        let mut kernels: Vec<(&'static str, BlerpBlock)> =
+            vec![("dispatched", blerp_block_handler())];
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            kernels.push(("neon", |c00, c10, c01, c11, tx, ty, dst| unsafe {
+                crate::neon::neon_blerp_block(c00, c10, c01, c11, tx, ty, dst)
+            }));
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "avx"))]
+        {
+            if std::arch::is_x86_feature_detected!("avx2") {
+                kernels.push(("avx", |c00, c10, c01, c11, tx, ty, dst| unsafe {
+                    crate::avx::avx_blerp_block(c00, c10, c01, c11, tx, ty, dst)
+                }));
+            }
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+        {
+            if std::arch::is_x86_feature_detected!("sse4.1") {
+                kernels.push(("sse", |c00, c10, c01, c11, tx, ty, dst| unsafe {
+                    crate::sse::sse_blerp_block(c00, c10, c01, c11, tx, ty, dst)
+                }));
+            }
+        }
+        kernels
+    }
+
+    #[test]
+    fn blerp_kernels_match_scalar() {
+        let mut next = random(0x9e37_79b9_7f4a_7c15);
+        let mut value = move |scale: f32| (next() % 1_000_000) as f32 / 1_000_000f32 * scale;
+        for length in 0..=75usize {
+            // One extra leading value to run kernels on unaligned slices too
+            let planes: Vec<Vec<f32>> = (0..4)
+                .map(|_| (0..length + 1).map(|_| value(65535f32)).collect())
+                .collect();
+            let tx: Vec<f32> = (0..length + 1).map(|_| value(1f32)).collect();
+            let ty = value(1f32);
+            for offset in 0..2 {
+                let range = offset..offset + length;
+                let mut expected = vec![0f32; range.len()];
+                blerp_block_scalar(
+                    &planes[0][range.clone()],
+                    &planes[1][range.clone()],
+                    &planes[2][range.clone()],
+                    &planes[3][range.clone()],
+                    &tx[range.clone()],
+                    ty,
+                    &mut expected,
+                );
+                for (name, kernel) in blerp_kernels() {
+                    let mut dst = vec![0f32; range.len()];
+                    kernel(
+                        &planes[0][range.clone()],
+                        &planes[1][range.clone()],
+                        &planes[2][range.clone()],
+                        &planes[3][range.clone()],
+                        &tx[range.clone()],
+                        ty,
+                        &mut dst,
+                    );
+                    assert_eq!(dst, expected, "{name} length {length} offset {offset}");
+                }
+            }
+        }
+    }
+
+    /// Dispatched counter and every kernel available on this CPU
+    fn row_counters() -> Vec<(&'static str, RowCounter<u16>)> {
+        #[allow(unused_mut)]
+        let mut counters: Vec<(&'static str, RowCounter<u16>)> = vec![
+            ("scalar", count_row_u16_scalar),
+            ("dispatched", u16::row_counter()),
+        ];
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            counters.push(("neon", |row, max_bin, bins, lanes, hist| unsafe {
+                crate::neon::neon_count_row_u16(row, max_bin, bins, lanes, hist)
+            }));
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "avx"))]
+        {
+            if std::arch::is_x86_feature_detected!("avx2") {
+                counters.push(("avx", |row, max_bin, bins, lanes, hist| unsafe {
+                    crate::avx::avx_count_row_u16(row, max_bin, bins, lanes, hist)
+                }));
+            }
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+        {
+            if std::arch::is_x86_feature_detected!("sse4.1") {
+                counters.push(("sse", |row, max_bin, bins, lanes, hist| unsafe {
+                    crate::sse::sse_count_row_u16(row, max_bin, bins, lanes, hist)
+                }));
+            }
+        }
+        counters
+    }
+
+    #[test]
+    fn row_counters_match_histogram() {
+        let mut next = random(0x2545_f491_4f6c_dd1d);
+        for (bins, lanes) in [(256usize, 1usize), (256, 4), (1024, 4), (16, 2)] {
+            for length in [0usize, 1, 7, 15, 16, 17, 31, 33, 63, 100] {
+                let row: Vec<u16> = (0..length).map(|_| (next() % 1500) as u16).collect();
+                let mut expected = vec![0u32; bins];
+                for &value in row.iter() {
+                    expected[(value as usize).min(bins - 1)] += 1;
+                }
+                for (name, count_row) in row_counters() {
+                    let mut histograms = vec![0u32; bins * lanes];
+                    count_row(&row, bins - 1, bins, lanes, &mut histograms);
+                    let merged: Vec<u32> = (0..bins)
+                        .map(|bin| (0..lanes).map(|lane| histograms[lane * bins + bin]).sum())
+                        .collect();
+                    assert_eq!(merged, expected, "{name} {bins} {lanes} {length}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn u8_row_counter_matches_histogram() {
+        let mut next = random(0x6a09_e667_f3bc_c909);
+        for length in [0usize, 15, 16, 31, 32, 33, 100] {
+            let row: Vec<u8> = (0..length).map(|_| next() as u8).collect();
+            let mut expected = vec![0u32; 64];
+            for &value in row.iter() {
+                expected[(value as usize).min(63)] += 1;
+            }
+            let mut histograms = vec![0u32; 64 * 4];
+            u8::row_counter()(&row, 63, 64, 4, &mut histograms);
+            let merged: Vec<u32> = (0..64)
+                .map(|bin| (0..4).map(|lane| histograms[lane * 64 + bin]).sum())
+                .collect();
+            assert_eq!(merged, expected, "{length}");
+        }
+    }
+}
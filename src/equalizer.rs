@@ -0,0 +1,1446 @@
+use crate::clahe_call_proxy::clahe_impl_u16_proxy;
+use crate::clahe_impl::clahe_impl_u16;
+use crate::clahe_yuv_impl::clahe_yuv_impl;
+use crate::context::{working_buffer, EqualizerContext};
+use crate::error::{
+    check_destination, check_image_size, check_source, check_yuv_bit_depth, HistogramError,
+};
+use crate::hist_equal_call_proxy::equalize_histogram_impl_proxy;
+use crate::hist_equal_impl::equalize_histogram_impl;
+use crate::hist_equal_yuv_impl::equalize_histogram_yuv_impl;
+use crate::hist_support::{AheImplementation, ClaheMode, ClaheOptions, ClipRedistribution};
+use crate::hsv::{
+    bgr_to_hsl, bgr_to_hsv, bgra_to_hsl, bgra_to_hsv, generic_image_to_hsv, hsl_to_bgr,
+    hsl_to_bgra, hsl_to_rgb, hsl_to_rgba, hsv_to_bgr, hsv_to_bgra, hsv_to_generic_image,
+    hsv_to_rgb, hsv_to_rgba, rgb_to_hsl, rgb_to_hsv, rgba_to_hsl, rgba_to_hsv, HsvTarget,
+};
+use crate::image_configuration::ImageConfiguration;
+use crate::jzazbz::{
+    bgr_to_jzazbz, bgra_to_jzazbz, generic_image_to_jzazbz, jzazbz_to_bgr, jzazbz_to_bgra,
+    jzazbz_to_generic_image, jzazbz_to_rgb, jzazbz_to_rgba, rgb_to_jzazbz, rgba_to_jzazbz,
+};
+use crate::lab::{
+    bgr_to_lab, bgra_to_lab, generic_image_to_lab_planar, lab_planar_to_generic_image, lab_to_bgr,
+    lab_to_bgra, lab_to_rgb, lab_to_rgba, rgb_to_lab, rgba_to_lab,
+};
+use crate::luv::{
+    bgr_to_luv, bgra_to_luv, generic_image_to_luv_planar, luv_planar_to_generic_image, luv_to_bgr,
+    luv_to_bgra, luv_to_rgb, luv_to_rgba, rgb_to_luv, rgba_to_luv,
+};
+use crate::mask::{blend_by_weights, mask_weights, EqualizationRegion, HistogramMask};
+use crate::oklab::{
+    bgr_to_oklab, bgra_to_oklab, generic_image_to_oklab, oklab_to_bgr, oklab_to_bgra,
+    oklab_to_generic_image, oklab_to_rgb, oklab_to_rgba, rgb_to_oklab, rgba_to_oklab,
+};
+use crate::oklch::{
+    bgr_to_oklch, bgra_to_oklch, generic_image_to_oklch, oklch_to_bgr, oklch_to_bgra,
+    oklch_to_generic_image, oklch_to_rgb, oklch_to_rgba, rgb_to_oklch, rgba_to_oklch,
+};
+use crate::sample::{premultiply_image, unpremultiply_image};
+use crate::video_equalizer::LutHistory;
+use crate::yuv_planar_impl::{equalize_yuv_biplanar_impl, equalize_yuv_planar_impl};
+use crate::yuv_support::{image_to_yuv444, yuv444_to_image, YuvMatrix, YuvOptions};
+use crate::ClaheGridSize;
+use colorutils_rs::{ColorError, ImageBuffer, ImageBufferMut, TransferFunction};
+use yuv::{
+    YuvBiPlanarImageMut, YuvChromaSubsampling, YuvError, YuvPlanarImageMut,
+    YuvPlanarImageWithAlpha, YuvRange,
+};
+
+/// Color space where lightness histogram is equalized
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+pub enum EqualizationColorSpace {
+    /// YUV of [Equalizer::with_yuv_matrix] and [Equalizer::with_yuv_range], YCgCo full range by
+    /// default, histogram has a bin for every luma level of the range
+    Yuv,
+    Hsv,
+    Hsl,
+    #[default]
+    Lab,
+    Luv,
+    Oklab,
+    Oklch,
+    Jzazbz,
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+pub enum EqualizationMethod {
+    /// Global histogram equalization
+    HistogramEqualization,
+    /// Adaptive histogram equalization
+    Ahe,
+    /// Contrast limited adaptive histogram equalization
+    #[default]
+    Clahe,
+}
+
+/// Histogram equalization configured once and applied to any number of images
+///
+/// # Example
+///
+/// ```
+/// use histogram_equalization::{
+///     ClaheGridSize, EqualizationColorSpace, EqualizationMethod, Equalizer, ImageConfiguration,
+/// };
+///
+/// let (width, height) = (64u32, 64u32);
+/// let src = vec![127u8; width as usize * height as usize * 3];
+/// let mut dst = vec![0u8; src.len()];
+/// Equalizer::new()
+///     .with_color_space(EqualizationColorSpace::Oklab)
+///     .with_method(EqualizationMethod::Clahe)
+///     .with_threshold(2f32)
+///     .with_grid_size(ClaheGridSize::new(8, 8))
+///     .with_layout(ImageConfiguration::Rgb)
+///     .apply(&src, width * 3, &mut dst, width * 3, width, height)
+///     .unwrap();
+/// ```
+///
+/// # Thread pool
+///
+/// With `rayon` feature every method of the equalizer, as every function of the crate, runs on
+/// the current rayon pool, so to run on a caller owned pool call it within
+/// `rayon::ThreadPool::install`. This holds for [Equalizer::apply], [Equalizer::apply_masked],
+/// [Equalizer::apply_with_context], YUV planar methods and video equalization alike.
+///
+/// ```
+/// # #[cfg(feature = "rayon")]
+/// # {
+/// use histogram_equalization::{Equalizer, EqualizerContext};
+///
+/// let (width, height) = (64u32, 64u32);
+/// let src = vec![127u8; width as usize * height as usize * 3];
+/// let mut dst = vec![0u8; src.len()];
+/// let mut context = EqualizerContext::new();
+/// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+/// pool.install(|| {
+///     Equalizer::new().apply_with_context(
+///         &mut context,
+///         &src,
+///         width * 3,
+///         &mut dst,
+///         width * 3,
+///         width,
+///         height,
+///     )
+/// })
+/// .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Equalizer {
+    color_space: EqualizationColorSpace,
+    method: EqualizationMethod,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+    layout: ImageConfiguration,
+    premultiplied_alpha: bool,
+    clahe_options: ClaheOptions,
+    feather_radius: u32,
+    yuv_options: YuvOptions,
+}
+
+impl Default for Equalizer {
+    fn default() -> Self {
+        Equalizer {
+            color_space: EqualizationColorSpace::default(),
+            method: EqualizationMethod::default(),
+            threshold: 4f32,
+            grid_size: ClaheGridSize::new(8, 8),
+            bins_count: 128,
+            layout: ImageConfiguration::Rgb,
+            premultiplied_alpha: false,
+            clahe_options: ClaheOptions::default(),
+            feather_radius: 0,
+            yuv_options: YuvOptions::default(),
+        }
+    }
+}
+
+impl Equalizer {
+    /// Creates equalizer with CLAHE in CIE L\*a\*b, threshold 4, grid (8,8), 128 bins and RGB layout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_color_space(mut self, color_space: EqualizationColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    pub fn with_method(mut self, method: EqualizationMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Level of clipping histogram ~[0, 10], used only by CLAHE
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Grid for constructing histograms, not used by global histogram equalization
+    pub fn with_grid_size(mut self, grid_size: ClaheGridSize) -> Self {
+        self.grid_size = grid_size;
+        self
+    }
+
+    /// Histogram bins, ignored for YUV where every luma level of the range has its own bin
+    pub fn with_bins_count(mut self, bins_count: usize) -> Self {
+        self.bins_count = bins_count;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: ImageConfiguration) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Marks color channels as premultiplied by alpha, they will be un-premultiplied before
+    /// equalization and premultiplied back after. Ignored for layouts without alpha.
+    ///
+    /// YUV supports only RGB, BGR, RGBA and BGRA
+    pub fn with_premultiplied_alpha(mut self, premultiplied_alpha: bool) -> Self {
+        self.premultiplied_alpha = premultiplied_alpha;
+        self
+    }
+
+    /// How the excess of clipped bins is given back, used only by CLAHE
+    pub fn with_clip_redistribution(mut self, clip_redistribution: ClipRedistribution) -> Self {
+        self.clahe_options.clip_redistribution = clip_redistribution;
+        self
+    }
+
+    /// Tiling and interpolation semantics of AHE and CLAHE, [ClaheMode::OpenCv] gives the same
+    /// result as `cv::CLAHE` on the lightness channel, [ClaheMode::SlidingWindow] maps every
+    /// pixel by its own neighbourhood
+    pub fn with_clahe_mode(mut self, clahe_mode: ClaheMode) -> Self {
+        self.clahe_options.mode = clahe_mode;
+        self
+    }
+
+    /// Width in pixels of the transition at the edge of the region in [Equalizer::apply_masked],
+    /// 0 gives a hard edge
+    pub fn with_feather_radius(mut self, feather_radius: u32) -> Self {
+        self.feather_radius = feather_radius;
+        self
+    }
+
+    /// Matrix of conversion into YUV, YCgCo by default
+    pub fn with_yuv_matrix(mut self, yuv_matrix: YuvMatrix) -> Self {
+        self.yuv_options.matrix = yuv_matrix;
+        self
+    }
+
+    /// Range of YUV, full by default. With limited range histograms span only luma levels
+    /// of the range and luma outside of it is clamped, this applies to planar frames as well
+    pub fn with_yuv_range(mut self, yuv_range: YuvRange) -> Self {
+        self.yuv_options.range = yuv_range;
+        self
+    }
+
+    pub fn color_space(&self) -> EqualizationColorSpace {
+        self.color_space
+    }
+
+    pub fn method(&self) -> EqualizationMethod {
+        self.method
+    }
+
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    pub fn grid_size(&self) -> ClaheGridSize {
+        self.grid_size
+    }
+
+    pub fn bins_count(&self) -> usize {
+        self.bins_count
+    }
+
+    pub fn layout(&self) -> ImageConfiguration {
+        self.layout
+    }
+
+    pub fn premultiplied_alpha(&self) -> bool {
+        self.premultiplied_alpha
+    }
+
+    pub fn clip_redistribution(&self) -> ClipRedistribution {
+        self.clahe_options.clip_redistribution
+    }
+
+    pub fn clahe_mode(&self) -> ClaheMode {
+        self.clahe_options.mode
+    }
+
+    pub fn feather_radius(&self) -> u32 {
+        self.feather_radius
+    }
+
+    pub fn yuv_matrix(&self) -> YuvMatrix {
+        self.yuv_options.matrix
+    }
+
+    pub fn yuv_range(&self) -> YuvRange {
+        self.yuv_options.range
+    }
+
+    /// Performs configured equalization of `src` into `dst`
+    ///
+    /// # Arguments
+    ///
+    /// * `src_stride` - Bytes per row of `src`
+    /// * `dst_stride` - Bytes per row of `dst`
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+    /// on the specified width, height, and strides, or if parameters are out of supported range
+    pub fn apply(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), HistogramError> {
+        self.apply_with_context(
+            &mut EqualizerContext::default(),
+            src,
+            src_stride,
+            dst,
+            dst_stride,
+            width,
+            height,
+        )
+    }
+
+    /// Performs configured equalization of `src` into `dst` with working buffers of `context`,
+    /// same as [Equalizer::apply] except that frames of the same size are equalized without
+    /// allocations, see [EqualizerContext]
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Working buffers, grow when the image doesn't fit in them
+    /// * `src_stride` - Bytes per row of `src`
+    /// * `dst_stride` - Bytes per row of `dst`
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+    /// on the specified width, height, and strides, or if parameters are out of supported range
+    pub fn apply_with_context(
+        &self,
+        context: &mut EqualizerContext,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), HistogramError> {
+        self.apply_with_mask(
+            src, src_stride, dst, dst_stride, width, height, None, None, context,
+        )
+    }
+
+    /// Performs configured equalization of `src` into `dst` only inside `region`.
+    ///
+    /// Histograms count only pixels of the region and the mapping is written only there,
+    /// pixels outside are copied from `src` unchanged. With [Equalizer::with_feather_radius]
+    /// equalized values are blended with the source near the edge of the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_stride` - Bytes per row of `src`
+    /// * `dst_stride` - Bytes per row of `dst`
+    /// * `region` - Rectangle or per pixel mask of `width` x `height` pixels
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes, the mask or the input data are not
+    /// valid based on the specified width, height, and strides, if parameters are out of supported
+    /// range, or if AHE and CLAHE are configured with [ClaheMode::OpenCv]
+    pub fn apply_masked(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+        region: EqualizationRegion<'_>,
+    ) -> Result<(), HistogramError> {
+        if self.method != EqualizationMethod::HistogramEqualization
+            && self.clahe_options.mode == ClaheMode::OpenCv
+        {
+            return Err(HistogramError::UnsupportedMaskMode(self.clahe_options.mode));
+        }
+        check_image_size(width, height)?;
+        let mask = HistogramMask::new(region, width, height)?;
+
+        self.apply_with_mask(
+            src,
+            src_stride,
+            dst,
+            dst_stride,
+            width,
+            height,
+            Some(mask),
+            None,
+            &mut EqualizerContext::default(),
+        )?;
+
+        let weights = mask_weights(mask, width as usize, height as usize, self.feather_radius);
+        blend_by_weights(
+            src,
+            src_stride as usize,
+            dst,
+            dst_stride as usize,
+            width as usize,
+            self.layout.get_channels_count(),
+            &weights,
+        );
+        Ok(())
+    }
+
+    /// Histograms count only pixels of `mask` when it is set, tile LUTs are blended with
+    /// `history` of previous frames when it is set, working buffers are taken from `context`
+    pub(crate) fn apply_with_mask(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+        mask: Option<HistogramMask<'_>>,
+        history: Option<&mut LutHistory>,
+        context: &mut EqualizerContext,
+    ) -> Result<(), HistogramError> {
+        if self.premultiplied_alpha && self.layout.has_alpha() {
+            return self.apply_premultiplied(
+                src, src_stride, dst, dst_stride, width, height, mask, history, context,
+            );
+        }
+        match (self.color_space, self.layout) {
+            (_, ImageConfiguration::Argb) => self
+                .apply_generic::<{ ImageConfiguration::Argb as u8 }>(
+                    src, src_stride, dst, dst_stride, width, height, mask, history, context,
+                ),
+            (_, ImageConfiguration::Abgr) => self
+                .apply_generic::<{ ImageConfiguration::Abgr as u8 }>(
+                    src, src_stride, dst, dst_stride, width, height, mask, history, context,
+                ),
+            (EqualizationColorSpace::Yuv, ImageConfiguration::Rgb) => self.apply_yuv::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                image_to_yuv444::<{ ImageConfiguration::Rgb as u8 }>,
+                yuv444_to_image::<{ ImageConfiguration::Rgb as u8 }>,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Yuv, ImageConfiguration::Rgba) => self.apply_yuv::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                image_to_yuv444::<{ ImageConfiguration::Rgba as u8 }>,
+                yuv444_to_image::<{ ImageConfiguration::Rgba as u8 }>,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Yuv, ImageConfiguration::Bgra) => self.apply_yuv::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                image_to_yuv444::<{ ImageConfiguration::Bgra as u8 }>,
+                yuv444_to_image::<{ ImageConfiguration::Bgra as u8 }>,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Hsv, ImageConfiguration::Rgb) => self
+                .apply_interleaved::<3, 2>(
+                    src, src_stride, dst, dst_stride, width, height, rgb_to_hsv, hsv_to_rgb, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Hsv, ImageConfiguration::Rgba) => self
+                .apply_interleaved::<4, 2>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    rgba_to_hsv,
+                    hsv_to_rgba,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Hsv, ImageConfiguration::Bgra) => self
+                .apply_interleaved::<4, 2>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    bgra_to_hsv,
+                    hsv_to_bgra,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Hsl, ImageConfiguration::Rgb) => self
+                .apply_interleaved::<3, 2>(
+                    src, src_stride, dst, dst_stride, width, height, rgb_to_hsl, hsl_to_rgb, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Hsl, ImageConfiguration::Rgba) => self
+                .apply_interleaved::<4, 2>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    rgba_to_hsl,
+                    hsl_to_rgba,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Hsl, ImageConfiguration::Bgra) => self
+                .apply_interleaved::<4, 2>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    bgra_to_hsl,
+                    hsl_to_bgra,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Lab, ImageConfiguration::Rgb) => self
+                .apply_interleaved::<3, 0>(
+                    src, src_stride, dst, dst_stride, width, height, rgb_to_lab, lab_to_rgb, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Lab, ImageConfiguration::Rgba) => self
+                .apply_interleaved::<4, 0>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    rgba_to_lab,
+                    lab_to_rgba,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Lab, ImageConfiguration::Bgra) => self
+                .apply_interleaved::<4, 0>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    bgra_to_lab,
+                    lab_to_bgra,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Luv, ImageConfiguration::Rgb) => self
+                .apply_interleaved::<3, 0>(
+                    src, src_stride, dst, dst_stride, width, height, rgb_to_luv, luv_to_rgb, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Luv, ImageConfiguration::Rgba) => self
+                .apply_interleaved::<4, 0>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    rgba_to_luv,
+                    luv_to_rgba,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Luv, ImageConfiguration::Bgra) => self
+                .apply_interleaved::<4, 0>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    bgra_to_luv,
+                    luv_to_bgra,
+                    mask,
+                    history,
+                    context,
+                ),
+            (EqualizationColorSpace::Oklab, ImageConfiguration::Rgb) => self.apply_planar::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                rgb_to_oklab,
+                oklab_to_rgb,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Oklab, ImageConfiguration::Rgba) => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                rgba_to_oklab,
+                oklab_to_rgba,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Oklab, ImageConfiguration::Bgra) => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                bgra_to_oklab,
+                oklab_to_bgra,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Oklch, ImageConfiguration::Rgb) => self.apply_planar::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                rgb_to_oklch,
+                oklch_to_rgb,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Oklch, ImageConfiguration::Rgba) => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                rgba_to_oklch,
+                oklch_to_rgba,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Oklch, ImageConfiguration::Bgra) => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                bgra_to_oklch,
+                oklch_to_bgra,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Jzazbz, ImageConfiguration::Rgb) => self.apply_planar::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                rgb_to_jzazbz,
+                jzazbz_to_rgb,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Jzazbz, ImageConfiguration::Rgba) => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                rgba_to_jzazbz,
+                jzazbz_to_rgba,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Jzazbz, ImageConfiguration::Bgra) => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                bgra_to_jzazbz,
+                jzazbz_to_bgra,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Yuv, ImageConfiguration::Bgr) => self.apply_yuv::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                image_to_yuv444::<{ ImageConfiguration::Bgr as u8 }>,
+                yuv444_to_image::<{ ImageConfiguration::Bgr as u8 }>,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Hsv, ImageConfiguration::Bgr) => self
+                .apply_interleaved::<3, 2>(
+                    src, src_stride, dst, dst_stride, width, height, bgr_to_hsv, hsv_to_bgr, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Hsl, ImageConfiguration::Bgr) => self
+                .apply_interleaved::<3, 2>(
+                    src, src_stride, dst, dst_stride, width, height, bgr_to_hsl, hsl_to_bgr, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Lab, ImageConfiguration::Bgr) => self
+                .apply_interleaved::<3, 0>(
+                    src, src_stride, dst, dst_stride, width, height, bgr_to_lab, lab_to_bgr, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Luv, ImageConfiguration::Bgr) => self
+                .apply_interleaved::<3, 0>(
+                    src, src_stride, dst, dst_stride, width, height, bgr_to_luv, luv_to_bgr, mask,
+                    history, context,
+                ),
+            (EqualizationColorSpace::Oklab, ImageConfiguration::Bgr) => self.apply_planar::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                bgr_to_oklab,
+                oklab_to_bgr,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Oklch, ImageConfiguration::Bgr) => self.apply_planar::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                bgr_to_oklch,
+                oklch_to_bgr,
+                mask,
+                history,
+                context,
+            ),
+            (EqualizationColorSpace::Jzazbz, ImageConfiguration::Bgr) => self.apply_planar::<3>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                bgr_to_jzazbz,
+                jzazbz_to_bgr,
+                mask,
+                history,
+                context,
+            ),
+        }
+    }
+
+    /// Performs configured equalization of the luma plane of planar YUV frame in place,
+    /// chroma planes are left untouched. Color space, layout, bins count and YUV matrix
+    /// are ignored, luma has a bin for every level of the YUV range.
+    ///
+    /// # Arguments
+    ///
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_planar(
+        &self,
+        image: &mut YuvPlanarImageMut<u8>,
+        subsampling: YuvChromaSubsampling,
+    ) -> Result<(), HistogramError> {
+        self.apply_yuv_planar_with_context(&mut EqualizerContext::default(), image, subsampling)
+    }
+
+    /// Performs configured equalization of the luma plane with working buffers of `context`,
+    /// same as [Equalizer::apply_yuv_planar] except that frames of the same size are equalized
+    /// without allocations, see [EqualizerContext]
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Working buffers, grow when the frame doesn't fit in them
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_planar_with_context(
+        &self,
+        context: &mut EqualizerContext,
+        image: &mut YuvPlanarImageMut<u8>,
+        subsampling: YuvChromaSubsampling,
+    ) -> Result<(), HistogramError> {
+        equalize_yuv_planar_impl(
+            image,
+            subsampling,
+            self.yuv_options.range,
+            8,
+            self.method,
+            self.threshold,
+            self.grid_size,
+            self.clahe_options,
+            None,
+            &mut context.yuv.tiles,
+        )
+    }
+
+    /// Performs configured equalization of the luma plane of bi-planar YUV frame in place,
+    /// interleaved chroma plane is left untouched. Color space, layout, bins count and YUV
+    /// matrix are ignored, luma has a bin for every level of the YUV range.
+    ///
+    /// # Arguments
+    ///
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma plane
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_biplanar(
+        &self,
+        image: &mut YuvBiPlanarImageMut<u8>,
+        subsampling: YuvChromaSubsampling,
+    ) -> Result<(), HistogramError> {
+        self.apply_yuv_biplanar_with_context(&mut EqualizerContext::default(), image, subsampling)
+    }
+
+    /// Performs configured equalization of the luma plane with working buffers of `context`,
+    /// same as [Equalizer::apply_yuv_biplanar] except that frames of the same size are equalized
+    /// without allocations, see [EqualizerContext]
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Working buffers, grow when the frame doesn't fit in them
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma plane
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_biplanar_with_context(
+        &self,
+        context: &mut EqualizerContext,
+        image: &mut YuvBiPlanarImageMut<u8>,
+        subsampling: YuvChromaSubsampling,
+    ) -> Result<(), HistogramError> {
+        equalize_yuv_biplanar_impl(
+            image,
+            subsampling,
+            self.yuv_options.range,
+            8,
+            self.method,
+            self.threshold,
+            self.grid_size,
+            self.clahe_options,
+            None,
+            &mut context.yuv.tiles,
+        )
+    }
+
+    /// Performs configured equalization of the luma plane of high bit depth planar YUV frame
+    /// in place, same as [Equalizer::apply_yuv_planar] with a bin for every luma level
+    /// at `bit_depth`
+    ///
+    /// # Arguments
+    ///
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+    /// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_planar_u16(
+        &self,
+        image: &mut YuvPlanarImageMut<u16>,
+        subsampling: YuvChromaSubsampling,
+        bit_depth: u32,
+    ) -> Result<(), HistogramError> {
+        self.apply_yuv_planar_u16_with_context(
+            &mut EqualizerContext::default(),
+            image,
+            subsampling,
+            bit_depth,
+        )
+    }
+
+    /// Performs configured equalization of the luma plane with working buffers of `context`,
+    /// same as [Equalizer::apply_yuv_planar_u16] except that frames of the same size are equalized
+    /// without allocations, see [EqualizerContext]
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Working buffers, grow when the frame doesn't fit in them
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+    /// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_planar_u16_with_context(
+        &self,
+        context: &mut EqualizerContext,
+        image: &mut YuvPlanarImageMut<u16>,
+        subsampling: YuvChromaSubsampling,
+        bit_depth: u32,
+    ) -> Result<(), HistogramError> {
+        check_yuv_bit_depth(bit_depth, None)?;
+        equalize_yuv_planar_impl(
+            image,
+            subsampling,
+            self.yuv_options.range,
+            bit_depth,
+            self.method,
+            self.threshold,
+            self.grid_size,
+            self.clahe_options,
+            None,
+            &mut context.tiles,
+        )
+    }
+
+    /// Performs configured equalization of the luma plane of high bit depth bi-planar YUV frame
+    /// in place, same as [Equalizer::apply_yuv_biplanar] with a bin for every luma level
+    /// at `bit_depth`
+    ///
+    /// # Arguments
+    ///
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma plane
+    /// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_biplanar_u16(
+        &self,
+        image: &mut YuvBiPlanarImageMut<u16>,
+        subsampling: YuvChromaSubsampling,
+        bit_depth: u32,
+    ) -> Result<(), HistogramError> {
+        self.apply_yuv_biplanar_u16_with_context(
+            &mut EqualizerContext::default(),
+            image,
+            subsampling,
+            bit_depth,
+        )
+    }
+
+    /// Performs configured equalization of the luma plane with working buffers of `context`,
+    /// same as [Equalizer::apply_yuv_biplanar_u16] except that frames of the same size are equalized
+    /// without allocations, see [EqualizerContext]
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Working buffers, grow when the frame doesn't fit in them
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma plane
+    /// * `bit_depth` - Bit depth of the frame, samples are stored in its lower bits, supported range is [8, 16]
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, or if parameters are out of supported range
+    pub fn apply_yuv_biplanar_u16_with_context(
+        &self,
+        context: &mut EqualizerContext,
+        image: &mut YuvBiPlanarImageMut<u16>,
+        subsampling: YuvChromaSubsampling,
+        bit_depth: u32,
+    ) -> Result<(), HistogramError> {
+        check_yuv_bit_depth(bit_depth, None)?;
+        equalize_yuv_biplanar_impl(
+            image,
+            subsampling,
+            self.yuv_options.range,
+            bit_depth,
+            self.method,
+            self.threshold,
+            self.grid_size,
+            self.clahe_options,
+            None,
+            &mut context.tiles,
+        )
+    }
+
+    /// Equalizes copy of premultiplied image with restored straight alpha and premultiplies
+    /// the result back, so the result matches equalization of straight alpha image
+    fn apply_premultiplied(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+        mask: Option<HistogramMask<'_>>,
+        history: Option<&mut LutHistory>,
+        context: &mut EqualizerContext,
+    ) -> Result<(), HistogramError> {
+        check_source(src, src_stride, width, height, 4)?;
+        check_destination(dst, dst_stride, width, height, 4)?;
+        let straight_stride = width as usize * 4;
+        let mut straight = std::mem::take(&mut context.straight);
+        let straight_image = working_buffer(&mut straight, straight_stride * height as usize);
+        unpremultiply_image(
+            src,
+            src_stride as usize,
+            straight_image,
+            straight_stride,
+            width as usize,
+            height as usize,
+            self.layout,
+        );
+        let straight_equalizer = Equalizer {
+            premultiplied_alpha: false,
+            ..*self
+        };
+        let result = straight_equalizer.apply_with_mask(
+            straight_image,
+            straight_stride as u32,
+            dst,
+            dst_stride,
+            width,
+            height,
+            mask,
+            history,
+            context,
+        );
+        context.straight = straight;
+        result?;
+        premultiply_image(
+            dst,
+            dst_stride as usize,
+            width as usize,
+            height as usize,
+            self.layout,
+        );
+        Ok(())
+    }
+
+    /// Equalizes any 4 channels layout through planar converters, those handle alpha-first
+    /// layouts
+    fn apply_generic<const IMAGE: u8>(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+        mask: Option<HistogramMask<'_>>,
+        history: Option<&mut LutHistory>,
+        context: &mut EqualizerContext,
+    ) -> Result<(), HistogramError> {
+        match self.color_space {
+            EqualizationColorSpace::Yuv => Err(HistogramError::UnsupportedLayout(self.layout)),
+            EqualizationColorSpace::Hsv => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                generic_image_to_hsv::<u8, IMAGE, { HsvTarget::Hsv as u8 }>,
+                hsv_to_generic_image::<u8, IMAGE, { HsvTarget::Hsv as u8 }>,
+                mask,
+                history,
+                context,
+            ),
+            EqualizationColorSpace::Hsl => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                generic_image_to_hsv::<u8, IMAGE, { HsvTarget::Hsl as u8 }>,
+                hsv_to_generic_image::<u8, IMAGE, { HsvTarget::Hsl as u8 }>,
+                mask,
+                history,
+                context,
+            ),
+            EqualizationColorSpace::Lab => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                generic_image_to_lab_planar::<u8, IMAGE>,
+                lab_planar_to_generic_image::<u8, IMAGE>,
+                mask,
+                history,
+                context,
+            ),
+            EqualizationColorSpace::Luv => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                generic_image_to_luv_planar::<u8, IMAGE>,
+                luv_planar_to_generic_image::<u8, IMAGE>,
+                mask,
+                history,
+                context,
+            ),
+            EqualizationColorSpace::Oklab => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                generic_image_to_oklab::<u8, IMAGE>,
+                oklab_to_generic_image::<u8, IMAGE>,
+                mask,
+                history,
+                context,
+            ),
+            EqualizationColorSpace::Oklch => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                generic_image_to_oklch::<u8, IMAGE>,
+                oklch_to_generic_image::<u8, IMAGE>,
+                mask,
+                history,
+                context,
+            ),
+            EqualizationColorSpace::Jzazbz => self.apply_planar::<4>(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                width,
+                height,
+                generic_image_to_jzazbz::<u8, IMAGE>,
+                jzazbz_to_generic_image::<u8, IMAGE>,
+                mask,
+                history,
+                context,
+            ),
+        }
+    }
+
+    fn apply_interleaved<const CHANNELS: usize, const CHANNEL_POSITION: usize>(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+        destructuring: fn(
+            &ImageBuffer<'_, u8>,
+            &mut ImageBufferMut<'_, u16>,
+            f32,
+        ) -> Result<(), ColorError>,
+        structuring: fn(
+            &ImageBuffer<'_, u16>,
+            &mut ImageBufferMut<'_, u8>,
+            f32,
+        ) -> Result<(), ColorError>,
+        mask: Option<HistogramMask<'_>>,
+        history: Option<&mut LutHistory>,
+        context: &mut EqualizerContext,
+    ) -> Result<(), HistogramError> {
+        match self.method {
+            EqualizationMethod::HistogramEqualization => {
+                equalize_histogram_impl::<CHANNELS, CHANNEL_POSITION>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    self.bins_count,
+                    destructuring,
+                    structuring,
+                    mask,
+                    context,
+                )
+            }
+            EqualizationMethod::Ahe => {
+                clahe_impl_u16::<CHANNELS, CHANNEL_POSITION, { AheImplementation::Ahe as u8 }>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    0f32,
+                    self.grid_size,
+                    self.clahe_options,
+                    self.bins_count,
+                    destructuring,
+                    structuring,
+                    mask,
+                    history,
+                    context,
+                )
+            }
+            EqualizationMethod::Clahe => {
+                clahe_impl_u16::<CHANNELS, CHANNEL_POSITION, { AheImplementation::Clahe as u8 }>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    self.threshold,
+                    self.grid_size,
+                    self.clahe_options,
+                    self.bins_count,
+                    destructuring,
+                    structuring,
+                    mask,
+                    history,
+                    context,
+                )
+            }
+        }
+    }
+
+    fn apply_planar<const CHANNELS: usize>(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+        destructuring: fn(
+            &[u8],
+            u32,
+            &mut [u16],
+            u32,
+            &mut [f32],
+            u32,
+            u32,
+            f32,
+            u32,
+            TransferFunction,
+        ) -> f32,
+        structuring: fn(&[u16], u32, &[f32], &mut [u8], u32, u32, u32, f32, u32, TransferFunction),
+        mask: Option<HistogramMask<'_>>,
+        history: Option<&mut LutHistory>,
+        context: &mut EqualizerContext,
+    ) -> Result<(), HistogramError> {
+        match self.method {
+            EqualizationMethod::HistogramEqualization => {
+                equalize_histogram_impl_proxy::<u8, CHANNELS>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    self.bins_count,
+                    8,
+                    TransferFunction::Srgb,
+                    destructuring,
+                    structuring,
+                    mask,
+                    context,
+                )
+            }
+            EqualizationMethod::Ahe => {
+                clahe_impl_u16_proxy::<u8, CHANNELS, { AheImplementation::Ahe as u8 }>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    0f32,
+                    self.grid_size,
+                    self.clahe_options,
+                    self.bins_count,
+                    8,
+                    TransferFunction::Srgb,
+                    destructuring,
+                    structuring,
+                    mask,
+                    history,
+                    context,
+                )
+            }
+            EqualizationMethod::Clahe => {
+                clahe_impl_u16_proxy::<u8, CHANNELS, { AheImplementation::Clahe as u8 }>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    self.threshold,
+                    self.grid_size,
+                    self.clahe_options,
+                    self.bins_count,
+                    8,
+                    TransferFunction::Srgb,
+                    destructuring,
+                    structuring,
+                    mask,
+                    history,
+                    context,
+                )
+            }
+        }
+    }
+
+    fn apply_yuv<const CHANNELS: usize>(
+        &self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+        destructuring: fn(
+            &mut YuvPlanarImageMut<u8>,
+            &[u8],
+            u32,
+            YuvOptions,
+        ) -> Result<(), YuvError>,
+        structuring: fn(
+            &YuvPlanarImageWithAlpha<u8>,
+            &mut [u8],
+            u32,
+            YuvOptions,
+        ) -> Result<(), YuvError>,
+        mask: Option<HistogramMask<'_>>,
+        history: Option<&mut LutHistory>,
+        context: &mut EqualizerContext,
+    ) -> Result<(), HistogramError> {
+        match self.method {
+            EqualizationMethod::HistogramEqualization => {
+                equalize_histogram_yuv_impl::<u8, CHANNELS>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    self.yuv_options,
+                    destructuring,
+                    structuring,
+                    mask,
+                    &mut context.yuv,
+                )
+            }
+            EqualizationMethod::Ahe => {
+                clahe_yuv_impl::<u8, CHANNELS, { AheImplementation::Ahe as u8 }>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    0f32,
+                    self.grid_size,
+                    self.clahe_options,
+                    self.yuv_options,
+                    destructuring,
+                    structuring,
+                    mask,
+                    history,
+                    &mut context.yuv,
+                )
+            }
+            EqualizationMethod::Clahe => {
+                clahe_yuv_impl::<u8, CHANNELS, { AheImplementation::Clahe as u8 }>(
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                    width,
+                    height,
+                    self.threshold,
+                    self.grid_size,
+                    self.clahe_options,
+                    self.yuv_options,
+                    destructuring,
+                    structuring,
+                    mask,
+                    history,
+                    &mut context.yuv,
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use crate::hist_support::{ClaheGridSize, ClaheMode, ImageHistogram, ImageRegion};
+use crate::image_configuration::ImageConfiguration;
+use crate::yuv_support::YuvMatrix;
+use colorutils_rs::ColorError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use yuv::YuvError;
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+/// Shows size mismatching
+pub struct MismatchedSize {
+    pub expected: usize,
+    pub received: usize,
+}
+
+#[derive(Debug)]
+pub enum HistogramError {
+    ZeroBaseSize,
+    SourceStrideMismatch(MismatchedSize),
+    DestinationStrideMismatch(MismatchedSize),
+    SourceSizeMismatch(MismatchedSize),
+    DestinationSizeMismatch(MismatchedSize),
+    InvalidGridSize(ClaheGridSize),
+    InvalidBinsCount(usize),
+    InvalidRegion(ImageRegion),
+    InvalidChannel(usize),
+    EmptyHistogram,
+    HistogramBinsMismatch(MismatchedSize),
+    UnsupportedBitDepth(u32),
+    UnsupportedLayout(ImageConfiguration),
+    MaskStrideMismatch(MismatchedSize),
+    MaskSizeMismatch(MismatchedSize),
+    UnsupportedMaskMode(ClaheMode),
+    UnsupportedYuvBitDepth(u32, Option<YuvMatrix>),
+    UnsupportedVideoMode(ClaheMode),
+    InvalidSmoothing(f32),
+    Color(ColorError),
+    Yuv(YuvError),
+}
+
+impl Display for HistogramError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistogramError::ZeroBaseSize => f.write_str("Zero sized images is not supported"),
+            HistogramError::SourceStrideMismatch(size) => f.write_fmt(format_args!(
+                "Source stride must be at least {}, but it was {}",
+                size.expected, size.received
+            )),
+            HistogramError::DestinationStrideMismatch(size) => f.write_fmt(format_args!(
+                "Destination stride must be at least {}, but it was {}",
+                size.expected, size.received
+            )),
+            HistogramError::SourceSizeMismatch(size) => f.write_fmt(format_args!(
+                "Source buffer must have at least {} elements, but it was {}",
+                size.expected, size.received
+            )),
+            HistogramError::DestinationSizeMismatch(size) => f.write_fmt(format_args!(
+                "Destination buffer must have at least {} elements, but it was {}",
+                size.expected, size.received
+            )),
+            HistogramError::InvalidGridSize(grid) => f.write_fmt(format_args!(
+                "Grid size must be non zero, but it was ({}, {})",
+                grid.w, grid.h
+            )),
+            HistogramError::InvalidBinsCount(bins_count) => f.write_fmt(format_args!(
+                "Bins count must be in range [2, 65536], but it was {}",
+                bins_count
+            )),
+            HistogramError::InvalidRegion(region) => f.write_fmt(format_args!(
+                "Region must be non empty and inside the image, but it was ({}, {}, {}, {})",
+                region.x, region.y, region.width, region.height
+            )),
+            HistogramError::InvalidChannel(channel) => f.write_fmt(format_args!(
+                "Channel {} does not exist in the image",
+                channel
+            )),
+            HistogramError::EmptyHistogram => f.write_str("Target histogram must not be empty"),
+            HistogramError::HistogramBinsMismatch(size) => f.write_fmt(format_args!(
+                "Target histogram must have {} bins, but it has {}",
+                size.expected, size.received
+            )),
+            HistogramError::UnsupportedBitDepth(bit_depth) => f.write_fmt(format_args!(
+                "Bit depth must be in range [1, 16], but it was {}",
+                bit_depth
+            )),
+            HistogramError::UnsupportedLayout(layout) => f.write_fmt(format_args!(
+                "Layout {:?} is not supported for requested operation",
+                layout
+            )),
+            HistogramError::MaskStrideMismatch(size) => f.write_fmt(format_args!(
+                "Mask stride must be at least {}, but it was {}",
+                size.expected, size.received
+            )),
+            HistogramError::MaskSizeMismatch(size) => f.write_fmt(format_args!(
+                "Mask must have at least {} elements, but it was {}",
+                size.expected, size.received
+            )),
+            HistogramError::UnsupportedMaskMode(mode) => f.write_fmt(format_args!(
+                "Masked equalization is not supported in {:?} CLAHE mode",
+                mode
+            )),
+            HistogramError::UnsupportedYuvBitDepth(bit_depth, matrix) => match matrix {
+                Some(matrix) => f.write_fmt(format_args!(
+                    "Bit depth {} is not supported by {:?} YUV conversion",
+                    bit_depth, matrix
+                )),
+                None => f.write_fmt(format_args!(
+                    "YUV bit depth must be in range [8, 16], but it was {}",
+                    bit_depth
+                )),
+            },
+            HistogramError::UnsupportedVideoMode(mode) => f.write_fmt(format_args!(
+                "Video equalization is not supported in {:?} CLAHE mode",
+                mode
+            )),
+            HistogramError::InvalidSmoothing(smoothing) => f.write_fmt(format_args!(
+                "Smoothing must be in range [0, 1), but it was {}",
+                smoothing
+            )),
+            HistogramError::Color(err) => f.write_fmt(format_args!("{}", err)),
+            HistogramError::Yuv(err) => f.write_fmt(format_args!("{}", err)),
+        }
+    }
+}
+
+impl Error for HistogramError {}
+
+impl From<ColorError> for HistogramError {
+    fn from(value: ColorError) -> Self {
+        HistogramError::Color(value)
+    }
+}
+
+impl From<YuvError> for HistogramError {
+    fn from(value: YuvError) -> Self {
+        HistogramError::Yuv(value)
+    }
+}
+
+#[inline]
+pub(crate) fn check_image_size(width: u32, height: u32) -> Result<(), HistogramError> {
+    if width == 0 || height == 0 {
+        return Err(HistogramError::ZeroBaseSize);
+    }
+    Ok(())
+}
+
+/// Checks that source has valid stride and enough elements for `height` rows, stride in elements
+#[inline]
+pub(crate) fn check_source<T>(
+    src: &[T],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    channels: usize,
+) -> Result<(), HistogramError> {
+    let min_stride = width as usize * channels;
+    if (src_stride as usize) < min_stride {
+        return Err(HistogramError::SourceStrideMismatch(MismatchedSize {
+            expected: min_stride,
+            received: src_stride as usize,
+        }));
+    }
+    let min_size = src_stride as usize * height as usize;
+    if src.len() < min_size {
+        return Err(HistogramError::SourceSizeMismatch(MismatchedSize {
+            expected: min_size,
+            received: src.len(),
+        }));
+    }
+    Ok(())
+}
+
+/// Checks that destination has valid stride and enough elements for `height` rows, stride in elements
+#[inline]
+pub(crate) fn check_destination<T>(
+    dst: &[T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    channels: usize,
+) -> Result<(), HistogramError> {
+    let min_stride = width as usize * channels;
+    if (dst_stride as usize) < min_stride {
+        return Err(HistogramError::DestinationStrideMismatch(MismatchedSize {
+            expected: min_stride,
+            received: dst_stride as usize,
+        }));
+    }
+    let min_size = dst_stride as usize * height as usize;
+    if dst.len() < min_size {
+        return Err(HistogramError::DestinationSizeMismatch(MismatchedSize {
+            expected: min_size,
+            received: dst.len(),
+        }));
+    }
+    Ok(())
+}
+
+/// Grid must be non zero, grid larger than the image is reduced by tiling to one pixel per tile
+#[inline]
+pub(crate) fn check_grid_size(grid_size: ClaheGridSize) -> Result<(), HistogramError> {
+    if grid_size.w == 0 || grid_size.h == 0 {
+        return Err(HistogramError::InvalidGridSize(grid_size));
+    }
+    Ok(())
+}
+
+/// Smoothing is the weight of previous frames, with 1 the first frame would be kept forever
+#[inline]
+pub(crate) fn check_smoothing(smoothing: f32) -> Result<(), HistogramError> {
+    if !(0f32..1f32).contains(&smoothing) {
+        return Err(HistogramError::InvalidSmoothing(smoothing));
+    }
+    Ok(())
+}
+
+/// Lightness is stored in `u16` so at most 65536 bins can be used
+#[inline]
+pub(crate) fn check_bins_count(bins_count: usize) -> Result<(), HistogramError> {
+    if !(2..=65536).contains(&bins_count) {
+        return Err(HistogramError::InvalidBinsCount(bins_count));
+    }
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn check_bit_depth(bit_depth: u32) -> Result<(), HistogramError> {
+    if !(1..=16).contains(&bit_depth) {
+        return Err(HistogramError::UnsupportedBitDepth(bit_depth));
+    }
+    Ok(())
+}
+
+/// Luma of YUV planes may have any bit depth in [8, 16], conversion from RGB is available
+/// for 10, 12, 14 and 16 bits, YCgCo only for 10 and 12
+#[inline]
+pub(crate) fn check_yuv_bit_depth(
+    bit_depth: u32,
+    matrix: Option<YuvMatrix>,
+) -> Result<(), HistogramError> {
+    let supported = match matrix {
+        None => (8..=16).contains(&bit_depth),
+        Some(YuvMatrix::YCgCo) => bit_depth == 10 || bit_depth == 12,
+        Some(_) => matches!(bit_depth, 10 | 12 | 14 | 16),
+    };
+    if !supported {
+        return Err(HistogramError::UnsupportedYuvBitDepth(bit_depth, matrix));
+    }
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn check_region(
+    region: ImageRegion,
+    width: u32,
+    height: u32,
+) -> Result<(), HistogramError> {
+    if region.width == 0
+        || region.height == 0
+        || region.x as u64 + region.width as u64 > width as u64
+        || region.y as u64 + region.height as u64 > height as u64
+    {
+        return Err(HistogramError::InvalidRegion(region));
+    }
+    Ok(())
+}
+
+/// Target histogram must have expected bins count and at least one sample
+#[inline]
+pub(crate) fn check_target_histogram(
+    target: &ImageHistogram,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    if target.bins.len() != bins_count {
+        return Err(HistogramError::HistogramBinsMismatch(MismatchedSize {
+            expected: bins_count,
+            received: target.bins.len(),
+        }));
+    }
+    if target.bins.iter().all(|&x| x == 0) {
+        return Err(HistogramError::EmptyHistogram);
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::context::{working_buffer, EqualizerContext};
+use crate::error::{
+    check_bins_count, check_bit_depth, check_destination, check_image_size, check_source,
+    HistogramError,
+};
+use crate::hist_support::equalization_lut;
+use crate::mask::HistogramMask;
+use colorutils_rs::TransferFunction;
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
+
+#[allow(dead_code)]
+pub(crate) fn equalize_histogram_impl_proxy<T: Copy, const CHANNELS: usize>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+    destructuring: fn(
+        &[T],
+        u32,
+        &mut [u16],
+        u32,
+        &mut [f32],
+        u32,
+        u32,
+        f32,
+        u32,
+        TransferFunction,
+    ) -> f32,
+    structuring: fn(&[u16], u32, &[f32], &mut [T], u32, u32, u32, f32, u32, TransferFunction),
+    mask: Option<HistogramMask<'_>>,
+    context: &mut EqualizerContext,
+) -> Result<(), HistogramError> {
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, CHANNELS)?;
+    check_destination(dst, dst_stride, width, height, CHANNELS)?;
+    check_bins_count(bins_count)?;
+    check_bit_depth(bit_depth)?;
+
+    let EqualizerContext {
+        image,
+        color_planes,
+        tiles,
+        ..
+    } = context;
+    let hsv_image = working_buffer(image, width as usize * height as usize);
+    let hsv_stride = width as usize;
+
+    let color_planes = working_buffer(
+        color_planes,
+        width as usize * height as usize * (CHANNELS - 1),
+    );
+
+    let headroom = destructuring(
+        src,
+        src_stride,
+        hsv_image,
+        hsv_stride as u32 * std::mem::size_of::<u16>() as u32,
+        color_planes,
+        width,
+        height,
+        (bins_count - 1) as f32,
+        bit_depth,
+        transfer_function,
+    );
+
+    let bins = equalization_lut::<0, 1, u16>(
+        hsv_image,
+        hsv_stride as u32,
+        width,
+        height,
+        bins_count,
+        mask,
+        &mut tiles.histograms,
+    );
+
+    let max_bins = bins_count - 1;
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = hsv_image.par_chunks_exact_mut(hsv_stride);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = hsv_image.chunks_exact_mut(hsv_stride);
+    }
+    iter.for_each(|hsv_image| {
+        for hsv in hsv_image.iter_mut() {
+            let value = (*hsv).min(max_bins as u16) as usize;
+            *hsv = unsafe { *bins.get_unchecked(value) } as u16;
+        }
+    });
+
+    structuring(
+        hsv_image,
+        hsv_stride as u32 * std::mem::size_of::<u16>() as u32,
+        color_planes,
+        dst,
+        dst_stride,
+        width,
+        height,
+        (bins_count - 1) as f32 / headroom,
+        bit_depth,
+        transfer_function,
+    );
+
+    Ok(())
+}
@@ -1,21 +1,34 @@
-use colorutils_rs::{
-    bgra_to_hsl, bgra_to_hsv, hsl_to_bgra, hsl_to_rgb, hsl_to_rgba, hsv_to_bgra, hsv_to_rgb,
-    hsv_to_rgba, rgb_to_hsl, rgb_to_hsv, rgba_to_hsl, rgba_to_hsv,
-};
+use colorutils_rs::TransferFunction;
 
+use crate::context::EqualizerContext;
+use crate::error::HistogramError;
+use crate::hist_equal_call_proxy::equalize_histogram_impl_proxy;
 use crate::hist_equal_impl::equalize_histogram_impl;
-use crate::lab::{bgra_to_lab, lab_to_bgra, lab_to_rgb, lab_to_rgba, rgb_to_lab, rgba_to_lab};
-use crate::luv::{bgra_to_luv, luv_to_bgra, luv_to_rgb, luv_to_rgba, rgb_to_luv, rgba_to_luv};
+use crate::hsv::{
+    bgr_to_hsl, bgr_to_hsv, bgra_to_hsl, bgra_to_hsv, generic_image_to_hsv, hsl_to_bgr,
+    hsl_to_bgra, hsl_to_rgb, hsl_to_rgba, hsv_to_bgr, hsv_to_bgra, hsv_to_generic_image,
+    hsv_to_rgb, hsv_to_rgba, rgb_to_hsl, rgb_to_hsv, rgba_to_hsl, rgba_to_hsv, HsvTarget,
+};
+use crate::image_configuration::ImageConfiguration;
+use crate::lab::{
+    bgr_to_lab, bgra_to_lab, generic_image_to_lab_planar, lab_planar_to_generic_image, lab_to_bgr,
+    lab_to_bgra, lab_to_rgb, lab_to_rgba, rgb_to_lab, rgba_to_lab,
+};
+use crate::luv::{
+    bgr_to_luv, bgra_to_luv, generic_image_to_luv_planar, luv_planar_to_generic_image, luv_to_bgr,
+    luv_to_bgra, luv_to_rgb, luv_to_rgba, rgb_to_luv, rgba_to_luv,
+};
 
 /// Converts image to HSV, performs histogram equalization and reverts back into RGB
 ///
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_hsv_rgb(
     src: &[u8],
     src_stride: u32,
@@ -24,10 +37,54 @@ pub fn hist_equal_hsv_rgb(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<3, 2>(
-        src, src_stride, dst, dst_stride, width, height, bins_count, rgb_to_hsv, hsv_to_rgb,
-    );
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        rgb_to_hsv,
+        hsv_to_rgb,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSV, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_hsv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl::<3, 2>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bgr_to_hsv,
+        hsv_to_bgr,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs histogram equalization and reverts back into RGBA
@@ -35,10 +92,11 @@ pub fn hist_equal_hsv_rgb(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_hsv_rgba(
     src: &[u8],
     src_stride: u32,
@@ -47,7 +105,7 @@ pub fn hist_equal_hsv_rgba(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 2>(
         src,
         src_stride,
@@ -58,7 +116,9 @@ pub fn hist_equal_hsv_rgba(
         bins_count,
         rgba_to_hsv,
         hsv_to_rgba,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSV, performs histogram equalization and reverts back into BGRA
@@ -66,10 +126,11 @@ pub fn hist_equal_hsv_rgba(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_hsv_bgra(
     src: &[u8],
     src_stride: u32,
@@ -78,7 +139,7 @@ pub fn hist_equal_hsv_bgra(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 2>(
         src,
         src_stride,
@@ -89,7 +150,9 @@ pub fn hist_equal_hsv_bgra(
         bins_count,
         bgra_to_hsv,
         hsv_to_bgra,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs histogram equalization and reverts back into RGB
@@ -97,10 +160,11 @@ pub fn hist_equal_hsv_bgra(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_hsl_rgb(
     src: &[u8],
     src_stride: u32,
@@ -109,10 +173,54 @@ pub fn hist_equal_hsl_rgb(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl::<3, 2>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        rgb_to_hsl,
+        hsl_to_rgb,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSL, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_hsl_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<3, 2>(
-        src, src_stride, dst, dst_stride, width, height, bins_count, rgb_to_hsl, hsl_to_rgb,
-    );
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bgr_to_hsl,
+        hsl_to_bgr,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs histogram equalization and reverts back into RGBA
@@ -120,10 +228,11 @@ pub fn hist_equal_hsl_rgb(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_hsl_rgba(
     src: &[u8],
     src_stride: u32,
@@ -132,7 +241,7 @@ pub fn hist_equal_hsl_rgba(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 2>(
         src,
         src_stride,
@@ -143,7 +252,9 @@ pub fn hist_equal_hsl_rgba(
         bins_count,
         rgba_to_hsl,
         hsl_to_rgba,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to HSL, performs histogram equalization and reverts back into BGRA
@@ -151,10 +262,11 @@ pub fn hist_equal_hsl_rgba(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_hsl_bgra(
     src: &[u8],
     src_stride: u32,
@@ -163,7 +275,7 @@ pub fn hist_equal_hsl_bgra(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 2>(
         src,
         src_stride,
@@ -174,7 +286,9 @@ pub fn hist_equal_hsl_bgra(
         bins_count,
         bgra_to_hsl,
         hsl_to_bgra,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs histogram equalization and reverts back into RGB
@@ -182,10 +296,11 @@ pub fn hist_equal_hsl_bgra(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_lab_rgb(
     src: &[u8],
     src_stride: u32,
@@ -194,10 +309,54 @@ pub fn hist_equal_lab_rgb(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<3, 0>(
-        src, src_stride, dst, dst_stride, width, height, bins_count, rgb_to_lab, lab_to_rgb,
-    );
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        rgb_to_lab,
+        lab_to_rgb,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LAB, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_lab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl::<3, 0>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bgr_to_lab,
+        lab_to_bgr,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs histogram equalization and reverts back into RGBA
@@ -205,10 +364,11 @@ pub fn hist_equal_lab_rgb(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_lab_rgba(
     src: &[u8],
     src_stride: u32,
@@ -217,7 +377,7 @@ pub fn hist_equal_lab_rgba(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 0>(
         src,
         src_stride,
@@ -228,7 +388,9 @@ pub fn hist_equal_lab_rgba(
         bins_count,
         rgba_to_lab,
         lab_to_rgba,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LAB, performs histogram equalization and reverts back into BGRA
@@ -236,10 +398,11 @@ pub fn hist_equal_lab_rgba(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_lab_bgra(
     src: &[u8],
     src_stride: u32,
@@ -248,7 +411,7 @@ pub fn hist_equal_lab_bgra(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 0>(
         src,
         src_stride,
@@ -259,7 +422,9 @@ pub fn hist_equal_lab_bgra(
         bins_count,
         bgra_to_lab,
         lab_to_bgra,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LUV, performs histogram equalization and reverts back into RGB
@@ -267,10 +432,11 @@ pub fn hist_equal_lab_bgra(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_luv_rgb(
     src: &[u8],
     src_stride: u32,
@@ -279,10 +445,54 @@ pub fn hist_equal_luv_rgb(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl::<3, 0>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        rgb_to_luv,
+        luv_to_rgb,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LUV, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_luv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<3, 0>(
-        src, src_stride, dst, dst_stride, width, height, bins_count, rgb_to_luv, luv_to_rgb,
-    );
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bgr_to_luv,
+        luv_to_bgr,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LUV, performs histogram equalization and reverts back into RGBA
@@ -290,10 +500,11 @@ pub fn hist_equal_luv_rgb(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_luv_rgba(
     src: &[u8],
     src_stride: u32,
@@ -302,7 +513,7 @@ pub fn hist_equal_luv_rgba(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 0>(
         src,
         src_stride,
@@ -313,7 +524,9 @@ pub fn hist_equal_luv_rgba(
         bins_count,
         rgba_to_luv,
         luv_to_rgba,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
 
 /// Converts image to LUV, performs histogram equalization and reverts back into BGRA
@@ -321,10 +534,11 @@ pub fn hist_equal_luv_rgba(
 /// # Arguments
 ///
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_luv_bgra(
     src: &[u8],
     src_stride: u32,
@@ -333,7 +547,7 @@ pub fn hist_equal_luv_bgra(
     width: u32,
     height: u32,
     bins_count: usize,
-) {
+) -> Result<(), HistogramError> {
     equalize_histogram_impl::<4, 0>(
         src,
         src_stride,
@@ -344,5 +558,647 @@ pub fn hist_equal_luv_bgra(
         bins_count,
         bgra_to_luv,
         luv_to_bgra,
-    );
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs histogram equalization and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsv_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsv_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs histogram equalization and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsv_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSV, performs histogram equalization and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsv_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs histogram equalization and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsl_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsl_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs histogram equalization and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsl_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to HSL, performs histogram equalization and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_hsl_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs histogram equalization and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_lab_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Rgb as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_lab_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Bgr as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs histogram equalization and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_lab_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Rgba as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LAB, performs histogram equalization and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_lab_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u16, { ImageConfiguration::Bgra as u8 }>,
+        lab_planar_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LUV, performs histogram equalization and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_luv_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u16, { ImageConfiguration::Rgb as u8 }>,
+        luv_planar_to_generic_image::<u16, { ImageConfiguration::Rgb as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LUV, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_luv_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u16, { ImageConfiguration::Bgr as u8 }>,
+        luv_planar_to_generic_image::<u16, { ImageConfiguration::Bgr as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LUV, performs histogram equalization and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_luv_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u16, { ImageConfiguration::Rgba as u8 }>,
+        luv_planar_to_generic_image::<u16, { ImageConfiguration::Rgba as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to LUV, performs histogram equalization and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_luv_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u16, { ImageConfiguration::Bgra as u8 }>,
+        luv_planar_to_generic_image::<u16, { ImageConfiguration::Bgra as u8 }>,
+        None,
+        &mut EqualizerContext::default(),
+    )
 }
@@ -0,0 +1,58 @@
+use crate::error::{check_bit_depth, HistogramError};
+use crate::hist_equal_gray_impl::equalize_histogram_gray_impl;
+
+/// Performs histogram equalization on single channel (gray) image
+///
+/// For optimization purposes gray histogram bins always 256
+///
+/// # Arguments
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_gray(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+) -> Result<(), HistogramError> {
+    equalize_histogram_gray_impl(src, src_stride, dst, dst_stride, width, height, 256)
+}
+
+/// Performs histogram equalization on single channel (gray) high bit depth image
+///
+/// Histogram bins always `1 << bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_gray_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+) -> Result<(), HistogramError> {
+    check_bit_depth(bit_depth)?;
+    equalize_histogram_gray_impl(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        1usize << bit_depth,
+    )
+}
@@ -0,0 +1,316 @@
+use crate::context::EqualizerContext;
+use crate::error::HistogramError;
+use crate::hist_equal_call_proxy::equalize_histogram_impl_proxy;
+use crate::oklab::{
+    bgr_to_oklab, bgra_to_oklab, oklab_to_bgr, oklab_to_bgra, oklab_to_rgb, oklab_to_rgba,
+    rgb_to_oklab, rgba_to_oklab,
+};
+use colorutils_rs::TransferFunction;
+
+/// Converts image to oklab, performs histogram equalization and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_oklab_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        8,
+        TransferFunction::Srgb,
+        rgb_to_oklab,
+        oklab_to_rgb,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_oklab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        8,
+        TransferFunction::Srgb,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, performs histogram equalization and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_oklab_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        8,
+        TransferFunction::Srgb,
+        rgba_to_oklab,
+        oklab_to_rgba,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, performs histogram equalization and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `bins_count` - Histogram bins, default is 128
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_oklab_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        8,
+        TransferFunction::Srgb,
+        bgra_to_oklab,
+        oklab_to_bgra,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs histogram equalization and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_oklab_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        rgb_to_oklab,
+        oklab_to_rgb,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs histogram equalization and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_oklab_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs histogram equalization and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_oklab_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        rgba_to_oklab,
+        oklab_to_rgba,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts high bit depth image to oklab, performs histogram equalization and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `bins_count` - Histogram bins, up to `1 << bit_depth` keeps full precision
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_oklab_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    equalize_histogram_impl_proxy::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        bins_count,
+        bit_depth,
+        TransferFunction::Srgb,
+        bgra_to_oklab,
+        oklab_to_bgra,
+        None,
+        &mut EqualizerContext::default(),
+    )
+}
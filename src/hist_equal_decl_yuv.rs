@@ -1,10 +1,12 @@
-use yuv::{
-    bgra_to_ycgco444, rgb_to_ycgco444, rgba_to_ycgco444, ycgco444_alpha_to_bgra,
-    ycgco444_alpha_to_rgba,
-};
-
-use crate::clahe_declarations_yuv::ycgco444_skip_alpha_to_rgb;
+use crate::context::YuvScratch;
+use crate::error::{check_yuv_bit_depth, HistogramError};
 use crate::hist_equal_yuv_impl::equalize_histogram_yuv_impl;
+use crate::image_configuration::ImageConfiguration;
+use crate::yuv_support::{
+    image_to_yuv444, image_to_yuv444_u16, yuv444_to_image, yuv444_to_image_u16, YuvMatrix,
+    YuvOptions,
+};
+use yuv::YuvRange;
 
 /// Converts image to YUV, performs histogram equalization and reverts back into RGB.
 ///
@@ -12,10 +14,11 @@ use crate::hist_equal_yuv_impl::equalize_histogram_yuv_impl;
 ///
 /// # Arguments
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_yuv_rgb(
     src: &[u8],
     src_stride: u32,
@@ -23,17 +26,54 @@ pub fn hist_equal_yuv_rgb(
     dst_stride: u32,
     width: u32,
     height: u32,
-) {
-    equalize_histogram_yuv_impl::<3>(
+) -> Result<(), HistogramError> {
+    equalize_histogram_yuv_impl::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Rgb as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgb as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts image to YUV, performs histogram equalization and reverts back into BGR.
+///
+/// For optimization purposes YUV histogram bins always 256
+///
+/// # Arguments
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
+pub fn hist_equal_yuv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+) -> Result<(), HistogramError> {
+    equalize_histogram_yuv_impl::<u8, 3>(
         src,
         src_stride,
         dst,
         dst_stride,
         width,
         height,
-        rgb_to_ycgco444,
-        ycgco444_skip_alpha_to_rgb,
-    );
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Bgr as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgr as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
 
 /// Converts image to YUV, performs histogram equalization and reverts back into RGBA
@@ -42,10 +82,11 @@ pub fn hist_equal_yuv_rgb(
 ///
 /// # Arguments
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_yuv_rgba(
     src: &[u8],
     src_stride: u32,
@@ -53,17 +94,20 @@ pub fn hist_equal_yuv_rgba(
     dst_stride: u32,
     width: u32,
     height: u32,
-) {
-    equalize_histogram_yuv_impl::<4>(
+) -> Result<(), HistogramError> {
+    equalize_histogram_yuv_impl::<u8, 4>(
         src,
         src_stride,
         dst,
         dst_stride,
         width,
         height,
-        rgba_to_ycgco444,
-        ycgco444_alpha_to_rgba,
-    );
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Rgba as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgba as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
 
 /// Converts image to YUV, performs histogram equalization and reverts back into BGRA
@@ -72,10 +116,11 @@ pub fn hist_equal_yuv_rgba(
 ///
 /// # Arguments
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function panics if the lengths of the planes or the input data are not valid based
-/// on the specified width, height, and strides
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+#[deprecated(note = "use Equalizer")]
 pub fn hist_equal_yuv_bgra(
     src: &[u8],
     src_stride: u32,
@@ -83,15 +128,206 @@ pub fn hist_equal_yuv_bgra(
     dst_stride: u32,
     width: u32,
     height: u32,
-) {
-    equalize_histogram_yuv_impl::<4>(
+) -> Result<(), HistogramError> {
+    equalize_histogram_yuv_impl::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        YuvOptions::default(),
+        image_to_yuv444::<{ ImageConfiguration::Bgra as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgra as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth RGB image to YUV, performs histogram equalization and reverts back into RGB.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_yuv_rgb_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    equalize_histogram_yuv_impl::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Rgb as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Rgb as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth BGR image to YUV, performs histogram equalization and reverts back into BGR.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_yuv_bgr_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    equalize_histogram_yuv_impl::<u16, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Bgr as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Bgr as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth RGBA image to YUV, performs histogram equalization and reverts back into RGBA.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_yuv_rgba_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    equalize_histogram_yuv_impl::<u16, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Rgba as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Rgba as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
+}
+
+/// Converts high bit depth BGRA image to YUV, performs histogram equalization and reverts back into BGRA.
+///
+/// Histogram has a bin for every luma level of `yuv_range` at `bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported 10, 12, 14 and 16, YCgCo supports only 10 and 12
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_equal_yuv_bgra_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    check_yuv_bit_depth(bit_depth, Some(yuv_matrix))?;
+    equalize_histogram_yuv_impl::<u16, 4>(
         src,
         src_stride,
         dst,
         dst_stride,
         width,
         height,
-        bgra_to_ycgco444,
-        ycgco444_alpha_to_bgra,
-    );
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth,
+        },
+        image_to_yuv444_u16::<{ ImageConfiguration::Bgra as u8 }>,
+        yuv444_to_image_u16::<{ ImageConfiguration::Bgra as u8 }>,
+        None,
+        &mut YuvScratch::default(),
+    )
 }
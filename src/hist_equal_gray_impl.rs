@@ -0,0 +1,93 @@
+use crate::dispatch::HistogramSample;
+use crate::error::{
+    check_bins_count, check_destination, check_image_size, check_source, HistogramError,
+};
+use crate::hist_support::{equalization_lut, HistogramScratch};
+use num_traits::{AsPrimitive, FromPrimitive};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
+
+#[allow(dead_code)]
+pub(crate) fn equalize_histogram_gray_impl<
+    T: Copy + Ord + FromPrimitive + Default + HistogramSample + Send + Sync + 'static,
+>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+) -> Result<(), HistogramError>
+where
+    u64: AsPrimitive<T>,
+{
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, 1)?;
+    check_destination(dst, dst_stride, width, height, 1)?;
+    check_bins_count(bins_count)?;
+
+    for (dst, src) in dst
+        .chunks_mut(dst_stride as usize)
+        .zip(src.chunks(src_stride as usize))
+        .take(height as usize)
+    {
+        dst[..width as usize].copy_from_slice(&src[..width as usize]);
+    }
+
+    equalize_histogram_gray_in_place(
+        dst,
+        dst_stride as usize,
+        width as usize,
+        height as usize,
+        bins_count,
+        &mut HistogramScratch::default(),
+    );
+
+    Ok(())
+}
+
+/// Performs histogram equalization of single channel `plane` in place,
+/// sizes must be already checked, histogram is counted in `histograms`
+pub(crate) fn equalize_histogram_gray_in_place<
+    T: Copy + Ord + FromPrimitive + Default + HistogramSample + Send + Sync + 'static,
+>(
+    plane: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+    bins_count: usize,
+    histograms: &mut HistogramScratch,
+) where
+    u64: AsPrimitive<T>,
+{
+    let bins = equalization_lut::<0, 1, T>(
+        plane,
+        stride as u32,
+        width as u32,
+        height as u32,
+        bins_count,
+        None,
+        histograms,
+    );
+    let max_bins = bins_count - 1;
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = plane.par_chunks_mut(stride).take(height);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = plane.chunks_mut(stride).take(height);
+    }
+    iter.for_each(|row| {
+        for value in row.iter_mut().take(width) {
+            let index = Into::<usize>::into(*value).min(max_bins);
+            let bin_value = unsafe { *bins.get_unchecked(index) };
+            *value = bin_value.as_();
+        }
+    });
+}
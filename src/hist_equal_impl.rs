@@ -1,5 +1,14 @@
-use crate::hist_support::{cdf, make_histogram_region, minmax};
+use crate::context::{working_buffer, EqualizerContext};
+use crate::error::{
+    check_bins_count, check_destination, check_image_size, check_source, HistogramError,
+};
+use crate::hist_support::equalization_lut;
+use crate::mask::HistogramMask;
 use colorutils_rs::{BufferStore, ColorError, ImageBuffer, ImageBufferMut};
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
 
 #[allow(dead_code)]
 pub(crate) fn equalize_histogram_impl<const CHANNELS: usize, const CHANNEL_POSITION: usize>(
@@ -20,12 +29,16 @@ pub(crate) fn equalize_histogram_impl<const CHANNELS: usize, const CHANNEL_POSIT
         &mut ImageBufferMut<'_, u8>,
         f32,
     ) -> Result<(), ColorError>,
-) {
-    if bins_count <= 1 {
-        panic!("Bins count must be more than one");
-    }
+    mask: Option<HistogramMask<'_>>,
+    context: &mut EqualizerContext,
+) -> Result<(), HistogramError> {
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, CHANNELS)?;
+    check_destination(dst, dst_stride, width, height, CHANNELS)?;
+    check_bins_count(bins_count)?;
 
-    let mut hsv_image: Vec<u16> = vec![0u16; width as usize * height as usize * CHANNELS];
+    let EqualizerContext { image, tiles, .. } = context;
+    let hsv_image = working_buffer(image, width as usize * height as usize * CHANNELS);
     let hsv_stride = width as usize * CHANNELS;
 
     let src_image = ImageBuffer {
@@ -36,60 +49,41 @@ pub(crate) fn equalize_histogram_impl<const CHANNELS: usize, const CHANNEL_POSIT
         channels: CHANNELS as u32,
     };
     let mut dst_image_hsv = ImageBufferMut {
-        data: BufferStore::Borrowed(&mut hsv_image),
+        data: BufferStore::Borrowed(hsv_image),
         stride: hsv_stride as u32,
         width,
         height,
-        channels: 3,
+        channels: CHANNELS as u32,
     };
 
-    _ = destructuring(&src_image, &mut dst_image_hsv, (bins_count - 1) as f32);
-    let histogram = make_histogram_region::<CHANNEL_POSITION, CHANNELS, u16>(
-        &hsv_image,
+    destructuring(&src_image, &mut dst_image_hsv, (bins_count - 1) as f32)?;
+    let bins = equalization_lut::<CHANNEL_POSITION, CHANNELS, u16>(
+        hsv_image,
         hsv_stride as u32,
-        0,
         width,
-        0,
         height,
         bins_count,
+        mask,
+        &mut tiles.histograms,
     );
-    let mut bins = histogram.bins;
-
-    cdf(&mut bins);
-
-    let pixels_count = width * height;
-
-    let (min_bin, _) = minmax(&bins);
-
-    let distance_r = 1f64 / (pixels_count as f64 - min_bin as f64);
 
     let max_bins = bins_count - 1;
 
-    if distance_r != 0f64 {
-        for i in 0..bins_count {
-            unsafe {
-                *bins.get_unchecked_mut(i) = (max_bins as f64
-                    * (*bins.get_unchecked(i) as f64 - min_bin as f64)
-                    * distance_r)
-                    .round()
-                    .min(max_bins as f64)
-                    .max(0f64) as u64;
-            }
-        }
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = hsv_image.par_chunks_exact_mut(hsv_stride);
     }
-
-    let mut y_shift = 0usize;
-    for _ in 0usize..height as usize {
-        for x in 0usize..width as usize {
-            let px = x * CHANNELS;
-            unsafe {
-                let value = *hsv_image.get_unchecked(y_shift + px + CHANNEL_POSITION) as usize;
-                let bin_value = *bins.get_unchecked(value);
-                *hsv_image.get_unchecked_mut(y_shift + px + CHANNEL_POSITION) = bin_value as u16;
-            }
-        }
-        y_shift += hsv_stride;
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = hsv_image.chunks_exact_mut(hsv_stride);
     }
+    iter.for_each(|hsv_row| {
+        for hsv in hsv_row.chunks_exact_mut(CHANNELS) {
+            let value = hsv[CHANNEL_POSITION].min(max_bins as u16) as usize;
+            hsv[CHANNEL_POSITION] = unsafe { *bins.get_unchecked(value) } as u16;
+        }
+    });
 
     let mut dst_image = ImageBufferMut {
         data: BufferStore::Borrowed(dst),
@@ -99,12 +93,14 @@ pub(crate) fn equalize_histogram_impl<const CHANNELS: usize, const CHANNEL_POSIT
         channels: CHANNELS as u32,
     };
     let src_image_hsv = ImageBuffer {
-        data: std::borrow::Cow::Borrowed(&hsv_image),
+        data: std::borrow::Cow::Borrowed(hsv_image),
         stride: hsv_stride as u32,
         width,
         height,
-        channels: 3,
+        channels: CHANNELS as u32,
     };
 
-    _ = structuring(&src_image_hsv, &mut dst_image, (bins_count - 1) as f32);
+    structuring(&src_image_hsv, &mut dst_image, (bins_count - 1) as f32)?;
+
+    Ok(())
 }
@@ -1,98 +1,100 @@
-use crate::hist_support::{cdf, make_histogram_region, minmax};
-use yuv::{BufferStoreMut, YuvError, YuvPlanarImageMut, YuvPlanarImageWithAlpha, YuvRange};
+use crate::context::{working_buffer, YuvScratch};
+use crate::dispatch::HistogramSample;
+use crate::error::{check_destination, check_image_size, check_source, HistogramError};
+use crate::hist_support::equalization_lut;
+use crate::mask::HistogramMask;
+use crate::yuv_support::{alpha_plane, luma_from_levels, luma_levels, luma_to_levels, YuvOptions};
+use num_traits::AsPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+#[cfg(feature = "rayon")]
+use rayon::prelude::ParallelSliceMut;
+use std::fmt::Debug;
+use yuv::{BufferStoreMut, YuvError, YuvPlanarImageMut, YuvPlanarImageWithAlpha};
 
 #[allow(dead_code)]
-pub(crate) fn equalize_histogram_yuv_impl<const CHANNELS: u8>(
-    src: &[u8],
+pub(crate) fn equalize_histogram_yuv_impl<
+    T: Copy + Debug + Default + HistogramSample + AsPrimitive<u32> + Send + Sync + 'static,
+    const CHANNELS: usize,
+>(
+    src: &[T],
     src_stride: u32,
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
-    destructuring: fn(&mut YuvPlanarImageMut<u8>, &[u8], u32, YuvRange) -> Result<(), YuvError>,
-    structuring: fn(&YuvPlanarImageWithAlpha<u8>, &mut [u8], u32, YuvRange) -> Result<(), YuvError>,
-) {
-    let bins_count = 256;
+    yuv_options: YuvOptions,
+    destructuring: fn(&mut YuvPlanarImageMut<T>, &[T], u32, YuvOptions) -> Result<(), YuvError>,
+    structuring: fn(&YuvPlanarImageWithAlpha<T>, &mut [T], u32, YuvOptions) -> Result<(), YuvError>,
+    mask: Option<HistogramMask<'_>>,
+    scratch: &mut YuvScratch<T>,
+) -> Result<(), HistogramError>
+where
+    u32: AsPrimitive<T>,
+    u64: AsPrimitive<T>,
+{
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, CHANNELS)?;
+    check_destination(dst, dst_stride, width, height, CHANNELS)?;
+    let (_, bins_count) = luma_levels(yuv_options.range, yuv_options.bit_depth);
 
-    let y_plane: Vec<u8> = vec![0u8; width as usize * height as usize];
-    let u_plane: Vec<u8> = vec![0u8; width as usize * height as usize];
-    let v_plane: Vec<u8> = vec![0u8; width as usize * height as usize];
-
-    let mut a_plane = if CHANNELS == 4 {
-        vec![0u8; width as usize * height as usize]
-    } else {
-        Vec::new()
-    };
+    let YuvScratch {
+        planes,
+        alpha,
+        tiles,
+    } = scratch;
+    let a_plane = alpha_plane::<T, CHANNELS>(src, src_stride, width, height, alpha);
 
+    let plane_length = width as usize * height as usize;
+    let planes = working_buffer(planes, plane_length * 3);
+    let (y_plane, uv_planes) = planes.split_at_mut(plane_length);
+    let (u_plane, v_plane) = uv_planes.split_at_mut(plane_length);
     let mut planar_image_mut = YuvPlanarImageMut {
-        y_plane: BufferStoreMut::Owned(y_plane),
+        y_plane: BufferStoreMut::Borrowed(y_plane),
         y_stride: width,
-        u_plane: BufferStoreMut::Owned(u_plane),
+        u_plane: BufferStoreMut::Borrowed(u_plane),
         u_stride: width,
-        v_plane: BufferStoreMut::Owned(v_plane),
+        v_plane: BufferStoreMut::Borrowed(v_plane),
         v_stride: width,
         width,
         height,
     };
 
-    destructuring(&mut planar_image_mut, src, src_stride, YuvRange::Full).unwrap();
+    destructuring(&mut planar_image_mut, src, src_stride, yuv_options)?;
 
-    if CHANNELS == 4 {
-        let mut a_shift = 0usize;
-        let mut y_shift = 0usize;
-        for _ in 0usize..height as usize {
-            for x in 0usize..width as usize {
-                unsafe {
-                    *a_plane.get_unchecked_mut(a_shift + x) =
-                        *src.get_unchecked(y_shift + x * 4 + 3);
-                }
-            }
-            y_shift += src_stride as usize;
-            a_shift += width as usize;
-        }
-    }
+    let y_plane = planar_image_mut.y_plane.borrow_mut();
+    let (w, h) = (width as usize, height as usize);
+    luma_to_levels(y_plane, w, w, h, yuv_options.range, yuv_options.bit_depth);
 
-    let histogram = make_histogram_region::<0, 1, u8>(
-        planar_image_mut.y_plane.borrow(),
+    let bins = equalization_lut::<0, 1, T>(
+        y_plane,
         width,
-        0,
         width,
-        0,
         height,
         bins_count,
+        mask,
+        &mut tiles.histograms,
     );
-    let mut bins = histogram.bins;
-
-    cdf(&mut bins);
-
-    let pixels_count = width * height;
+    let max_bins = bins_count - 1;
 
-    let (min_bin, _) = minmax(&bins);
-
-    let distance_r = 1f64 / (pixels_count as f64 - min_bin as f64);
-
-    if distance_r != 0f64 {
-        for i in 0..256usize {
-            unsafe {
-                *bins.get_unchecked_mut(i) =
-                    (255f64 * (*bins.get_unchecked(i) as f64 - min_bin as f64) * distance_r)
-                        .round()
-                        .min(255f64) as u64;
-            }
-        }
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = y_plane.par_chunks_exact_mut(w);
     }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = y_plane.chunks_exact_mut(w);
+    }
+    iter.for_each(|row| {
+        for dst in row.iter_mut() {
+            let value = Into::<usize>::into(*dst).min(max_bins);
+            let bin_value = unsafe { *bins.get_unchecked(value) };
+            *dst = bin_value.as_();
+        }
+    });
 
-    planar_image_mut
-        .y_plane
-        .borrow_mut()
-        .chunks_exact_mut(width as usize)
-        .for_each(|row| {
-            for dst in row.iter_mut() {
-                let value = *dst;
-                let bin_value = unsafe { *bins.get_unchecked(value as usize) };
-                *dst = bin_value as u8;
-            }
-        });
+    luma_from_levels(y_plane, w, w, h, yuv_options.range, yuv_options.bit_depth);
 
     let planar_image = YuvPlanarImageWithAlpha {
         y_plane: planar_image_mut.y_plane.borrow(),
@@ -101,11 +103,13 @@ pub(crate) fn equalize_histogram_yuv_impl<const CHANNELS: u8>(
         u_stride: width,
         v_plane: planar_image_mut.v_plane.borrow(),
         v_stride: width,
-        a_plane: &a_plane,
+        a_plane,
         a_stride: width,
         width,
         height,
     };
 
-    structuring(&planar_image, dst, dst_stride, YuvRange::Full).unwrap();
+    structuring(&planar_image, dst, dst_stride, yuv_options)?;
+
+    Ok(())
 }
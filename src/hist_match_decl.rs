@@ -0,0 +1,1029 @@
+use crate::context::EqualizerContext;
+use crate::error::HistogramError;
+use crate::hist_match_impl::match_histogram_impl_proxy;
+use crate::hist_support::ImageHistogram;
+use crate::hsv::{generic_image_to_hsv, hsv_to_generic_image, HsvTarget};
+use crate::image_configuration::ImageConfiguration;
+use crate::jzazbz::{
+    bgr_to_jzazbz, bgra_to_jzazbz, jzazbz_to_bgr, jzazbz_to_bgra, jzazbz_to_rgb, jzazbz_to_rgba,
+    rgb_to_jzazbz, rgba_to_jzazbz,
+};
+use crate::lab::{generic_image_to_lab_planar, lab_planar_to_generic_image};
+use crate::luv::{generic_image_to_luv_planar, luv_planar_to_generic_image};
+use crate::oklab::{
+    bgr_to_oklab, bgra_to_oklab, oklab_to_bgr, oklab_to_bgra, oklab_to_rgb, oklab_to_rgba,
+    rgb_to_oklab, rgba_to_oklab,
+};
+use crate::oklch::{
+    bgr_to_oklch, bgra_to_oklch, oklch_to_bgr, oklch_to_bgra, oklch_to_rgb, oklch_to_rgba,
+    rgb_to_oklch, rgba_to_oklch,
+};
+use colorutils_rs::TransferFunction;
+
+/// Converts image to HSV, matches lightness histogram to `target` and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsv_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSV, matches lightness histogram to `target` and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSV, matches lightness histogram to `target` and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsv_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSV, matches lightness histogram to `target` and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsv_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSL, matches lightness histogram to `target` and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsl], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsl_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSL, matches lightness histogram to `target` and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsl], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsl_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSL, matches lightness histogram to `target` and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsl], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsl_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to HSL, matches lightness histogram to `target` and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Hsl], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_hsl_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_hsv::<u8, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        hsv_to_generic_image::<u8, { ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LAB, matches lightness histogram to `target` and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Lab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_lab_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u8, { ImageConfiguration::Rgb as u8 }>,
+        lab_planar_to_generic_image::<u8, { ImageConfiguration::Rgb as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LAB, matches lightness histogram to `target` and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Lab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_lab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u8, { ImageConfiguration::Bgr as u8 }>,
+        lab_planar_to_generic_image::<u8, { ImageConfiguration::Bgr as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LAB, matches lightness histogram to `target` and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Lab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_lab_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u8, { ImageConfiguration::Rgba as u8 }>,
+        lab_planar_to_generic_image::<u8, { ImageConfiguration::Rgba as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LAB, matches lightness histogram to `target` and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Lab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_lab_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_lab_planar::<u8, { ImageConfiguration::Bgra as u8 }>,
+        lab_planar_to_generic_image::<u8, { ImageConfiguration::Bgra as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LUV, matches lightness histogram to `target` and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Luv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_luv_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u8, { ImageConfiguration::Rgb as u8 }>,
+        luv_planar_to_generic_image::<u8, { ImageConfiguration::Rgb as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LUV, matches lightness histogram to `target` and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Luv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_luv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u8, { ImageConfiguration::Bgr as u8 }>,
+        luv_planar_to_generic_image::<u8, { ImageConfiguration::Bgr as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LUV, matches lightness histogram to `target` and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Luv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_luv_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u8, { ImageConfiguration::Rgba as u8 }>,
+        luv_planar_to_generic_image::<u8, { ImageConfiguration::Rgba as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to LUV, matches lightness histogram to `target` and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Luv], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_luv_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        generic_image_to_luv_planar::<u8, { ImageConfiguration::Bgra as u8 }>,
+        luv_planar_to_generic_image::<u8, { ImageConfiguration::Bgra as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, matches lightness histogram to `target` and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklab_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        rgb_to_oklab,
+        oklab_to_rgb,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, matches lightness histogram to `target` and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklab_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        bgr_to_oklab,
+        oklab_to_bgr,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, matches lightness histogram to `target` and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklab_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        rgba_to_oklab,
+        oklab_to_rgba,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklab, matches lightness histogram to `target` and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklab], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklab_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        bgra_to_oklab,
+        oklab_to_bgra,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklch, matches lightness histogram to `target` and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklch], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklch_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        rgb_to_oklch,
+        oklch_to_rgb,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklch, matches lightness histogram to `target` and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklch], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklch_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        bgr_to_oklch,
+        oklch_to_bgr,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklch, matches lightness histogram to `target` and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklch], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklch_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        rgba_to_oklch,
+        oklch_to_rgba,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to oklch, matches lightness histogram to `target` and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Oklch], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_oklch_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        bgra_to_oklch,
+        oklch_to_bgra,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to jzazbz, matches lightness histogram to `target` and reverts back into RGB
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Jzazbz], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_jzazbz_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        rgb_to_jzazbz,
+        jzazbz_to_rgb,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to jzazbz, matches lightness histogram to `target` and reverts back into BGR
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Jzazbz], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_jzazbz_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        bgr_to_jzazbz,
+        jzazbz_to_bgr,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to jzazbz, matches lightness histogram to `target` and reverts back into RGBA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Jzazbz], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_jzazbz_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        rgba_to_jzazbz,
+        jzazbz_to_rgba,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to jzazbz, matches lightness histogram to `target` and reverts back into BGRA
+///
+/// # Arguments
+///
+/// * `target` - Desired lightness histogram, e.g. [crate::lightness_histogram] of reference image
+///   in [crate::EqualizationColorSpace::Jzazbz], its bins count is used for matching
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_jzazbz_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_impl_proxy::<u8, 4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        8,
+        TransferFunction::Srgb,
+        bgra_to_jzazbz,
+        jzazbz_to_bgra,
+        &mut EqualizerContext::default(),
+    )
+}
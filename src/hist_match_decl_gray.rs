@@ -0,0 +1,78 @@
+use crate::context::EqualizerContext;
+use crate::error::{check_bit_depth, HistogramError};
+use crate::hist_match_impl::match_histogram_gray_impl;
+use crate::hist_support::ImageHistogram;
+
+/// Matches histogram of single channel (gray) image to `target`
+///
+/// For optimization purposes gray histogram bins always 256
+///
+/// # Arguments
+///
+/// * `target` - Desired histogram with 256 bins, e.g. [crate::channel_histogram] of reference image
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_gray(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    match_histogram_gray_impl(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        256,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Matches histogram of single channel (gray) high bit depth image to `target`
+///
+/// Histogram bins always `1 << bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `dst_stride` - Elements per row of `dst`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `target` - Desired histogram with `1 << bit_depth` bins, e.g. [crate::channel_histogram_u16]
+///   of reference image
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_gray_u16(
+    src: &[u16],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    target: &ImageHistogram,
+) -> Result<(), HistogramError> {
+    check_bit_depth(bit_depth)?;
+    match_histogram_gray_impl(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        1usize << bit_depth,
+        &mut EqualizerContext::default(),
+    )
+}
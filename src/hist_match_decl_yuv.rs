@@ -0,0 +1,187 @@
+use crate::context::EqualizerContext;
+use crate::error::HistogramError;
+use crate::hist_match_impl::match_histogram_yuv_impl;
+use crate::hist_support::ImageHistogram;
+use crate::image_configuration::ImageConfiguration;
+use crate::yuv_support::{image_to_yuv444, yuv444_to_image, YuvMatrix, YuvOptions};
+use yuv::YuvRange;
+
+/// Converts image to YUV, matches luma histogram to `target` and reverts back into RGB.
+///
+/// Histogram has a bin for every luma level of `yuv_range`
+///
+/// # Arguments
+///
+/// * `target` - Desired luma histogram, e.g. [crate::lightness_histogram_yuv] of reference image
+///   with the same matrix and range
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_yuv_rgb(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    match_histogram_yuv_impl::<3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth: 8,
+        },
+        image_to_yuv444::<{ ImageConfiguration::Rgb as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgb as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to YUV, matches luma histogram to `target` and reverts back into BGR.
+///
+/// Histogram has a bin for every luma level of `yuv_range`
+///
+/// # Arguments
+///
+/// * `target` - Desired luma histogram, e.g. [crate::lightness_histogram_yuv] of reference image
+///   with the same matrix and range
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_yuv_bgr(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    match_histogram_yuv_impl::<3>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth: 8,
+        },
+        image_to_yuv444::<{ ImageConfiguration::Bgr as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgr as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to YUV, matches luma histogram to `target` and reverts back into RGBA.
+///
+/// Histogram has a bin for every luma level of `yuv_range`
+///
+/// # Arguments
+///
+/// * `target` - Desired luma histogram, e.g. [crate::lightness_histogram_yuv] of reference image
+///   with the same matrix and range
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_yuv_rgba(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    match_histogram_yuv_impl::<4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth: 8,
+        },
+        image_to_yuv444::<{ ImageConfiguration::Rgba as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Rgba as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
+
+/// Converts image to YUV, matches luma histogram to `target` and reverts back into BGRA.
+///
+/// Histogram has a bin for every luma level of `yuv_range`
+///
+/// # Arguments
+///
+/// * `target` - Desired luma histogram, e.g. [crate::lightness_histogram_yuv] of reference image
+///   with the same matrix and range
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV, with limited range histograms span only its luma levels
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn hist_match_yuv_bgra(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+) -> Result<(), HistogramError> {
+    match_histogram_yuv_impl::<4>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        target,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth: 8,
+        },
+        image_to_yuv444::<{ ImageConfiguration::Bgra as u8 }>,
+        yuv444_to_image::<{ ImageConfiguration::Bgra as u8 }>,
+        &mut EqualizerContext::default(),
+    )
+}
@@ -0,0 +1,260 @@
+use crate::context::{working_buffer, EqualizerContext, YuvScratch};
+use crate::dispatch::HistogramSample;
+use crate::error::{
+    check_bins_count, check_bit_depth, check_destination, check_image_size, check_source,
+    check_target_histogram, HistogramError,
+};
+use crate::hist_support::{count_histogram_region, match_histogram_lut, ImageHistogram};
+use crate::yuv_support::{alpha_plane, luma_from_levels, luma_levels, luma_to_levels, YuvOptions};
+use colorutils_rs::TransferFunction;
+use num_traits::{AsPrimitive, FromPrimitive};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::{ParallelSlice, ParallelSliceMut};
+use yuv::{BufferStoreMut, YuvError, YuvPlanarImageMut, YuvPlanarImageWithAlpha};
+
+/// Histogram matching through planar lightness, bins count is taken from `target`,
+/// working buffers are taken from `context`
+pub(crate) fn match_histogram_impl_proxy<T: Copy, const CHANNELS: usize>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+    destructuring: fn(
+        &[T],
+        u32,
+        &mut [u16],
+        u32,
+        &mut [f32],
+        u32,
+        u32,
+        f32,
+        u32,
+        TransferFunction,
+    ) -> f32,
+    structuring: fn(&[u16], u32, &[f32], &mut [T], u32, u32, u32, f32, u32, TransferFunction),
+    context: &mut EqualizerContext,
+) -> Result<(), HistogramError> {
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, CHANNELS)?;
+    check_destination(dst, dst_stride, width, height, CHANNELS)?;
+    let bins_count = target.bins.len();
+    check_bins_count(bins_count)?;
+    check_target_histogram(target, bins_count)?;
+    check_bit_depth(bit_depth)?;
+
+    let EqualizerContext {
+        image,
+        color_planes,
+        tiles,
+        ..
+    } = context;
+    let hsv_image = working_buffer(image, width as usize * height as usize);
+    let hsv_stride = width as usize;
+
+    let color_planes = working_buffer(
+        color_planes,
+        width as usize * height as usize * (CHANNELS - 1),
+    );
+
+    let headroom = destructuring(
+        src,
+        src_stride,
+        hsv_image,
+        hsv_stride as u32 * std::mem::size_of::<u16>() as u32,
+        color_planes,
+        width,
+        height,
+        (bins_count - 1) as f32,
+        bit_depth,
+        transfer_function,
+    );
+
+    let (lut, sub_histograms) = tiles.histograms.regions(1, bins_count);
+    count_histogram_region::<0, 1, u16>(
+        hsv_image,
+        hsv_stride as u32,
+        0,
+        width,
+        0,
+        height,
+        lut,
+        sub_histograms,
+    );
+    match_histogram_lut(lut, &target.bins);
+
+    let max_bins = bins_count - 1;
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = hsv_image.par_chunks_exact_mut(hsv_stride);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = hsv_image.chunks_exact_mut(hsv_stride);
+    }
+    iter.for_each(|hsv_image| {
+        for hsv in hsv_image.iter_mut() {
+            let value = (*hsv).min(max_bins as u16) as usize;
+            *hsv = unsafe { *lut.get_unchecked(value) } as u16;
+        }
+    });
+
+    structuring(
+        hsv_image,
+        hsv_stride as u32 * std::mem::size_of::<u16>() as u32,
+        color_planes,
+        dst,
+        dst_stride,
+        width,
+        height,
+        (bins_count - 1) as f32 / headroom,
+        bit_depth,
+        transfer_function,
+    );
+
+    Ok(())
+}
+
+/// Histogram matching of luma in matrix and range of `yuv_options`, `target` must have a bin
+/// for every luma level of the range, working buffers are taken from `context`
+pub(crate) fn match_histogram_yuv_impl<const CHANNELS: usize>(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+    yuv_options: YuvOptions,
+    destructuring: fn(&mut YuvPlanarImageMut<u8>, &[u8], u32, YuvOptions) -> Result<(), YuvError>,
+    structuring: fn(
+        &YuvPlanarImageWithAlpha<u8>,
+        &mut [u8],
+        u32,
+        YuvOptions,
+    ) -> Result<(), YuvError>,
+    context: &mut EqualizerContext,
+) -> Result<(), HistogramError> {
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, CHANNELS)?;
+    check_destination(dst, dst_stride, width, height, CHANNELS)?;
+    let (_, bins_count) = luma_levels(yuv_options.range, yuv_options.bit_depth);
+    check_target_histogram(target, bins_count)?;
+
+    let YuvScratch {
+        planes,
+        alpha,
+        tiles,
+    } = &mut context.yuv;
+    let a_plane = alpha_plane::<u8, CHANNELS>(src, src_stride, width, height, alpha);
+
+    let plane_length = width as usize * height as usize;
+    let planes = working_buffer(planes, plane_length * 3);
+    let (y_plane, uv_planes) = planes.split_at_mut(plane_length);
+    let (u_plane, v_plane) = uv_planes.split_at_mut(plane_length);
+    let mut planar_image_mut = YuvPlanarImageMut {
+        y_plane: BufferStoreMut::Borrowed(y_plane),
+        y_stride: width,
+        u_plane: BufferStoreMut::Borrowed(u_plane),
+        u_stride: width,
+        v_plane: BufferStoreMut::Borrowed(v_plane),
+        v_stride: width,
+        width,
+        height,
+    };
+
+    destructuring(&mut planar_image_mut, src, src_stride, yuv_options)?;
+
+    let y_plane = planar_image_mut.y_plane.borrow_mut();
+    let (w, h) = (width as usize, height as usize);
+    luma_to_levels(y_plane, w, w, h, yuv_options.range, yuv_options.bit_depth);
+
+    let (lut, sub_histograms) = tiles.histograms.regions(1, bins_count);
+    count_histogram_region::<0, 1, u8>(y_plane, width, 0, width, 0, height, lut, sub_histograms);
+    match_histogram_lut(lut, &target.bins);
+
+    y_plane.chunks_exact_mut(w).for_each(|row| {
+        for dst in row.iter_mut() {
+            *dst = unsafe { *lut.get_unchecked(*dst as usize) } as u8;
+        }
+    });
+
+    luma_from_levels(y_plane, w, w, h, yuv_options.range, yuv_options.bit_depth);
+
+    let planar_image = YuvPlanarImageWithAlpha {
+        y_plane: planar_image_mut.y_plane.borrow(),
+        y_stride: width,
+        u_plane: planar_image_mut.u_plane.borrow(),
+        u_stride: width,
+        v_plane: planar_image_mut.v_plane.borrow(),
+        v_stride: width,
+        a_plane,
+        a_stride: width,
+        width,
+        height,
+    };
+
+    structuring(&planar_image, dst, dst_stride, yuv_options)?;
+
+    Ok(())
+}
+
+/// Histogram matching of single channel image, bins count is taken from `target`,
+/// histogram is counted in `context`
+pub(crate) fn match_histogram_gray_impl<
+    T: Copy + Ord + FromPrimitive + Default + HistogramSample + Send + Sync + 'static,
+>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    target: &ImageHistogram,
+    bins_count: usize,
+    context: &mut EqualizerContext,
+) -> Result<(), HistogramError>
+where
+    u64: AsPrimitive<T>,
+{
+    check_image_size(width, height)?;
+    check_source(src, src_stride, width, height, 1)?;
+    check_destination(dst, dst_stride, width, height, 1)?;
+    check_target_histogram(target, bins_count)?;
+    let (lut, sub_histograms) = context.tiles.histograms.regions(1, bins_count);
+    count_histogram_region::<0, 1, T>(src, src_stride, 0, width, 0, height, lut, sub_histograms);
+    match_histogram_lut(lut, &target.bins);
+
+    let max_bins = bins_count - 1;
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(src.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(src.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|(dst, src)| {
+        for (dst, &src) in dst.iter_mut().zip(src.iter()).take(width as usize) {
+            let value = Into::<usize>::into(src).min(max_bins);
+            let bin_value = unsafe { *lut.get_unchecked(value) };
+            *dst = bin_value.as_();
+        }
+    });
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use num_traits::FromPrimitive;
+use crate::dispatch::HistogramSample;
+use crate::mask::HistogramMask;
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub(crate) enum AheImplementation {
@@ -18,14 +19,40 @@ impl From<u8> for AheImplementation {
     }
 }
 
-#[inline]
-pub(crate) fn lerp(a: f32, b: f32, f: f32) -> f32 {
-    a * (1f32 - f) + (b * f)
+/// How the excess of clipped histogram bins is given back to the histogram
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+pub enum ClipRedistribution {
+    /// Excess is repeatedly spread over bins below the clip limit and the residual is
+    /// distributed across bins, so the limit is honored and samples count is preserved
+    #[default]
+    Iterative,
+    /// Single uniform pass of the previous versions, bins may end up above the clip limit
+    Uniform,
 }
 
-#[inline]
-pub(crate) fn blerp(c00: f32, c10: f32, c01: f32, c11: f32, tx: f32, ty: f32) -> f32 {
-    lerp(lerp(c00, c10, tx), lerp(c01, c11, tx), ty)
+/// Tiling, clipping and interpolation semantics of CLAHE
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+pub enum ClaheMode {
+    /// Tiling and interpolation of this crate, remainder is spread over balanced tiles
+    #[default]
+    Native,
+    /// Mirrors `cv::CLAHE::apply`: image is padded by reflection to a multiple of the grid,
+    /// clip limit is `max(threshold * tile_area / bins, 1)`, residual excess is spread with
+    /// a constant step and LUT is interpolated between tile centers.
+    /// Threshold of zero or less disables clipping, clip redistribution option is ignored
+    OpenCv,
+    /// Exact neighbourhood equalization: every pixel is mapped by the histogram of the window
+    /// centered on it, window is the size of a grid tile and is cut by image borders.
+    /// Histograms are updated incrementally along rows, yet it is much slower than tiles,
+    /// mostly with CLAHE where the window histogram is clipped for every pixel
+    SlidingWindow,
+}
+
+/// Options of tile processing shared by all CLAHE cores
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+pub(crate) struct ClaheOptions {
+    pub(crate) clip_redistribution: ClipRedistribution,
+    pub(crate) mode: ClaheMode,
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
@@ -40,11 +67,126 @@ impl ClaheGridSize {
     }
 }
 
+/// Rectangle of the image in pixels
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct ImageRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> ImageRegion {
+        ImageRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
 pub struct ImageHistogram {
     pub bins: Vec<u64>,
 }
 
+impl ImageHistogram {
+    /// Creates empty histogram with `bins_count` bins
+    pub fn new(bins_count: usize) -> ImageHistogram {
+        ImageHistogram {
+            bins: vec![0u64; bins_count],
+        }
+    }
+
+    /// Count of all samples in histogram
+    pub fn total(&self) -> u64 {
+        self.bins.iter().sum()
+    }
+
+    /// Cumulative distribution, last value equals to [ImageHistogram::total]
+    pub fn cdf(&self) -> Vec<u64> {
+        let mut bins = self.bins.clone();
+        cdf(&mut bins);
+        bins
+    }
+
+    /// Lowest non empty bin, `None` for empty histogram
+    pub fn min_bin(&self) -> Option<usize> {
+        self.bins.iter().position(|&x| x != 0)
+    }
+
+    /// Highest non empty bin, `None` for empty histogram
+    pub fn max_bin(&self) -> Option<usize> {
+        self.bins.iter().rposition(|&x| x != 0)
+    }
+
+    /// Smallest bin where at least `percentile` share of samples is accumulated,
+    /// so 0 gives [ImageHistogram::min_bin] and 1 gives [ImageHistogram::max_bin]
+    ///
+    /// # Arguments
+    ///
+    /// * `percentile` - Share of samples in range [0, 1], clamped when out of range
+    ///
+    /// Returns 0 for empty histogram
+    pub fn percentile(&self, percentile: f32) -> usize {
+        let total = self.total();
+        if total == 0 {
+            return 0;
+        }
+        let fraction = percentile.clamp(0f32, 1f32) as f64;
+        let target = ((fraction * total as f64).ceil() as u64).max(1);
+        let mut sum = 0u64;
+        for (bin, &count) in self.bins.iter().enumerate() {
+            sum += count;
+            if sum >= target {
+                return bin;
+            }
+        }
+        self.bins.len() - 1
+    }
+
+    /// Bin where half of the samples are accumulated
+    pub fn median(&self) -> usize {
+        self.percentile(0.5f32)
+    }
+
+    /// Mean bin weighted by counts, returns 0 for empty histogram
+    pub fn mean(&self) -> f32 {
+        let total = self.total();
+        if total == 0 {
+            return 0f32;
+        }
+        let sum: f64 = self
+            .bins
+            .iter()
+            .enumerate()
+            .map(|(bin, &count)| bin as f64 * count as f64)
+            .sum();
+        (sum / total as f64) as f32
+    }
+
+    /// Shannon entropy in bits, 0 for empty or single valued histogram
+    pub fn entropy(&self) -> f32 {
+        let total = self.total();
+        if total == 0 {
+            return 0f32;
+        }
+        let recip_total = 1f64 / total as f64;
+        let entropy: f64 = self
+            .bins
+            .iter()
+            .filter(|&&count| count != 0)
+            .map(|&count| {
+                let p = count as f64 * recip_total;
+                -p * p.log2()
+            })
+            .sum();
+        entropy as f32
+    }
+}
+
 pub(crate) fn cdf(arr: &mut [u64]) {
     let mut sum: u64 = 0u64;
 
@@ -54,9 +196,54 @@ pub(crate) fn cdf(arr: &mut [u64]) {
     }
 }
 
-pub(crate) fn clip_hist_clahe(bins: &mut [u64], level: f32, width: usize, height: usize) {
-    let threshold_value: f64 = level as f64 * width as f64 * height as f64 / bins.len() as f64;
-    let clip_limit = threshold_value as u64;
+/// Replaces every bin of `bins` by the first non empty target bin which cumulative share
+/// is not less than the source one. Both histograms must have the same bins count and be
+/// non empty
+pub(crate) fn match_histogram_lut(bins: &mut [u64], target: &[u64]) {
+    cdf(bins);
+
+    let source_total = *bins.last().unwrap_or(&0) as u128;
+    let target_total = target.iter().map(|&v| v as u128).sum::<u128>();
+
+    let mut j = 0usize;
+    let mut target_sum = target[0];
+    let last_bin = target.len() - 1;
+    for bin in bins.iter_mut() {
+        let source_sum = *bin;
+        // source_sum / source_total <= target_sum / target_total without rounding
+        while j < last_bin
+            && (target_sum == 0
+                || (target_sum as u128) * source_total < source_sum as u128 * target_total)
+        {
+            j += 1;
+            target_sum += target[j];
+        }
+        *bin = j as u64;
+    }
+}
+
+/// Clip limit of `width` x `height` region histogram of `bins_count` bins at `level`
+#[inline]
+pub(crate) fn clip_limit(level: f32, width: usize, height: usize, bins_count: usize) -> u64 {
+    let threshold_value: f64 = level as f64 * width as f64 * height as f64 / bins_count as f64;
+    (threshold_value as u64).max(1)
+}
+
+pub(crate) fn clip_hist_clahe(
+    bins: &mut [u64],
+    level: f32,
+    width: usize,
+    height: usize,
+    redistribution: ClipRedistribution,
+) {
+    let clip_limit = clip_limit(level, width, height, bins.len());
+    match redistribution {
+        ClipRedistribution::Iterative => clip_hist_iterative(bins, clip_limit),
+        ClipRedistribution::Uniform => clip_hist_uniform(bins, clip_limit),
+    }
+}
+
+fn clip_hist_uniform(bins: &mut [u64], clip_limit: u64) {
     let mut excess = 0u64;
 
     for i in 0..bins.len() {
@@ -78,6 +265,58 @@ pub(crate) fn clip_hist_clahe(bins: &mut [u64], level: f32, width: usize, height
     }
 }
 
+fn clip_hist_iterative(bins: &mut [u64], clip_limit: u64) {
+    let mut excess = 0u64;
+    for bin in bins.iter_mut() {
+        if *bin > clip_limit {
+            excess += *bin - clip_limit;
+            *bin = clip_limit;
+        }
+    }
+
+    while excess > 0 {
+        let free_bins = bins.iter().filter(|&&x| x < clip_limit).count() as u64;
+        if free_bins == 0 {
+            break;
+        }
+        let batch = excess / free_bins;
+        if batch == 0 {
+            // Less than one sample per free bin, spread the residual evenly across the range
+            let step = (free_bins / excess).max(1) as usize;
+            for bin in bins
+                .iter_mut()
+                .filter(|x| **x < clip_limit)
+                .step_by(step)
+                .take(excess as usize)
+            {
+                *bin += 1;
+            }
+            return;
+        }
+        for bin in bins.iter_mut() {
+            if *bin < clip_limit {
+                let added = batch.min(clip_limit - *bin);
+                *bin += added;
+                excess -= added;
+            }
+        }
+    }
+
+    // Clip limit below mean bin height can't hold all samples, give back the rest uniformly
+    if excess > 0 {
+        let n = bins.len() as u64;
+        let batch = excess / n;
+        let residual = (excess % n) as usize;
+        let step = (bins.len() / residual.max(1)).max(1);
+        for bin in bins.iter_mut() {
+            *bin += batch;
+        }
+        for bin in bins.iter_mut().step_by(step).take(residual) {
+            *bin += 1;
+        }
+    }
+}
+
 pub(crate) fn minmax(slice: &[u64]) -> (u64, u64) {
     let mut min_value = u64::MAX;
     let mut max_value = u64::MIN;
@@ -97,10 +336,52 @@ pub(crate) fn minmax(slice: &[u64]) -> (u64, u64) {
     (min_value, max_value)
 }
 
+/// Histograms are counted into at most this count of sub-histograms
+pub(crate) const HISTOGRAM_LANES: usize = 4;
+
+/// Bins and sub-histograms of several regions counted at once, kept between calls
+/// so steady state processing doesn't allocate
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HistogramScratch {
+    bins: Vec<u64>,
+    sub_histograms: Vec<u32>,
+}
+
+impl HistogramScratch {
+    /// Zeroed bins of `regions` histograms with `bins_count` bins and [HISTOGRAM_LANES]
+    /// sub-histograms for each of them, buffers grow only when they are too small
+    pub(crate) fn regions(
+        &mut self,
+        regions: usize,
+        bins_count: usize,
+    ) -> (&mut [u64], &mut [u32]) {
+        self.bins.clear();
+        self.bins.resize(regions * bins_count, 0);
+        self.sub_histograms.clear();
+        self.sub_histograms
+            .resize(regions * HISTOGRAM_LANES * bins_count, 0);
+        (&mut self.bins, &mut self.sub_histograms)
+    }
+}
+
+/// Merging costs `lanes * bins_count`, so small regions are counted into one histogram
+#[inline]
+fn histogram_lanes(region_area: usize, bins_count: usize) -> usize {
+    if region_area >= bins_count * 16 {
+        HISTOGRAM_LANES
+    } else {
+        1
+    }
+}
+
+/// Counts `CHANNEL` of the region, samples above `bins_count - 1` go to the last bin.
+///
+/// Samples are spread over several sub-histograms so consecutive equal samples do not wait
+/// for each other's increments, planar rows are clamped with SIMD when available
 pub(crate) fn make_histogram_region<
     const CHANNEL: usize,
     const CHANNELS: usize,
-    T: Copy + Ord + FromPrimitive + Default + Into<usize>,
+    T: HistogramSample,
 >(
     in_place: &[T],
     stride: u32,
@@ -110,24 +391,318 @@ pub(crate) fn make_histogram_region<
     end_y: u32,
     bins_count: usize,
 ) -> ImageHistogram {
-    let mut bins = vec![0u64; bins_count];
-    let mut y_shift = (stride * start_y) as usize;
-
-    let min_v = T::from_u16(bins_count as u16 - 1).unwrap_or_default();
-
-    for _ in start_y as usize..end_y as usize {
-        for x in start_x as usize..end_x as usize {
-            let px = x * CHANNELS;
-            let value: T = unsafe { *in_place.get_unchecked(y_shift + px + CHANNEL) }
-                .min(min_v)
-                .max(T::from_u16(0u16).unwrap_or_default());
-            let iter: usize = value.into();
-            unsafe {
-                *bins.get_unchecked_mut(iter) += 1u64;
+    let region_area = (end_x - start_x) as usize * (end_y - start_y) as usize;
+    let mut sub_histograms = vec![0u32; histogram_lanes(region_area, bins_count) * bins_count];
+    let mut histogram = ImageHistogram::new(bins_count);
+    count_histogram_region::<CHANNEL, CHANNELS, T>(
+        in_place,
+        stride,
+        start_x,
+        end_x,
+        start_y,
+        end_y,
+        &mut histogram.bins,
+        &mut sub_histograms,
+    );
+    histogram
+}
+
+/// Adds counts of `CHANNEL` of the region to `bins`, see [make_histogram_region].
+///
+/// `sub_histograms` must be zeroed and hold [HISTOGRAM_LANES] histograms of `bins.len()` bins
+/// or a single one for regions smaller than 16 samples per bin, they are zeroed on return.
+pub(crate) fn count_histogram_region<
+    const CHANNEL: usize,
+    const CHANNELS: usize,
+    T: HistogramSample,
+>(
+    in_place: &[T],
+    stride: u32,
+    start_x: u32,
+    end_x: u32,
+    start_y: u32,
+    end_y: u32,
+    bins: &mut [u64],
+    sub_histograms: &mut [u32],
+) {
+    let bins_count = bins.len();
+    let region_width = (end_x - start_x) as usize;
+    let region_height = (end_y - start_y) as usize;
+    let max_bin = bins_count - 1;
+
+    let lanes = histogram_lanes(region_width * region_height, bins_count);
+    let sub_histograms = &mut sub_histograms[..lanes * bins_count];
+    let count_row = T::row_counter();
+
+    let flush = |sub_histograms: &mut [u32], bins: &mut [u64]| {
+        for sub_histogram in sub_histograms.chunks_exact_mut(bins_count) {
+            for (dst, src) in bins.iter_mut().zip(sub_histogram.iter_mut()) {
+                *dst += *src as u64;
+                *src = 0;
+            }
+        }
+    };
+
+    let mut pending = 0u64;
+    for row in in_place
+        .chunks(stride as usize)
+        .skip(start_y as usize)
+        .take(region_height)
+    {
+        // Sub-histogram bins are u32, they are flushed before they might overflow
+        if pending + region_width as u64 > u32::MAX as u64 {
+            flush(sub_histograms, bins);
+            pending = 0;
+        }
+        if CHANNELS == 1 {
+            count_row(
+                &row[start_x as usize + CHANNEL..end_x as usize + CHANNEL],
+                max_bin,
+                bins_count,
+                lanes,
+                sub_histograms,
+            );
+        } else {
+            let lane_mask = lanes - 1;
+            for (i, px) in row[start_x as usize * CHANNELS..end_x as usize * CHANNELS]
+                .chunks_exact(CHANNELS)
+                .enumerate()
+            {
+                let value: usize = px[CHANNEL].into();
+                unsafe {
+                    *sub_histograms
+                        .get_unchecked_mut((i & lane_mask) * bins_count + value.min(max_bin)) += 1;
+                }
             }
         }
-        y_shift += stride as usize;
+        pending += region_width as u64;
     }
 
-    ImageHistogram { bins }
+    flush(sub_histograms, bins);
+}
+
+/// Adds counts of `CHANNEL` of the region only where `mask` is set to `bins`,
+/// samples above `bins.len() - 1` go to the last bin.
+///
+/// Rectangle is intersected with the region and counted by [count_histogram_region]
+/// with `sub_histograms`, per pixel masks are counted directly into `bins`
+pub(crate) fn count_histogram_region_masked<
+    const CHANNEL: usize,
+    const CHANNELS: usize,
+    T: HistogramSample,
+>(
+    in_place: &[T],
+    stride: u32,
+    start_x: u32,
+    end_x: u32,
+    start_y: u32,
+    end_y: u32,
+    bins: &mut [u64],
+    sub_histograms: &mut [u32],
+    mask: HistogramMask<'_>,
+) {
+    let (mask, mask_stride) = match mask {
+        HistogramMask::Rect(rect) => {
+            let (start_x, end_x) = (start_x.max(rect.x), end_x.min(rect.x + rect.width));
+            let (start_y, end_y) = (start_y.max(rect.y), end_y.min(rect.y + rect.height));
+            if start_x < end_x && start_y < end_y {
+                count_histogram_region::<CHANNEL, CHANNELS, T>(
+                    in_place,
+                    stride,
+                    start_x,
+                    end_x,
+                    start_y,
+                    end_y,
+                    bins,
+                    sub_histograms,
+                );
+            }
+            return;
+        }
+        HistogramMask::Pixels { mask, stride } => (mask, stride),
+    };
+    let max_bin = bins.len() - 1;
+    for (y, row) in in_place
+        .chunks(stride as usize)
+        .enumerate()
+        .skip(start_y as usize)
+        .take((end_y - start_y) as usize)
+    {
+        let mask_row = &mask[y * mask_stride + start_x as usize..y * mask_stride + end_x as usize];
+        let row = &row[start_x as usize * CHANNELS..end_x as usize * CHANNELS];
+        for (px, &masked) in row.chunks_exact(CHANNELS).zip(mask_row.iter()) {
+            if masked != 0 {
+                let value: usize = px[CHANNEL].into();
+                bins[value.min(max_bin)] += 1;
+            }
+        }
+    }
+}
+
+/// Global equalization LUT of `CHANNEL` counted in `scratch`, histogram counts only masked
+/// pixels when `mask` is set, with an empty mask values are kept as is
+pub(crate) fn equalization_lut<
+    'a,
+    const CHANNEL: usize,
+    const CHANNELS: usize,
+    T: HistogramSample,
+>(
+    image: &[T],
+    stride: u32,
+    width: u32,
+    height: u32,
+    bins_count: usize,
+    mask: Option<HistogramMask<'_>>,
+    scratch: &'a mut HistogramScratch,
+) -> &'a [u64] {
+    let (bins, sub_histograms) = scratch.regions(1, bins_count);
+    let pixels_count = match mask {
+        Some(mask) => {
+            count_histogram_region_masked::<CHANNEL, CHANNELS, T>(
+                image,
+                stride,
+                0,
+                width,
+                0,
+                height,
+                bins,
+                sub_histograms,
+                mask,
+            );
+            let samples = bins.iter().sum::<u64>();
+            if samples == 0 {
+                for (i, bin) in bins.iter_mut().enumerate() {
+                    *bin = i as u64;
+                }
+                return bins;
+            }
+            samples
+        }
+        None => {
+            count_histogram_region::<CHANNEL, CHANNELS, T>(
+                image,
+                stride,
+                0,
+                width,
+                0,
+                height,
+                bins,
+                sub_histograms,
+            );
+            width as u64 * height as u64
+        }
+    };
+
+    cdf(bins);
+
+    let (min_bin, _) = minmax(bins);
+
+    let distance_r = 1f64 / (pixels_count as f64 - min_bin as f64);
+
+    let max_bins = bins_count - 1;
+
+    if distance_r != 0f64 {
+        for bin in bins.iter_mut() {
+            *bin = (max_bins as f64 * (*bin as f64 - min_bin as f64) * distance_r)
+                .round()
+                .min(max_bins as f64)
+                .max(0f64) as u64;
+        }
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_onto_itself_is_identity() {
+        let full: Vec<u64> = (0..256u64).map(|i| (i * 37) % 11 + 1).collect();
+        let mut lut = full.clone();
+        match_histogram_lut(&mut lut, &full);
+        assert!(lut.iter().enumerate().all(|(i, &v)| v == i as u64));
+
+        // Empty bins hold no samples, every populated bin still maps onto itself
+        let sparse: Vec<u64> = (0..256u64)
+            .map(|i| if i % 3 == 1 { i + 1 } else { 0 })
+            .collect();
+        let mut lut = sparse.clone();
+        match_histogram_lut(&mut lut, &sparse);
+        for (i, (&v, &count)) in lut.iter().zip(sparse.iter()).enumerate() {
+            if count != 0 {
+                assert_eq!(v, i as u64);
+            }
+        }
+    }
+
+    /// Deterministic histograms of various shapes and sizes
+    fn histograms() -> Vec<Vec<u64>> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..200)
+            .map(|i| {
+                let len = [2usize, 3, 16, 17, 255, 256, 1024][i % 7];
+                let spread = [1u64, 4, 100, 10_000][i % 4];
+                (0..len)
+                    .map(|_| {
+                        // Mostly empty or small bins with a few spikes
+                        match next() % 8 {
+                            0 | 1 => 0,
+                            7 => next() % (spread * 50),
+                            _ => next() % spread,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn iterative_clip_keeps_bins_under_limit_and_mass() {
+        for bins in histograms() {
+            let total: u64 = bins.iter().sum();
+            let len = bins.len() as u64;
+            for clip_limit in [1u64, 2, 3, 7, 50, 1000] {
+                let mut clipped = bins.clone();
+                clip_hist_iterative(&mut clipped, clip_limit);
+                assert_eq!(clipped.iter().sum::<u64>(), total);
+                if clip_limit * len >= total {
+                    assert!(
+                        clipped.iter().all(|&v| v <= clip_limit),
+                        "{clip_limit} {bins:?} {clipped:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn uniform_clip_matches_previous_versions() {
+        let mut bins = [0u64, 3, 12, 40, 7, 1, 25, 2, 0, 9, 60, 4, 5, 30, 2, 0];
+        // 16 bins of 16x10 tile at level 1 clip at 10
+        clip_hist_clahe(&mut bins, 1f32, 16, 10, ClipRedistribution::Uniform);
+        assert_eq!(
+            bins,
+            [10, 13, 20, 20, 17, 11, 20, 12, 10, 19, 20, 14, 15, 20, 12, 10]
+        );
+    }
+
+    #[test]
+    fn matching_onto_constant_maps_to_its_level() {
+        let source: Vec<u64> = (0..256u64)
+            .map(|i| if i % 5 == 0 { 0 } else { i })
+            .collect();
+        let mut constant = vec![0u64; 256];
+        constant[173] = 4096;
+        let mut lut = source;
+        match_histogram_lut(&mut lut, &constant);
+        assert!(lut.iter().all(|&v| v == 173));
+    }
 }
@@ -0,0 +1,347 @@
+use crate::equalizer::EqualizationColorSpace;
+use crate::error::{
+    check_bins_count, check_bit_depth, check_image_size, check_region, check_source, HistogramError,
+};
+use crate::hist_support::{make_histogram_region, ImageHistogram, ImageRegion};
+use crate::hsv::{generic_image_to_hsv, HsvTarget};
+use crate::image_configuration::ImageConfiguration;
+use crate::jzazbz::generic_image_to_jzazbz;
+use crate::lab::generic_image_to_lab_planar;
+use crate::luv::generic_image_to_luv_planar;
+use crate::oklab::generic_image_to_oklab;
+use crate::oklch::generic_image_to_oklch;
+use crate::yuv_support::{image_to_yuv444, luma_levels, luma_to_levels, YuvMatrix, YuvOptions};
+use colorutils_rs::TransferFunction;
+use std::borrow::Cow;
+use yuv::{BufferStoreMut, YuvError, YuvPlanarImageMut, YuvRange};
+
+type LightnessConverter =
+    fn(&[u8], u32, &mut [u16], u32, &mut [f32], u32, u32, f32, u32, TransferFunction) -> f32;
+
+/// Computes histogram of a single channel of 8 bit interleaved image, always 256 bins
+///
+/// # Arguments
+///
+/// * `channels` - Count of interleaved channels in the image
+/// * `channel` - Index of the channel to count, must be less than `channels`
+/// * `region` - Part of the image to count, whole image when `None`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn channel_histogram(
+    src: &[u8],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    channels: usize,
+    channel: usize,
+    region: Option<ImageRegion>,
+) -> Result<ImageHistogram, HistogramError> {
+    channel_histogram_impl(
+        src, src_stride, width, height, channels, channel, region, 256,
+    )
+}
+
+/// Computes histogram of a single channel of high bit depth interleaved image,
+/// bins count is `1 << bit_depth`
+///
+/// # Arguments
+///
+/// * `src_stride` - Elements per row of `src`
+/// * `bit_depth` - Image bit depth, supported range is [1, 16]
+/// * `channels` - Count of interleaved channels in the image
+/// * `channel` - Index of the channel to count, must be less than `channels`
+/// * `region` - Part of the image to count, whole image when `None`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn channel_histogram_u16(
+    src: &[u16],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    bit_depth: u32,
+    channels: usize,
+    channel: usize,
+    region: Option<ImageRegion>,
+) -> Result<ImageHistogram, HistogramError> {
+    check_bit_depth(bit_depth)?;
+    channel_histogram_impl(
+        src,
+        src_stride,
+        width,
+        height,
+        channels,
+        channel,
+        region,
+        1usize << bit_depth,
+    )
+}
+
+/// Computes histogram of lightness component of the color space, exactly as equalization
+/// in the same color space sees it
+///
+/// # Arguments
+///
+/// * `layout` - Pixel layout of `src`
+/// * `color_space` - Color space where lightness is taken
+/// * `bins_count` - Histogram bins, ignored for YUV where bins always 256 of full range YCgCo,
+///   other matrices and ranges are counted by [lightness_histogram_yuv]
+/// * `region` - Part of the image to count, whole image when `None`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range.
+/// YUV supports only RGB, BGR, RGBA and BGRA layouts
+pub fn lightness_histogram(
+    src: &[u8],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    layout: ImageConfiguration,
+    color_space: EqualizationColorSpace,
+    bins_count: usize,
+    region: Option<ImageRegion>,
+) -> Result<ImageHistogram, HistogramError> {
+    check_image_size(width, height)?;
+    let channels = layout.get_channels_count();
+    check_source(src, src_stride, width, height, channels)?;
+    let region = region.unwrap_or(ImageRegion::new(0, 0, width, height));
+    check_region(region, width, height)?;
+
+    let (region_image, region_stride) =
+        crop_region(src, src_stride, width, height, channels, region);
+
+    if color_space == EqualizationColorSpace::Yuv {
+        return yuv_lightness_histogram(
+            &region_image,
+            region_stride,
+            region.width,
+            region.height,
+            layout,
+            YuvOptions::default(),
+        );
+    }
+
+    check_bins_count(bins_count)?;
+
+    let converter = match layout {
+        ImageConfiguration::Rgb => {
+            lightness_converter::<{ ImageConfiguration::Rgb as u8 }>(color_space)
+        }
+        ImageConfiguration::Rgba => {
+            lightness_converter::<{ ImageConfiguration::Rgba as u8 }>(color_space)
+        }
+        ImageConfiguration::Bgra => {
+            lightness_converter::<{ ImageConfiguration::Bgra as u8 }>(color_space)
+        }
+        ImageConfiguration::Bgr => {
+            lightness_converter::<{ ImageConfiguration::Bgr as u8 }>(color_space)
+        }
+        ImageConfiguration::Argb => {
+            lightness_converter::<{ ImageConfiguration::Argb as u8 }>(color_space)
+        }
+        ImageConfiguration::Abgr => {
+            lightness_converter::<{ ImageConfiguration::Abgr as u8 }>(color_space)
+        }
+    };
+
+    let lightness_stride = region.width as usize;
+    let mut lightness: Vec<u16> = vec![0u16; lightness_stride * region.height as usize];
+    let mut color_planes: Vec<f32> =
+        vec![0.; lightness_stride * region.height as usize * (channels - 1)];
+
+    converter(
+        &region_image,
+        region_stride,
+        &mut lightness,
+        lightness_stride as u32 * std::mem::size_of::<u16>() as u32,
+        &mut color_planes,
+        region.width,
+        region.height,
+        (bins_count - 1) as f32,
+        8,
+        TransferFunction::Srgb,
+    );
+
+    Ok(make_histogram_region::<0, 1, u16>(
+        &lightness,
+        lightness_stride as u32,
+        0,
+        region.width,
+        0,
+        region.height,
+        bins_count,
+    ))
+}
+
+/// Computes luma histogram of 8 bit image in YUV of `yuv_matrix` and `yuv_range`, the histogram
+/// [crate::hist_match_yuv_rgb] and others match to
+///
+/// Histogram has a bin for every luma level of `yuv_range`, limited range levels start from 16
+///
+/// # Arguments
+///
+/// * `layout` - Pixel layout of `src`, only RGB, BGR, RGBA and BGRA are supported
+/// * `yuv_matrix` - Matrix of conversion into YUV
+/// * `yuv_range` - Range of YUV
+/// * `region` - Part of the image to count, whole image when `None`
+///
+/// # Errors
+///
+/// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+/// on the specified width, height, and strides, or if parameters are out of supported range
+pub fn lightness_histogram_yuv(
+    src: &[u8],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    layout: ImageConfiguration,
+    yuv_matrix: YuvMatrix,
+    yuv_range: YuvRange,
+    region: Option<ImageRegion>,
+) -> Result<ImageHistogram, HistogramError> {
+    check_image_size(width, height)?;
+    let channels = layout.get_channels_count();
+    check_source(src, src_stride, width, height, channels)?;
+    let region = region.unwrap_or(ImageRegion::new(0, 0, width, height));
+    check_region(region, width, height)?;
+
+    let (region_image, region_stride) =
+        crop_region(src, src_stride, width, height, channels, region);
+
+    yuv_lightness_histogram(
+        &region_image,
+        region_stride,
+        region.width,
+        region.height,
+        layout,
+        YuvOptions {
+            matrix: yuv_matrix,
+            range: yuv_range,
+            bit_depth: 8,
+        },
+    )
+}
+
+fn channel_histogram_impl<T: Copy + Into<usize>>(
+    src: &[T],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    channels: usize,
+    channel: usize,
+    region: Option<ImageRegion>,
+    bins_count: usize,
+) -> Result<ImageHistogram, HistogramError> {
+    check_image_size(width, height)?;
+    if channels == 0 || channel >= channels {
+        return Err(HistogramError::InvalidChannel(channel));
+    }
+    check_source(src, src_stride, width, height, channels)?;
+    let region = region.unwrap_or(ImageRegion::new(0, 0, width, height));
+    check_region(region, width, height)?;
+
+    let mut histogram = ImageHistogram::new(bins_count);
+    let max_bin = bins_count - 1;
+
+    for row in src
+        .chunks(src_stride as usize)
+        .skip(region.y as usize)
+        .take(region.height as usize)
+    {
+        let start = region.x as usize * channels;
+        let end = start + region.width as usize * channels;
+        for px in row[start..end].chunks_exact(channels) {
+            let value: usize = px[channel].into();
+            histogram.bins[value.min(max_bin)] += 1;
+        }
+    }
+
+    Ok(histogram)
+}
+
+/// Borrows the image when region covers it completely, otherwise copies region rows
+fn crop_region<'a>(
+    src: &'a [u8],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    channels: usize,
+    region: ImageRegion,
+) -> (Cow<'a, [u8]>, u32) {
+    if region == ImageRegion::new(0, 0, width, height) {
+        return (Cow::Borrowed(src), src_stride);
+    }
+    let region_stride = region.width as usize * channels;
+    let mut cropped = vec![0u8; region_stride * region.height as usize];
+    for (dst, src) in cropped.chunks_exact_mut(region_stride).zip(
+        src.chunks(src_stride as usize)
+            .skip(region.y as usize)
+            .take(region.height as usize),
+    ) {
+        let start = region.x as usize * channels;
+        dst.copy_from_slice(&src[start..start + region_stride]);
+    }
+    (Cow::Owned(cropped), region_stride as u32)
+}
+
+fn lightness_converter<const IMAGE: u8>(color_space: EqualizationColorSpace) -> LightnessConverter {
+    match color_space {
+        EqualizationColorSpace::Hsv => generic_image_to_hsv::<u8, IMAGE, { HsvTarget::Hsv as u8 }>,
+        EqualizationColorSpace::Hsl => generic_image_to_hsv::<u8, IMAGE, { HsvTarget::Hsl as u8 }>,
+        EqualizationColorSpace::Lab => generic_image_to_lab_planar::<u8, IMAGE>,
+        EqualizationColorSpace::Luv => generic_image_to_luv_planar::<u8, IMAGE>,
+        EqualizationColorSpace::Oklab => generic_image_to_oklab::<u8, IMAGE>,
+        EqualizationColorSpace::Oklch => generic_image_to_oklch::<u8, IMAGE>,
+        EqualizationColorSpace::Jzazbz => generic_image_to_jzazbz::<u8, IMAGE>,
+        EqualizationColorSpace::Yuv => unreachable!("YUV lightness is taken from Y plane"),
+    }
+}
+
+fn yuv_lightness_histogram(
+    src: &[u8],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    layout: ImageConfiguration,
+    yuv_options: YuvOptions,
+) -> Result<ImageHistogram, HistogramError> {
+    type Converter = fn(&mut YuvPlanarImageMut<u8>, &[u8], u32, YuvOptions) -> Result<(), YuvError>;
+    let converter: Converter = match layout {
+        ImageConfiguration::Rgb => image_to_yuv444::<{ ImageConfiguration::Rgb as u8 }>,
+        ImageConfiguration::Rgba => image_to_yuv444::<{ ImageConfiguration::Rgba as u8 }>,
+        ImageConfiguration::Bgra => image_to_yuv444::<{ ImageConfiguration::Bgra as u8 }>,
+        ImageConfiguration::Bgr => image_to_yuv444::<{ ImageConfiguration::Bgr as u8 }>,
+        ImageConfiguration::Argb | ImageConfiguration::Abgr => {
+            return Err(HistogramError::UnsupportedLayout(layout));
+        }
+    };
+
+    let mut planar_image_mut = YuvPlanarImageMut {
+        y_plane: BufferStoreMut::Owned(vec![0u8; width as usize * height as usize]),
+        y_stride: width,
+        u_plane: BufferStoreMut::Owned(vec![0u8; width as usize * height as usize]),
+        u_stride: width,
+        v_plane: BufferStoreMut::Owned(vec![0u8; width as usize * height as usize]),
+        v_stride: width,
+        width,
+        height,
+    };
+
+    converter(&mut planar_image_mut, src, src_stride, yuv_options)?;
+
+    let y_plane = planar_image_mut.y_plane.borrow_mut();
+    let (w, h) = (width as usize, height as usize);
+    luma_to_levels(y_plane, w, w, h, yuv_options.range, yuv_options.bit_depth);
+    let (_, bins_count) = luma_levels(yuv_options.range, yuv_options.bit_depth);
+
+    Ok(make_histogram_region::<0, 1, u8>(
+        y_plane, width, 0, width, 0, height, bins_count,
+    ))
+}
@@ -0,0 +1,545 @@
+use std::slice;
+
+use crate::image_configuration::ImageConfiguration;
+use crate::sample::{max_value_for_bit_depth, ImageSample};
+use colorutils_rs::{ColorError, Hsl, Hsv, ImageBuffer, ImageBufferMut, Rgb, TransferFunction};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::{ParallelSlice, ParallelSliceMut};
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub(crate) enum HsvTarget {
+    Hsv = 0,
+    Hsl = 1,
+}
+
+impl From<u8> for HsvTarget {
+    #[inline(always)]
+    fn from(value: u8) -> Self {
+        match value {
+            0 => HsvTarget::Hsv,
+            1 => HsvTarget::Hsl,
+            _ => {
+                panic!("Unknown value")
+            }
+        }
+    }
+}
+
+#[inline]
+fn rgb_to_hue(r: f32, g: f32, b: f32, c_max: f32, delta: f32) -> f32 {
+    let h = if delta == 0f32 {
+        0f32
+    } else if c_max == r {
+        60f32 * (((g - b) / delta) % 6f32)
+    } else if c_max == g {
+        60f32 * (((b - r) / delta) + 2f32)
+    } else {
+        60f32 * (((r - g) / delta) + 4f32)
+    };
+    if h < 0f32 {
+        h + 360f32
+    } else {
+        h
+    }
+}
+
+/// Returns (hue, saturation, value or lightness) for normalized RGB
+#[inline]
+fn rgb_to_hsv_components(r: f32, g: f32, b: f32, target: HsvTarget) -> (f32, f32, f32) {
+    let c_max = r.max(g).max(b);
+    let c_min = r.min(g).min(b);
+    let delta = c_max - c_min;
+    let h = rgb_to_hue(r, g, b, c_max, delta);
+    match target {
+        HsvTarget::Hsv => {
+            let s = if c_max > 0f32 { delta / c_max } else { 0f32 };
+            (h, s, c_max)
+        }
+        HsvTarget::Hsl => {
+            let l = 0.5f32 * (c_max + c_min);
+            let d = 1f32 - (2f32 * l - 1f32).abs();
+            let s = if d > 0f32 { delta / d } else { 0f32 };
+            (h, s, l)
+        }
+    }
+}
+
+#[inline]
+#[allow(clippy::manual_range_contains)]
+fn hsv_components_to_rgb(h: f32, s: f32, v: f32, target: HsvTarget) -> (f32, f32, f32) {
+    let (c, m) = match target {
+        HsvTarget::Hsv => {
+            let c = v * s;
+            (c, v - c)
+        }
+        HsvTarget::Hsl => {
+            let c = (1f32 - (2f32 * v - 1f32).abs()) * s;
+            (c, v - c * 0.5f32)
+        }
+    };
+    let h_prime = (h / 60f32) % 6f32;
+    let x = c * (1f32 - ((h_prime % 2f32) - 1f32).abs());
+
+    let (r, g, b) = if h_prime >= 0f32 && h_prime < 1f32 {
+        (c, x, 0f32)
+    } else if h_prime >= 1f32 && h_prime < 2f32 {
+        (x, c, 0f32)
+    } else if h_prime >= 2f32 && h_prime < 3f32 {
+        (0f32, c, x)
+    } else if h_prime >= 3f32 && h_prime < 4f32 {
+        (0f32, x, c)
+    } else if h_prime >= 4f32 && h_prime < 5f32 {
+        (x, 0f32, c)
+    } else {
+        (c, 0f32, x)
+    };
+
+    (r + m, g + m, b + m)
+}
+
+/// Converts image into planar HSV/HSL, where V/L stored in `dst` in range [0, scale]
+/// and hue, saturation and alpha are stored in `color_planes`, returns lightness headroom
+/// which is always 1 as samples are integer
+pub(crate) fn generic_image_to_hsv<T: ImageSample, const IMAGE: u8, const TARGET: u8>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    color_planes: &mut [f32],
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    _: TransferFunction,
+) -> f32 {
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let target: HsvTarget = TARGET.into();
+    let channels = image_configuration.get_channels_count();
+
+    let normalize_scale = 1f32 / max_value_for_bit_depth(bit_depth);
+
+    let color_planes_channels = if image_configuration.has_alpha() {
+        3usize
+    } else {
+        2usize
+    };
+
+    let color_planes_stride = width as usize * color_planes_channels;
+
+    let dst_slice_safe_align = unsafe {
+        slice::from_raw_parts_mut(
+            dst.as_mut_ptr() as *mut u8,
+            dst_stride as usize * height as usize,
+        )
+    };
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst_slice_safe_align
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact_mut(color_planes_stride))
+            .zip(src.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst_slice_safe_align
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact_mut(color_planes_stride))
+            .zip(src.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let dst_ptr = dst.as_mut_ptr() as *mut u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
+
+            let r = src
+                .get_unchecked(px + image_configuration.get_r_channel_offset())
+                .to_normalized(normalize_scale);
+            let g = src
+                .get_unchecked(px + image_configuration.get_g_channel_offset())
+                .to_normalized(normalize_scale);
+            let b = src
+                .get_unchecked(px + image_configuration.get_b_channel_offset())
+                .to_normalized(normalize_scale);
+
+            let (h, s, v) = rgb_to_hsv_components(r, g, b, target);
+            let value = (v * scale).round().min(scale) as u16;
+            dst_ptr.add(x).write_unaligned(value);
+            *color.get_unchecked_mut(cx) = h;
+            *color.get_unchecked_mut(cx + 1) = s;
+            if image_configuration.has_alpha() {
+                let a = *src.get_unchecked(px + image_configuration.get_a_channel_offset());
+                *color.get_unchecked_mut(cx + 2) = a.to_normalized(normalize_scale);
+            }
+        }
+    });
+    1f32
+}
+
+pub(crate) fn hsv_to_generic_image<T: ImageSample, const IMAGE: u8, const TARGET: u8>(
+    src: &[u16],
+    src_stride: u32,
+    color_planes: &[f32],
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    _: TransferFunction,
+) {
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let target: HsvTarget = TARGET.into();
+    let channels = image_configuration.get_channels_count();
+
+    let full_scale = 1. / scale;
+    let max_value = max_value_for_bit_depth(bit_depth);
+
+    let color_planes_channels = if image_configuration.has_alpha() {
+        3usize
+    } else {
+        2usize
+    };
+
+    let color_planes_stride = width as usize * color_planes_channels;
+
+    let src_slice_safe_align = unsafe {
+        slice::from_raw_parts(
+            src.as_ptr() as *const u8,
+            src_stride as usize * height as usize,
+        )
+    };
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let src_ptr = src.as_ptr() as *const u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
+
+            let v = src_ptr.add(x).read_unaligned() as f32 * full_scale;
+
+            let h = *color.get_unchecked(cx);
+            let s = *color.get_unchecked(cx + 1);
+
+            let (r, g, b) = hsv_components_to_rgb(h, s, v, target);
+            *dst.get_unchecked_mut(px + image_configuration.get_r_channel_offset()) =
+                T::from_normalized(r, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_g_channel_offset()) =
+                T::from_normalized(g, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_b_channel_offset()) =
+                T::from_normalized(b, max_value);
+            if image_configuration.has_alpha() {
+                let a = *color.get_unchecked(cx + 2);
+                *dst.get_unchecked_mut(px + image_configuration.get_a_channel_offset()) =
+                    T::from_normalized(a, max_value);
+            }
+        }
+    });
+}
+
+/// Interleaved HSV/HSL in the same layout as colorutils produces: hue in degrees,
+/// saturation and value or lightness in range [0, scale], alpha copied as it is.
+///
+/// Used for every layout instead of colorutils `rgb_to_hsv` family, which has no BGR,
+/// rounds its SIMD rows differently from scalar ones and corrupts memory on 4 channels images,
+/// so RGB and BGR images are equalized exactly the same
+fn generic_image_to_hsv_interleaved<const IMAGE: u8, const TARGET: u8>(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    src.validate()?;
+    dst.validate()?;
+    dst.try_match_immutable(src)?;
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let target: HsvTarget = TARGET.into();
+    let channels = image_configuration.get_channels_count();
+
+    let dst_stride = dst.stride();
+    let width = src.width as usize;
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .par_chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().par_chunks_exact(src.stride()));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().chunks_exact(src.stride()));
+    }
+    iter.for_each(|(dst, src)| {
+        let dst = &mut dst[..width * channels];
+        let src = &src[..width * channels];
+        for (dst, src) in dst
+            .chunks_exact_mut(channels)
+            .zip(src.chunks_exact(channels))
+        {
+            let rgb = Rgb::<u8>::new(
+                src[image_configuration.get_r_channel_offset()],
+                src[image_configuration.get_g_channel_offset()],
+                src[image_configuration.get_b_channel_offset()],
+            );
+            let (h, s, v) = match target {
+                HsvTarget::Hsv => {
+                    let hsv = rgb.to_hsv();
+                    (hsv.h, hsv.s, hsv.v)
+                }
+                HsvTarget::Hsl => {
+                    let hsl = rgb.to_hsl();
+                    (hsl.h, hsl.s, hsl.l)
+                }
+            };
+            dst[0] = h as u16;
+            dst[1] = (s * scale).round() as u16;
+            dst[2] = (v * scale).round() as u16;
+            if image_configuration.has_alpha() {
+                dst[3] = src[image_configuration.get_a_channel_offset()] as u16;
+            }
+        }
+    });
+    Ok(())
+}
+
+fn hsv_interleaved_to_generic_image<const IMAGE: u8, const TARGET: u8>(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    src.validate()?;
+    dst.validate()?;
+    dst.try_match_immutable(src)?;
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let target: HsvTarget = TARGET.into();
+    let channels = image_configuration.get_channels_count();
+
+    let inv_scale = 1f32 / scale;
+
+    let dst_stride = dst.stride();
+    let width = src.width as usize;
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .par_chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().par_chunks_exact(src.stride()));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().chunks_exact(src.stride()));
+    }
+    iter.for_each(|(dst, src)| {
+        let dst = &mut dst[..width * channels];
+        let src = &src[..width * channels];
+        for (dst, src) in dst
+            .chunks_exact_mut(channels)
+            .zip(src.chunks_exact(channels))
+        {
+            let h = src[0] as f32;
+            let s = src[1] as f32 * inv_scale;
+            let v = src[2] as f32 * inv_scale;
+            let rgb = match target {
+                HsvTarget::Hsv => Hsv::from_components(h, s, v).to_rgb8(),
+                HsvTarget::Hsl => Hsl::from_components(h, s, v).to_rgb8(),
+            };
+            dst[image_configuration.get_r_channel_offset()] = rgb.r;
+            dst[image_configuration.get_g_channel_offset()] = rgb.g;
+            dst[image_configuration.get_b_channel_offset()] = rgb.b;
+            if image_configuration.has_alpha() {
+                dst[image_configuration.get_a_channel_offset()] = src[3] as u8;
+            }
+        }
+    });
+    Ok(())
+}
+
+pub(crate) fn rgb_to_hsv(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn rgb_to_hsl(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsv_to_rgb(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsl_to_rgb(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Rgb as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn bgr_to_hsv(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn bgr_to_hsl(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsv_to_bgr(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsl_to_bgr(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Bgr as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsv_to_rgba(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsv_to_bgra(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsl_to_rgba(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn hsl_to_bgra(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    hsv_interleaved_to_generic_image::<{ ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn rgba_to_hsv(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn bgra_to_hsv(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsv as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn rgba_to_hsl(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Rgba as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
+
+pub(crate) fn bgra_to_hsl(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_hsv_interleaved::<{ ImageConfiguration::Bgra as u8 }, { HsvTarget::Hsl as u8 }>(
+        src, dst, scale,
+    )
+}
@@ -1,9 +1,12 @@
-#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+/// Pixel layout of interleaved image
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub enum ImageConfiguration {
     Rgb = 0,
     Rgba = 1,
     Bgra = 2,
     Bgr = 3,
+    Argb = 4,
+    Abgr = 5,
 }
 
 impl ImageConfiguration {
@@ -11,7 +14,10 @@ impl ImageConfiguration {
     pub fn get_channels_count(&self) -> usize {
         match self {
             ImageConfiguration::Rgb | ImageConfiguration::Bgr => 3,
-            ImageConfiguration::Rgba | ImageConfiguration::Bgra => 4,
+            ImageConfiguration::Rgba
+            | ImageConfiguration::Bgra
+            | ImageConfiguration::Argb
+            | ImageConfiguration::Abgr => 4,
         }
     }
 
@@ -19,7 +25,10 @@ impl ImageConfiguration {
     pub fn has_alpha(&self) -> bool {
         match self {
             ImageConfiguration::Rgb | ImageConfiguration::Bgr => false,
-            ImageConfiguration::Rgba | ImageConfiguration::Bgra => true,
+            ImageConfiguration::Rgba
+            | ImageConfiguration::Bgra
+            | ImageConfiguration::Argb
+            | ImageConfiguration::Abgr => true,
         }
     }
 
@@ -29,6 +38,8 @@ impl ImageConfiguration {
             ImageConfiguration::Rgb => 0,
             ImageConfiguration::Rgba => 0,
             ImageConfiguration::Bgra | ImageConfiguration::Bgr => 2,
+            ImageConfiguration::Argb => 1,
+            ImageConfiguration::Abgr => 3,
         }
     }
 
@@ -37,6 +48,7 @@ impl ImageConfiguration {
         match self {
             ImageConfiguration::Rgb | ImageConfiguration::Bgr => 1,
             ImageConfiguration::Rgba | ImageConfiguration::Bgra => 1,
+            ImageConfiguration::Argb | ImageConfiguration::Abgr => 2,
         }
     }
 
@@ -46,6 +58,8 @@ impl ImageConfiguration {
             ImageConfiguration::Rgb => 2,
             ImageConfiguration::Rgba => 2,
             ImageConfiguration::Bgra | ImageConfiguration::Bgr => 0,
+            ImageConfiguration::Argb => 3,
+            ImageConfiguration::Abgr => 1,
         }
     }
     #[inline(always)]
@@ -53,6 +67,7 @@ impl ImageConfiguration {
         match self {
             ImageConfiguration::Rgb | ImageConfiguration::Bgr => 0,
             ImageConfiguration::Rgba | ImageConfiguration::Bgra => 3,
+            ImageConfiguration::Argb | ImageConfiguration::Abgr => 0,
         }
     }
 }
@@ -65,6 +80,8 @@ impl From<u8> for ImageConfiguration {
             1 => ImageConfiguration::Rgba,
             2 => ImageConfiguration::Bgra,
             3 => ImageConfiguration::Bgr,
+            4 => ImageConfiguration::Argb,
+            5 => ImageConfiguration::Abgr,
             _ => {
                 panic!("Unknown value")
             }
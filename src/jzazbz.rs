@@ -1,13 +1,35 @@
 use std::slice;
 
 use crate::image_configuration::ImageConfiguration;
+use crate::sample::{
+    gamma_rgb, lightness_headroom, linearize_rgb, max_value_for_bit_depth, ImageSample,
+};
 use colorutils_rs::{Jzazbz, Rgb, TransferFunction};
+#[cfg(feature = "rayon")]
 use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
 use rayon::prelude::{ParallelSlice, ParallelSliceMut};
 
+/// Jz mapped to the last histogram bin: PQ encodes absolute luminance, so its lightness is
+/// quantized up to the peak PQ is able to represent, other transfer functions use whole [0, 1]
 #[inline]
-pub(crate) fn generic_image_to_jzazbz<const IMAGE: u8>(
-    src: &[u8],
+fn jz_peak(transfer_function: TransferFunction) -> f32 {
+    match transfer_function {
+        TransferFunction::Pq => {
+            let peak = linearize_rgb(Rgb::<f32>::new(1., 1., 1.), TransferFunction::Pq);
+            Jzazbz::from_linear_rgb_with_luminance(peak, 200.).jz
+        }
+        _ => 1f32,
+    }
+}
+
+/// Converts image into planar Jzazbz, where lightness stored in `dst` in range [0, scale]
+/// and other components with alpha are stored in `color_planes`.
+///
+/// Returns lightness headroom of the image, lightness above reference white is quantized up to it
+#[inline]
+pub(crate) fn generic_image_to_jzazbz<T: ImageSample, const IMAGE: u8>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -15,11 +37,19 @@ pub(crate) fn generic_image_to_jzazbz<const IMAGE: u8>(
     width: u32,
     height: u32,
     scale: f32,
-) {
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
     let image_configuration: ImageConfiguration = IMAGE.into();
     let channels = image_configuration.get_channels_count();
 
-    let full_scale = scale;
+    let peak = jz_peak(transfer_function);
+    let headroom =
+        lightness_headroom::<T, IMAGE>(src, src_stride, width, height, transfer_function, |rgb| {
+            Jzazbz::from_linear_rgb_with_luminance(rgb, 200.).jz / peak
+        });
+    let full_scale = scale / (peak * headroom);
+    let normalize_scale = 1f32 / max_value_for_bit_depth(bit_depth);
 
     let color_planes_stride = if image_configuration.has_alpha() {
         width as usize * 3usize
@@ -39,50 +69,69 @@ pub(crate) fn generic_image_to_jzazbz<const IMAGE: u8>(
         2usize
     };
 
-    dst_slice_safe_align
-        .par_chunks_exact_mut(dst_stride as usize)
-        .zip(color_planes.par_chunks_exact_mut(color_planes_stride))
-        .zip(src.par_chunks_exact(src_stride as usize))
-        .for_each(|((dst, color), src)| unsafe {
-            let dst_ptr = dst.as_mut_ptr() as *mut u16;
-            for x in 0..width as usize {
-                let px = x * channels;
-                let cx = x * color_planes_channels;
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst_slice_safe_align
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact_mut(color_planes_stride))
+            .zip(src.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst_slice_safe_align
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact_mut(color_planes_stride))
+            .zip(src.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let dst_ptr = dst.as_mut_ptr() as *mut u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
 
-                let rgb = Rgb::<u8>::new(
-                    *src.get_unchecked(px + image_configuration.get_r_channel_offset()),
-                    *src.get_unchecked(px + image_configuration.get_g_channel_offset()),
-                    *src.get_unchecked(px + image_configuration.get_b_channel_offset()),
-                );
-                let jzazbz = Jzazbz::from_rgb(rgb, TransferFunction::Srgb);
-                dst_ptr
-                    .add(x)
-                    .write_unaligned((jzazbz.jz * full_scale).round().min(scale) as u16);
-                *color.get_unchecked_mut(cx) = jzazbz.az;
-                *color.get_unchecked_mut(cx + 1) = jzazbz.bz;
-                if image_configuration.has_alpha() {
-                    let a = *src.get_unchecked(px + image_configuration.get_a_channel_offset());
-                    *color.get_unchecked_mut(cx + 2) = a as f32;
-                }
+            let rgb = Rgb::<f32>::new(
+                src.get_unchecked(px + image_configuration.get_r_channel_offset())
+                    .to_normalized(normalize_scale),
+                src.get_unchecked(px + image_configuration.get_g_channel_offset())
+                    .to_normalized(normalize_scale),
+                src.get_unchecked(px + image_configuration.get_b_channel_offset())
+                    .to_normalized(normalize_scale),
+            );
+            let jzazbz =
+                Jzazbz::from_linear_rgb_with_luminance(linearize_rgb(rgb, transfer_function), 200.);
+            dst_ptr
+                .add(x)
+                .write_unaligned((jzazbz.jz * full_scale).round().min(scale) as u16);
+            *color.get_unchecked_mut(cx) = jzazbz.az;
+            *color.get_unchecked_mut(cx + 1) = jzazbz.bz;
+            if image_configuration.has_alpha() {
+                let a = *src.get_unchecked(px + image_configuration.get_a_channel_offset());
+                *color.get_unchecked_mut(cx + 2) = a.to_normalized(normalize_scale);
             }
-        });
+        }
+    });
+    headroom
 }
 
 #[inline]
-pub(crate) fn jzazbz_to_generic_image<const IMAGE: u8>(
+pub(crate) fn jzazbz_to_generic_image<T: ImageSample, const IMAGE: u8>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
     let image_configuration: ImageConfiguration = IMAGE.into();
     let channels = image_configuration.get_channels_count();
 
-    let full_scale = 1. / scale;
+    let full_scale = jz_peak(transfer_function) / scale;
+    let max_value = max_value_for_bit_depth(bit_depth);
 
     let color_planes_stride = if image_configuration.has_alpha() {
         width as usize * 3usize
@@ -93,7 +142,7 @@ pub(crate) fn jzazbz_to_generic_image<const IMAGE: u8>(
     let src_slice_safe_align = unsafe {
         slice::from_raw_parts(
             src.as_ptr() as *const u8,
-            dst_stride as usize * height as usize,
+            src_stride as usize * height as usize,
         )
     };
 
@@ -103,36 +152,51 @@ pub(crate) fn jzazbz_to_generic_image<const IMAGE: u8>(
         2usize
     };
 
-    dst.par_chunks_exact_mut(dst_stride as usize)
-        .zip(color_planes.par_chunks_exact(color_planes_stride))
-        .zip(src_slice_safe_align.par_chunks_exact(src_stride as usize))
-        .for_each(|((dst, color), src)| unsafe {
-            let src_ptr = src.as_ptr() as *const u16;
-            for x in 0..width as usize {
-                let px = x * channels;
-                let cx = x * color_planes_channels;
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let src_ptr = src.as_ptr() as *const u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
 
-                let l = src_ptr.add(x).read_unaligned() as f32 * full_scale;
+            let l = src_ptr.add(x).read_unaligned() as f32 * full_scale;
 
-                let a = *color.get_unchecked(cx);
-                let b = *color.get_unchecked(cx + 1);
+            let a = *color.get_unchecked(cx);
+            let b = *color.get_unchecked(cx + 1);
 
-                let rgb = Jzazbz::new(l, a, b);
-                let rgb = rgb.to_rgb(TransferFunction::Srgb);
-                *dst.get_unchecked_mut(px + image_configuration.get_r_channel_offset()) = rgb.r;
-                *dst.get_unchecked_mut(px + image_configuration.get_g_channel_offset()) = rgb.g;
-                *dst.get_unchecked_mut(px + image_configuration.get_b_channel_offset()) = rgb.b;
-                if image_configuration.has_alpha() {
-                    let a = *color.get_unchecked(cx + 2);
-                    *dst.get_unchecked_mut(px + image_configuration.get_a_channel_offset()) =
-                        a as u8;
-                }
+            let rgb = Jzazbz::new(l, a, b);
+            let rgb = gamma_rgb(rgb.to_linear_rgb(), transfer_function);
+            *dst.get_unchecked_mut(px + image_configuration.get_r_channel_offset()) =
+                T::from_normalized(rgb.r, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_g_channel_offset()) =
+                T::from_normalized(rgb.g, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_b_channel_offset()) =
+                T::from_normalized(rgb.b, max_value);
+            if image_configuration.has_alpha() {
+                let a = *color.get_unchecked(cx + 2);
+                *dst.get_unchecked_mut(px + image_configuration.get_a_channel_offset()) =
+                    T::from_normalized(a, max_value);
             }
-        });
+        }
+    });
 }
 
-pub(crate) fn rgb_to_jzazbz(
-    src: &[u8],
+pub(crate) fn rgb_to_jzazbz<T: ImageSample>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -140,8 +204,10 @@ pub(crate) fn rgb_to_jzazbz(
     width: u32,
     height: u32,
     scale: f32,
-) {
-    generic_image_to_jzazbz::<{ ImageConfiguration::Rgb as u8 }>(
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_jzazbz::<T, { ImageConfiguration::Rgb as u8 }>(
         src,
         src_stride,
         dst,
@@ -150,11 +216,13 @@ pub(crate) fn rgb_to_jzazbz(
         width,
         height,
         scale,
-    );
+        bit_depth,
+        transfer_function,
+    )
 }
 
-pub(crate) fn bgra_to_jzazbz(
-    src: &[u8],
+pub(crate) fn bgr_to_jzazbz<T: ImageSample>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -162,8 +230,10 @@ pub(crate) fn bgra_to_jzazbz(
     width: u32,
     height: u32,
     scale: f32,
-) {
-    generic_image_to_jzazbz::<{ ImageConfiguration::Bgra as u8 }>(
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_jzazbz::<T, { ImageConfiguration::Bgr as u8 }>(
         src,
         src_stride,
         dst,
@@ -172,11 +242,13 @@ pub(crate) fn bgra_to_jzazbz(
         width,
         height,
         scale,
-    );
+        bit_depth,
+        transfer_function,
+    )
 }
 
-pub(crate) fn rgba_to_jzazbz(
-    src: &[u8],
+pub(crate) fn bgra_to_jzazbz<T: ImageSample>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -184,8 +256,36 @@ pub(crate) fn rgba_to_jzazbz(
     width: u32,
     height: u32,
     scale: f32,
-) {
-    generic_image_to_jzazbz::<{ ImageConfiguration::Rgba as u8 }>(
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_jzazbz::<T, { ImageConfiguration::Bgra as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        color_planes,
+        width,
+        height,
+        scale,
+        bit_depth,
+        transfer_function,
+    )
+}
+
+pub(crate) fn rgba_to_jzazbz<T: ImageSample>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    color_planes: &mut [f32],
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_jzazbz::<T, { ImageConfiguration::Rgba as u8 }>(
         src,
         src_stride,
         dst,
@@ -194,20 +294,50 @@ pub(crate) fn rgba_to_jzazbz(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
+    )
+}
+
+pub(crate) fn jzazbz_to_rgb<T: ImageSample>(
+    src: &[u16],
+    src_stride: u32,
+    color_planes: &[f32],
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) {
+    jzazbz_to_generic_image::<T, { ImageConfiguration::Rgb as u8 }>(
+        src,
+        src_stride,
+        color_planes,
+        dst,
+        dst_stride,
+        width,
+        height,
+        scale,
+        bit_depth,
+        transfer_function,
     );
 }
 
-pub(crate) fn jzazbz_to_rgb(
+pub(crate) fn jzazbz_to_bgr<T: ImageSample>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
-    jzazbz_to_generic_image::<{ ImageConfiguration::Rgb as u8 }>(
+    jzazbz_to_generic_image::<T, { ImageConfiguration::Bgr as u8 }>(
         src,
         src_stride,
         color_planes,
@@ -216,20 +346,24 @@ pub(crate) fn jzazbz_to_rgb(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
     );
 }
 
-pub(crate) fn jzazbz_to_bgra(
+pub(crate) fn jzazbz_to_bgra<T: ImageSample>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
-    jzazbz_to_generic_image::<{ ImageConfiguration::Bgra as u8 }>(
+    jzazbz_to_generic_image::<T, { ImageConfiguration::Bgra as u8 }>(
         src,
         src_stride,
         color_planes,
@@ -238,20 +372,24 @@ pub(crate) fn jzazbz_to_bgra(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
     );
 }
 
-pub(crate) fn jzazbz_to_rgba(
+pub(crate) fn jzazbz_to_rgba<T: ImageSample>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
-    jzazbz_to_generic_image::<{ ImageConfiguration::Rgba as u8 }>(
+    jzazbz_to_generic_image::<T, { ImageConfiguration::Rgba as u8 }>(
         src,
         src_stride,
         color_planes,
@@ -260,5 +398,7 @@ pub(crate) fn jzazbz_to_rgba(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
     );
 }
@@ -1,5 +1,8 @@
 #![allow(clippy::too_many_arguments, clippy::type_complexity)]
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "avx"))]
+mod avx;
 mod clahe_call_proxy;
+mod clahe_declarations_gray;
 mod clahe_declarations_hsv;
 mod clahe_declarations_jzazbz;
 mod clahe_declarations_lab;
@@ -7,20 +10,52 @@ mod clahe_declarations_luv;
 mod clahe_declarations_oklab;
 mod clahe_declarations_oklch;
 mod clahe_declarations_yuv;
+mod clahe_declarations_yuv_planar;
+mod clahe_gray_impl;
 mod clahe_impl;
+mod clahe_opencv;
+mod clahe_sliding;
+mod clahe_tiles;
 mod clahe_yuv_impl;
+mod context;
+mod dispatch;
+mod equalizer;
+mod error;
+mod hist_equal_call_proxy;
 mod hist_equal_decl;
+mod hist_equal_decl_gray;
+mod hist_equal_decl_jzazbz;
+mod hist_equal_decl_oklab;
+mod hist_equal_decl_oklch;
 mod hist_equal_decl_yuv;
+mod hist_equal_decl_yuv_planar;
+mod hist_equal_gray_impl;
 mod hist_equal_impl;
 mod hist_equal_yuv_impl;
+mod hist_match_decl;
+mod hist_match_decl_gray;
+mod hist_match_decl_yuv;
+mod hist_match_impl;
 mod hist_support;
+mod histogram;
+mod hsv;
 mod image_configuration;
 mod jzazbz;
 mod lab;
 mod luv;
+mod mask;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon;
 mod oklab;
 mod oklch;
+mod sample;
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+mod sse;
+mod video_equalizer;
+mod yuv_planar_impl;
+mod yuv_support;
 
+pub use clahe_declarations_gray::*;
 pub use clahe_declarations_hsv::*;
 pub use clahe_declarations_jzazbz::*;
 pub use clahe_declarations_lab::*;
@@ -28,6 +63,28 @@ pub use clahe_declarations_luv::*;
 pub use clahe_declarations_oklab::*;
 pub use clahe_declarations_oklch::*;
 pub use clahe_declarations_yuv::*;
+pub use clahe_declarations_yuv_planar::*;
+pub use context::EqualizerContext;
+pub use equalizer::*;
+pub use error::*;
 pub use hist_equal_decl::*;
+pub use hist_equal_decl_gray::*;
+pub use hist_equal_decl_jzazbz::*;
+pub use hist_equal_decl_oklab::*;
+pub use hist_equal_decl_oklch::*;
 pub use hist_equal_decl_yuv::*;
+pub use hist_equal_decl_yuv_planar::*;
+pub use hist_match_decl::*;
+pub use hist_match_decl_gray::*;
+pub use hist_match_decl_yuv::*;
 pub use hist_support::*;
+pub use histogram::*;
+pub use image_configuration::ImageConfiguration;
+pub use mask::EqualizationRegion;
+pub use video_equalizer::VideoEqualizer;
+pub use yuv_support::YuvMatrix;
+
+pub use colorutils_rs::TransferFunction;
+pub use yuv::{
+    BufferStoreMut, YuvBiPlanarImageMut, YuvChromaSubsampling, YuvPlanarImageMut, YuvRange,
+};
@@ -1,8 +1,22 @@
+use std::slice;
+
 use crate::image_configuration::ImageConfiguration;
-use colorutils_rs::{ColorError, ImageBuffer, ImageBufferMut, Luv, Rgb};
+use crate::sample::{
+    gamma_rgb, lightness_headroom, linearize_rgb, max_value_for_bit_depth, ImageSample,
+};
+use colorutils_rs::{
+    ColorError, ImageBuffer, ImageBufferMut, Luv, Rgb, TransferFunction, Xyz, SRGB_TO_XYZ_D65,
+    XYZ_TO_SRGB_D65,
+};
+#[cfg(feature = "rayon")]
 use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
 use rayon::prelude::{ParallelSlice, ParallelSliceMut};
 
+/// Interleaved u and v are stored in `u16` with 0.01 precision shifted by the offset,
+/// it covers [-300, 355] and so every color of sRGB
+const CHROMA_OFFSET: f32 = 300f32;
+
 pub(crate) fn generic_image_to_luv<const IMAGE: u8>(
     src: &ImageBuffer<'_, u8>,
     dst: &mut ImageBufferMut<'_, u16>,
@@ -18,52 +32,65 @@ pub(crate) fn generic_image_to_luv<const IMAGE: u8>(
 
     let dst_stride = dst.stride();
     let width = src.width as usize;
-    dst.data
-        .borrow_mut()
-        .par_chunks_exact_mut(dst_stride)
-        .zip(src.data.as_ref().par_chunks_exact(src.stride()))
-        .for_each(|(dst, src)| {
-            let dst = &mut dst[..width * channels];
-            let src = &src[..width * channels];
-            if image_configuration.has_alpha() {
-                for (dst, src) in dst
-                    .as_chunks_mut::<4>()
-                    .0
-                    .iter_mut()
-                    .zip(src.as_chunks::<4>().0.iter())
-                {
-                    let rgb = Rgb::<u8>::new(
-                        src[image_configuration.get_r_channel_offset()],
-                        src[image_configuration.get_g_channel_offset()],
-                        src[image_configuration.get_b_channel_offset()],
-                    );
-                    let luv = rgb.to_luv();
-
-                    dst[0] = (luv.l * full_scale).round().min(scale) as u16;
-                    dst[1] = (luv.u * 100f32 + 100f32).round() as u16;
-                    dst[2] = (luv.v * 100f32 + 100f32).round() as u16;
-                    dst[3] = src[image_configuration.get_a_channel_offset()] as u16;
-                }
-            } else {
-                for (dst, src) in dst
-                    .as_chunks_mut::<3>()
-                    .0
-                    .iter_mut()
-                    .zip(src.as_chunks::<3>().0.iter())
-                {
-                    let rgb = Rgb::<u8>::new(
-                        src[image_configuration.get_r_channel_offset()],
-                        src[image_configuration.get_g_channel_offset()],
-                        src[image_configuration.get_b_channel_offset()],
-                    );
-                    let luv = rgb.to_luv();
-
-                    dst[0] = (luv.l * full_scale).round().min(scale) as u16;
-                    dst[1] = (luv.u * 100f32 + 100f32).round() as u16;
-                    dst[2] = (luv.v * 100f32 + 100f32).round() as u16;
-                }
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .par_chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().par_chunks_exact(src.stride()));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().chunks_exact(src.stride()));
+    }
+    iter.for_each(|(dst, src)| {
+        let dst = &mut dst[..width * channels];
+        let src = &src[..width * channels];
+        if image_configuration.has_alpha() {
+            for (dst, src) in dst
+                .as_chunks_mut::<4>()
+                .0
+                .iter_mut()
+                .zip(src.as_chunks::<4>().0.iter())
+            {
+                let rgb = Rgb::<u8>::new(
+                    src[image_configuration.get_r_channel_offset()],
+                    src[image_configuration.get_g_channel_offset()],
+                    src[image_configuration.get_b_channel_offset()],
+                );
+                let luv = rgb.to_luv();
+
+                dst[0] = (luv.l * full_scale).round().min(scale) as u16;
+                dst[1] = ((luv.u + CHROMA_OFFSET) * 100f32).round() as u16;
+                dst[2] = ((luv.v + CHROMA_OFFSET) * 100f32).round() as u16;
+                dst[3] = src[image_configuration.get_a_channel_offset()] as u16;
             }
-        });
+        } else {
+            for (dst, src) in dst
+                .as_chunks_mut::<3>()
+                .0
+                .iter_mut()
+                .zip(src.as_chunks::<3>().0.iter())
+            {
+                let rgb = Rgb::<u8>::new(
+                    src[image_configuration.get_r_channel_offset()],
+                    src[image_configuration.get_g_channel_offset()],
+                    src[image_configuration.get_b_channel_offset()],
+                );
+                let luv = rgb.to_luv();
+
+                dst[0] = (luv.l * full_scale).round().min(scale) as u16;
+                dst[1] = ((luv.u + CHROMA_OFFSET) * 100f32).round() as u16;
+                dst[2] = ((luv.v + CHROMA_OFFSET) * 100f32).round() as u16;
+            }
+        }
+    });
     Ok(())
 }
 
@@ -79,54 +106,67 @@ pub(crate) fn luv_to_generic_image<const IMAGE: u8>(
 
     let dst_stride = dst.stride();
     let width = src.width as usize;
-    dst.data
-        .borrow_mut()
-        .par_chunks_exact_mut(dst_stride)
-        .zip(src.data.as_ref().par_chunks_exact(src.stride()))
-        .for_each(|(dst, src)| {
-            let dst = &mut dst[..width * channels];
-            let src = &src[..width * channels];
-            if image_configuration.has_alpha() {
-                for (dst, src) in dst
-                    .as_chunks_mut::<4>()
-                    .0
-                    .iter_mut()
-                    .zip(src.as_chunks::<4>().0.iter())
-                {
-                    let l = src[0] as f32 * full_scale;
-
-                    let a = (src[1] as f32 - 100f32) * (1. / 100.);
-                    let b = (src[2] as f32 - 100f32) * (1. / 100.);
-
-                    let rgb = Luv::new(l, a, b);
-                    let rgb = rgb.to_rgb();
-
-                    dst[image_configuration.get_r_channel_offset()] = rgb.r;
-                    dst[image_configuration.get_g_channel_offset()] = rgb.g;
-                    dst[image_configuration.get_b_channel_offset()] = rgb.b;
-                    dst[image_configuration.get_a_channel_offset()] = src[3] as u8;
-                }
-            } else {
-                for (dst, src) in dst
-                    .as_chunks_mut::<3>()
-                    .0
-                    .iter_mut()
-                    .zip(src.as_chunks::<3>().0.iter())
-                {
-                    let l = src[0] as f32 * full_scale;
-
-                    let a = (src[1] as f32 - 100f32) * (1. / 100.);
-                    let b = (src[2] as f32 - 100f32) * (1. / 100.);
-
-                    let rgb = Luv::new(l, a, b);
-                    let rgb = rgb.to_rgb();
-
-                    dst[image_configuration.get_r_channel_offset()] = rgb.r;
-                    dst[image_configuration.get_g_channel_offset()] = rgb.g;
-                    dst[image_configuration.get_b_channel_offset()] = rgb.b;
-                }
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .par_chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().par_chunks_exact(src.stride()));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .data
+            .borrow_mut()
+            .chunks_exact_mut(dst_stride)
+            .zip(src.data.as_ref().chunks_exact(src.stride()));
+    }
+    iter.for_each(|(dst, src)| {
+        let dst = &mut dst[..width * channels];
+        let src = &src[..width * channels];
+        if image_configuration.has_alpha() {
+            for (dst, src) in dst
+                .as_chunks_mut::<4>()
+                .0
+                .iter_mut()
+                .zip(src.as_chunks::<4>().0.iter())
+            {
+                let l = src[0] as f32 * full_scale;
+
+                let a = src[1] as f32 * (1. / 100.) - CHROMA_OFFSET;
+                let b = src[2] as f32 * (1. / 100.) - CHROMA_OFFSET;
+
+                let rgb = Luv::new(l, a, b);
+                let rgb = rgb.to_rgb();
+
+                dst[image_configuration.get_r_channel_offset()] = rgb.r;
+                dst[image_configuration.get_g_channel_offset()] = rgb.g;
+                dst[image_configuration.get_b_channel_offset()] = rgb.b;
+                dst[image_configuration.get_a_channel_offset()] = src[3] as u8;
             }
-        });
+        } else {
+            for (dst, src) in dst
+                .as_chunks_mut::<3>()
+                .0
+                .iter_mut()
+                .zip(src.as_chunks::<3>().0.iter())
+            {
+                let l = src[0] as f32 * full_scale;
+
+                let a = src[1] as f32 * (1. / 100.) - CHROMA_OFFSET;
+                let b = src[2] as f32 * (1. / 100.) - CHROMA_OFFSET;
+
+                let rgb = Luv::new(l, a, b);
+                let rgb = rgb.to_rgb();
+
+                dst[image_configuration.get_r_channel_offset()] = rgb.r;
+                dst[image_configuration.get_g_channel_offset()] = rgb.g;
+                dst[image_configuration.get_b_channel_offset()] = rgb.b;
+            }
+        }
+    });
     Ok(())
 }
 
@@ -154,6 +194,14 @@ pub(crate) fn rgba_to_luv(
     generic_image_to_luv::<{ ImageConfiguration::Rgba as u8 }>(src, dst, scale)
 }
 
+pub(crate) fn bgr_to_luv(
+    src: &ImageBuffer<'_, u8>,
+    dst: &mut ImageBufferMut<'_, u16>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    generic_image_to_luv::<{ ImageConfiguration::Bgr as u8 }>(src, dst, scale)
+}
+
 pub(crate) fn luv_to_rgb(
     src: &ImageBuffer<'_, u16>,
     dst: &mut ImageBufferMut<'_, u8>,
@@ -177,3 +225,234 @@ pub(crate) fn luv_to_rgba(
 ) -> Result<(), ColorError> {
     luv_to_generic_image::<{ ImageConfiguration::Rgba as u8 }>(src, dst, scale)
 }
+
+pub(crate) fn luv_to_bgr(
+    src: &ImageBuffer<'_, u16>,
+    dst: &mut ImageBufferMut<'_, u8>,
+    scale: f32,
+) -> Result<(), ColorError> {
+    luv_to_generic_image::<{ ImageConfiguration::Bgr as u8 }>(src, dst, scale)
+}
+
+/// Converts image into planar Luv, where L stored in `dst` in range [0, scale]
+/// and other components with alpha are stored in `color_planes`.
+///
+/// Returns lightness headroom of the image, L above reference white is quantized up to it
+pub(crate) fn generic_image_to_luv_planar<T: ImageSample, const IMAGE: u8>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    color_planes: &mut [f32],
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let channels = image_configuration.get_channels_count();
+
+    let headroom =
+        lightness_headroom::<T, IMAGE>(src, src_stride, width, height, transfer_function, |rgb| {
+            Luv::from_xyz(Xyz::from_linear_rgb(rgb, &SRGB_TO_XYZ_D65)).l / 100f32
+        });
+    let full_scale = scale / (100f32 * headroom);
+    let normalize_scale = 1f32 / max_value_for_bit_depth(bit_depth);
+
+    let color_planes_channels = if image_configuration.has_alpha() {
+        3usize
+    } else {
+        2usize
+    };
+
+    let color_planes_stride = width as usize * color_planes_channels;
+
+    let dst_slice_safe_align = unsafe {
+        slice::from_raw_parts_mut(
+            dst.as_mut_ptr() as *mut u8,
+            dst_stride as usize * height as usize,
+        )
+    };
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst_slice_safe_align
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact_mut(color_planes_stride))
+            .zip(src.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst_slice_safe_align
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact_mut(color_planes_stride))
+            .zip(src.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let dst_ptr = dst.as_mut_ptr() as *mut u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
+
+            let rgb = Rgb::<f32>::new(
+                src.get_unchecked(px + image_configuration.get_r_channel_offset())
+                    .to_normalized(normalize_scale),
+                src.get_unchecked(px + image_configuration.get_g_channel_offset())
+                    .to_normalized(normalize_scale),
+                src.get_unchecked(px + image_configuration.get_b_channel_offset())
+                    .to_normalized(normalize_scale),
+            );
+            let xyz = Xyz::from_linear_rgb(linearize_rgb(rgb, transfer_function), &SRGB_TO_XYZ_D65);
+            let luv = Luv::from_xyz(xyz);
+            let value = (luv.l * full_scale).round().min(scale) as u16;
+            dst_ptr.add(x).write_unaligned(value);
+            *color.get_unchecked_mut(cx) = luv.u;
+            *color.get_unchecked_mut(cx + 1) = luv.v;
+            if image_configuration.has_alpha() {
+                let a = *src.get_unchecked(px + image_configuration.get_a_channel_offset());
+                *color.get_unchecked_mut(cx + 2) = a.to_normalized(normalize_scale);
+            }
+        }
+    });
+    headroom
+}
+
+pub(crate) fn luv_planar_to_generic_image<T: ImageSample, const IMAGE: u8>(
+    src: &[u16],
+    src_stride: u32,
+    color_planes: &[f32],
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) {
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let channels = image_configuration.get_channels_count();
+
+    let full_scale = 100f32 / scale;
+    let max_value = max_value_for_bit_depth(bit_depth);
+
+    let color_planes_channels = if image_configuration.has_alpha() {
+        3usize
+    } else {
+        2usize
+    };
+
+    let color_planes_stride = width as usize * color_planes_channels;
+
+    let src_slice_safe_align = unsafe {
+        slice::from_raw_parts(
+            src.as_ptr() as *const u8,
+            src_stride as usize * height as usize,
+        )
+    };
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let src_ptr = src.as_ptr() as *const u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
+
+            let l = src_ptr.add(x).read_unaligned() as f32 * full_scale;
+
+            let c1 = *color.get_unchecked(cx);
+            let c2 = *color.get_unchecked(cx + 1);
+
+            let rgb = gamma_rgb(
+                Luv::new(l, c1, c2).to_linear_rgb(&XYZ_TO_SRGB_D65),
+                transfer_function,
+            );
+            *dst.get_unchecked_mut(px + image_configuration.get_r_channel_offset()) =
+                T::from_normalized(rgb.r, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_g_channel_offset()) =
+                T::from_normalized(rgb.g, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_b_channel_offset()) =
+                T::from_normalized(rgb.b, max_value);
+            if image_configuration.has_alpha() {
+                let a = *color.get_unchecked(cx + 2);
+                *dst.get_unchecked_mut(px + image_configuration.get_a_channel_offset()) =
+                    T::from_normalized(a, max_value);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use colorutils_rs::BufferStore;
+
+    /// Saturated greens and blues have components far below -1, packing has to keep them
+    #[test]
+    fn packed_round_trip() {
+        let src: Vec<u8> = (0..=255u8)
+            .step_by(15)
+            .flat_map(|r| {
+                (0..=255u8)
+                    .step_by(15)
+                    .flat_map(move |g| (0..=255u8).step_by(15).flat_map(move |b| [r, g, b]))
+            })
+            .collect();
+        let width = (src.len() / 3) as u32;
+        let mut packed = vec![0u16; src.len()];
+        let mut dst = vec![0u8; src.len()];
+
+        let src_image = ImageBuffer {
+            data: std::borrow::Cow::Borrowed(src.as_slice()),
+            stride: width * 3,
+            width,
+            height: 1,
+            channels: 3,
+        };
+        let mut packed_image = ImageBufferMut {
+            data: BufferStore::Borrowed(&mut packed),
+            stride: width * 3,
+            width,
+            height: 1,
+            channels: 3,
+        };
+        rgb_to_luv(&src_image, &mut packed_image, 65535f32).unwrap();
+
+        let packed_image = ImageBuffer {
+            data: std::borrow::Cow::Borrowed(packed.as_slice()),
+            stride: width * 3,
+            width,
+            height: 1,
+            channels: 3,
+        };
+        let mut dst_image = ImageBufferMut {
+            data: BufferStore::Borrowed(&mut dst),
+            stride: width * 3,
+            width,
+            height: 1,
+            channels: 3,
+        };
+        luv_to_rgb(&packed_image, &mut dst_image, 65535f32).unwrap();
+
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact(3)) {
+            for (&a, &b) in src.iter().zip(dst.iter()) {
+                assert!(a.abs_diff(b) <= 1, "{src:?} -> {dst:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use crate::error::{check_region, HistogramError, MismatchedSize};
+use crate::hist_support::ImageRegion;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::{ParallelSlice, ParallelSliceMut};
+
+/// Part of the image equalized by [crate::Equalizer::apply_masked]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EqualizationRegion<'a> {
+    /// Rectangle of the image
+    Rect(ImageRegion),
+    /// Pixels where `mask` is non zero, mask has one value per pixel and `stride` values per row
+    Mask { mask: &'a [u8], stride: u32 },
+}
+
+/// Pixels histograms count, a rectangle is kept as its bounds
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum HistogramMask<'a> {
+    /// Pixels inside the rectangle
+    Rect(ImageRegion),
+    /// Pixels with non zero value, mask has `stride` values per row
+    Pixels { mask: &'a [u8], stride: usize },
+}
+
+impl<'a> HistogramMask<'a> {
+    /// Mask of the region of `width` x `height` image, checks that the region fits it
+    pub(crate) fn new(
+        region: EqualizationRegion<'a>,
+        width: u32,
+        height: u32,
+    ) -> Result<HistogramMask<'a>, HistogramError> {
+        match region {
+            EqualizationRegion::Rect(rect) => {
+                check_region(rect, width, height)?;
+                Ok(HistogramMask::Rect(rect))
+            }
+            EqualizationRegion::Mask { mask, stride } => {
+                if stride < width {
+                    return Err(HistogramError::MaskStrideMismatch(MismatchedSize {
+                        expected: width as usize,
+                        received: stride as usize,
+                    }));
+                }
+                let required = stride as usize * (height as usize - 1) + width as usize;
+                if mask.len() < required {
+                    return Err(HistogramError::MaskSizeMismatch(MismatchedSize {
+                        expected: required,
+                        received: mask.len(),
+                    }));
+                }
+                Ok(HistogramMask::Pixels {
+                    mask,
+                    stride: stride as usize,
+                })
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, x: usize, y: usize) -> bool {
+        match *self {
+            HistogramMask::Rect(rect) => {
+                x >= rect.x as usize
+                    && x < (rect.x + rect.width) as usize
+                    && y >= rect.y as usize
+                    && y < (rect.y + rect.height) as usize
+            }
+            HistogramMask::Pixels { mask, stride } => mask[y * stride + x] != 0,
+        }
+    }
+}
+
+/// Count of `start..end` covered by `start_in..end_in`
+#[inline]
+fn overlap(start: usize, end: usize, start_in: usize, end_in: usize) -> usize {
+    end.min(end_in).saturating_sub(start.max(start_in))
+}
+
+/// Weight of equalized value for every pixel, 1 inside the mask and 0 outside.
+///
+/// With `feather_radius` weight rises inside the mask from 0 at its edge to 1 at
+/// `feather_radius` pixels from it: mask is box blurred, windows are cut by image borders,
+/// and blurred value `b` gives weight `2 * b - 1`, so pixels outside are never touched.
+pub(crate) fn mask_weights(
+    mask: HistogramMask<'_>,
+    width: usize,
+    height: usize,
+    feather_radius: u32,
+) -> Vec<f32> {
+    let (mask, mask_stride) = match mask {
+        HistogramMask::Rect(rect) => return rect_weights(rect, width, height, feather_radius),
+        HistogramMask::Pixels { mask, stride } => (mask, stride),
+    };
+    let mask_row = |y: usize| &mask[y * mask_stride..y * mask_stride + width];
+
+    let mut weights = vec![0f32; width * height];
+    if feather_radius == 0 {
+        for (y, row) in weights.chunks_exact_mut(width).enumerate() {
+            for (dst, &src) in row.iter_mut().zip(mask_row(y).iter()) {
+                *dst = if src != 0 { 1f32 } else { 0f32 };
+            }
+        }
+        return weights;
+    }
+
+    let radius = feather_radius as usize;
+
+    // Horizontal running sums of set pixels
+    let mut horizontal = vec![0u32; width * height];
+    for (y, row) in horizontal.chunks_exact_mut(width).enumerate() {
+        let mask_row = mask_row(y);
+        let mut sum = mask_row[..radius.min(width - 1) + 1]
+            .iter()
+            .filter(|&&x| x != 0)
+            .count() as u32;
+        for (x, dst) in row.iter_mut().enumerate() {
+            if x > 0 {
+                if x + radius < width && mask_row[x + radius] != 0 {
+                    sum += 1;
+                }
+                if x > radius && mask_row[x - radius - 1] != 0 {
+                    sum -= 1;
+                }
+            }
+            *dst = sum;
+        }
+    }
+
+    // Vertical running sums over the horizontal ones
+    let mut sums = vec![0u32; width];
+    for row in horizontal
+        .chunks_exact(width)
+        .take(radius.min(height - 1) + 1)
+    {
+        for (dst, &src) in sums.iter_mut().zip(row.iter()) {
+            *dst += src;
+        }
+    }
+    for (y, row) in weights.chunks_exact_mut(width).enumerate() {
+        if y > 0 {
+            if y + radius < height {
+                let entering = &horizontal[(y + radius) * width..(y + radius + 1) * width];
+                for (dst, &src) in sums.iter_mut().zip(entering.iter()) {
+                    *dst += src;
+                }
+            }
+            if y > radius {
+                let leaving = &horizontal[(y - radius - 1) * width..(y - radius) * width];
+                for (dst, &src) in sums.iter_mut().zip(leaving.iter()) {
+                    *dst -= src;
+                }
+            }
+        }
+        let window_height = (y + radius).min(height - 1) - y.saturating_sub(radius) + 1;
+        for (x, (dst, &sum)) in row.iter_mut().zip(sums.iter()).enumerate() {
+            let window_width = (x + radius).min(width - 1) - x.saturating_sub(radius) + 1;
+            let blurred = sum as f32 / (window_width * window_height) as f32;
+            *dst = if mask_row(y)[x] != 0 {
+                (2f32 * blurred - 1f32).clamp(0f32, 1f32)
+            } else {
+                0f32
+            };
+        }
+    }
+    weights
+}
+
+/// [mask_weights] of a rectangle, count of its pixels in a window is the product of the
+/// counts covered along each axis, so the rectangle isn't rasterized
+fn rect_weights(rect: ImageRegion, width: usize, height: usize, feather_radius: u32) -> Vec<f32> {
+    let radius = feather_radius as usize;
+    let (left, right) = (rect.x as usize, (rect.x + rect.width) as usize);
+    let (top, bottom) = (rect.y as usize, (rect.y + rect.height) as usize);
+    // Covered and whole length of the window around every position along an axis
+    let covered = |size: usize, start: usize, end: usize| -> Vec<(usize, usize)> {
+        (0..size)
+            .map(|i| {
+                let (window_start, window_end) =
+                    (i.saturating_sub(radius), (i + radius + 1).min(size));
+                (
+                    overlap(window_start, window_end, start, end),
+                    window_end - window_start,
+                )
+            })
+            .collect()
+    };
+    let columns = covered(width, left, right);
+    let rows = covered(height, top, bottom);
+
+    let mut weights = vec![0f32; width * height];
+    for (y, row) in weights
+        .chunks_exact_mut(width)
+        .enumerate()
+        .take(bottom)
+        .skip(top)
+    {
+        let (covered_height, window_height) = rows[y];
+        for (dst, &(covered_width, window_width)) in
+            row[left..right].iter_mut().zip(columns[left..right].iter())
+        {
+            *dst = if radius == 0 {
+                1f32
+            } else {
+                let blurred =
+                    (covered_width * covered_height) as f32 / (window_width * window_height) as f32;
+                (2f32 * blurred - 1f32).clamp(0f32, 1f32)
+            };
+        }
+    }
+    weights
+}
+
+/// Blends equalized `dst` with `src` by per pixel `weights`, pixels with zero weight
+/// are copied from `src`
+pub(crate) fn blend_by_weights(
+    src: &[u8],
+    src_stride: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    width: usize,
+    channels: usize,
+    weights: &[f32],
+) {
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .par_chunks_mut(dst_stride)
+            .zip(src.par_chunks(src_stride))
+            .zip(weights.par_chunks_exact(width));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .chunks_mut(dst_stride)
+            .zip(src.chunks(src_stride))
+            .zip(weights.chunks_exact(width));
+    }
+    iter.for_each(|((dst, src), weights)| {
+        for ((dst, src), &weight) in dst[..width * channels]
+            .chunks_exact_mut(channels)
+            .zip(src[..width * channels].chunks_exact(channels))
+            .zip(weights.iter())
+        {
+            if weight == 0f32 {
+                dst.copy_from_slice(src);
+            } else if weight < 1f32 {
+                for (dst, &src) in dst.iter_mut().zip(src.iter()) {
+                    let blended = src as f32 + (*dst as f32 - src as f32) * weight;
+                    *dst = blended.round().clamp(0f32, 255f32) as u8;
+                }
+            }
+        }
+    });
+}
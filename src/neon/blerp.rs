@@ -0,0 +1,47 @@
+use crate::dispatch::blerp_block_scalar;
+use std::arch::aarch64::*;
+
+/// Interpolates 4 pixels at once, multiplications and additions are not fused
+/// so results are identical to scalar `blerp`
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn neon_blerp_block(
+    c00: &[f32],
+    c10: &[f32],
+    c01: &[f32],
+    c11: &[f32],
+    tx: &[f32],
+    ty: f32,
+    dst: &mut [f32],
+) {
+    let length = dst.len();
+    let v_ones = vdupq_n_f32(1f32);
+    let v_ty = vdupq_n_f32(ty);
+    let v_ty1 = vdupq_n_f32(1f32 - ty);
+
+    let mut x = 0usize;
+    while x + 4 <= length {
+        let v_tx = vld1q_f32(tx.as_ptr().add(x));
+        let v_tx1 = vsubq_f32(v_ones, v_tx);
+        let top = vaddq_f32(
+            vmulq_f32(vld1q_f32(c00.as_ptr().add(x)), v_tx1),
+            vmulq_f32(vld1q_f32(c10.as_ptr().add(x)), v_tx),
+        );
+        let bottom = vaddq_f32(
+            vmulq_f32(vld1q_f32(c01.as_ptr().add(x)), v_tx1),
+            vmulq_f32(vld1q_f32(c11.as_ptr().add(x)), v_tx),
+        );
+        let v = vaddq_f32(vmulq_f32(top, v_ty1), vmulq_f32(bottom, v_ty));
+        vst1q_f32(dst.as_mut_ptr().add(x), v);
+        x += 4;
+    }
+
+    blerp_block_scalar(
+        &c00[x..length],
+        &c10[x..length],
+        &c01[x..length],
+        &c11[x..length],
+        &tx[x..length],
+        ty,
+        &mut dst[x..],
+    );
+}
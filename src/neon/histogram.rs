@@ -0,0 +1,52 @@
+use crate::dispatch::count_row_scalar;
+use std::arch::aarch64::*;
+
+/// Clamps 16 samples at once and counts them into sub-histograms
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn neon_count_row_u8(
+    row: &[u8],
+    max_bin: usize,
+    bins: usize,
+    lanes: usize,
+    histograms: &mut [u32],
+) {
+    let lane_mask = lanes - 1;
+    let v_max = vdupq_n_u8(max_bin.min(u8::MAX as usize) as u8);
+    let mut values = [0u8; 16];
+
+    let mut chunks = row.chunks_exact(16);
+    for chunk in &mut chunks {
+        let v = vminq_u8(vld1q_u8(chunk.as_ptr()), v_max);
+        vst1q_u8(values.as_mut_ptr(), v);
+        for (i, &value) in values.iter().enumerate() {
+            *histograms.get_unchecked_mut((i & lane_mask) * bins + value as usize) += 1;
+        }
+    }
+
+    count_row_scalar(chunks.remainder(), max_bin, bins, lanes, histograms);
+}
+
+/// Clamps 8 samples at once and counts them into sub-histograms
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn neon_count_row_u16(
+    row: &[u16],
+    max_bin: usize,
+    bins: usize,
+    lanes: usize,
+    histograms: &mut [u32],
+) {
+    let lane_mask = lanes - 1;
+    let v_max = vdupq_n_u16(max_bin.min(u16::MAX as usize) as u16);
+    let mut values = [0u16; 8];
+
+    let mut chunks = row.chunks_exact(8);
+    for chunk in &mut chunks {
+        let v = vminq_u16(vld1q_u16(chunk.as_ptr()), v_max);
+        vst1q_u16(values.as_mut_ptr(), v);
+        for (i, &value) in values.iter().enumerate() {
+            *histograms.get_unchecked_mut((i & lane_mask) * bins + value as usize) += 1;
+        }
+    }
+
+    count_row_scalar(chunks.remainder(), max_bin, bins, lanes, histograms);
+}
@@ -0,0 +1,5 @@
+mod blerp;
+mod histogram;
+
+pub(crate) use blerp::neon_blerp_block;
+pub(crate) use histogram::{neon_count_row_u16, neon_count_row_u8};
@@ -1,12 +1,21 @@
 use std::slice;
 
 use crate::image_configuration::ImageConfiguration;
+use crate::sample::{
+    gamma_rgb, lightness_headroom, linearize_rgb, max_value_for_bit_depth, ImageSample,
+};
 use colorutils_rs::{Oklab, Rgb, TransferFunction};
+#[cfg(feature = "rayon")]
 use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
 use rayon::prelude::{ParallelSlice, ParallelSliceMut};
 
-pub(crate) fn generic_image_to_oklab<const IMAGE: u8>(
-    src: &[u8],
+/// Converts image into planar Oklab, where lightness stored in `dst` in range [0, scale]
+/// and other components with alpha are stored in `color_planes`.
+///
+/// Returns lightness headroom of the image, lightness above reference white is quantized up to it
+pub(crate) fn generic_image_to_oklab<T: ImageSample, const IMAGE: u8>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -14,11 +23,18 @@ pub(crate) fn generic_image_to_oklab<const IMAGE: u8>(
     width: u32,
     height: u32,
     scale: f32,
-) {
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
     let image_configuration: ImageConfiguration = IMAGE.into();
     let channels = image_configuration.get_channels_count();
 
-    let full_scale = scale;
+    let headroom =
+        lightness_headroom::<T, IMAGE>(src, src_stride, width, height, transfer_function, |rgb| {
+            Oklab::from_linear_rgb(rgb).l
+        });
+    let full_scale = scale / headroom;
+    let normalize_scale = 1f32 / max_value_for_bit_depth(bit_depth);
 
     let color_planes_stride = if image_configuration.has_alpha() {
         width as usize * 3usize
@@ -39,49 +55,67 @@ pub(crate) fn generic_image_to_oklab<const IMAGE: u8>(
         )
     };
 
-    dst_slice_safe_align
-        .par_chunks_exact_mut(dst_stride as usize)
-        .zip(color_planes.par_chunks_exact_mut(color_planes_stride))
-        .zip(src.par_chunks_exact(src_stride as usize))
-        .for_each(|((dst, color), src)| unsafe {
-            let dst_ptr = dst.as_mut_ptr() as *mut u16;
-            for x in 0..width as usize {
-                let px = x * channels;
-                let cx = x * color_planes_channels;
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst_slice_safe_align
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact_mut(color_planes_stride))
+            .zip(src.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst_slice_safe_align
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact_mut(color_planes_stride))
+            .zip(src.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let dst_ptr = dst.as_mut_ptr() as *mut u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
 
-                let rgb = Rgb::<u8>::new(
-                    *src.get_unchecked(px + image_configuration.get_r_channel_offset()),
-                    *src.get_unchecked(px + image_configuration.get_g_channel_offset()),
-                    *src.get_unchecked(px + image_configuration.get_b_channel_offset()),
-                );
-                let oklab = Oklab::from_rgb(rgb, TransferFunction::Srgb);
-                let value = (oklab.l * full_scale).round().min(scale) as u16;
-                dst_ptr.add(x).write_unaligned(value);
-                // Just for storing in u16 adding 500 to subtract 500 after to keep values in positive range
-                *color.get_unchecked_mut(cx) = oklab.a;
-                *color.get_unchecked_mut(cx + 1) = oklab.b;
-                if image_configuration.has_alpha() {
-                    let a = *src.get_unchecked(px + image_configuration.get_a_channel_offset());
-                    *color.get_unchecked_mut(cx + 2) = a as f32;
-                }
+            let rgb = Rgb::<f32>::new(
+                src.get_unchecked(px + image_configuration.get_r_channel_offset())
+                    .to_normalized(normalize_scale),
+                src.get_unchecked(px + image_configuration.get_g_channel_offset())
+                    .to_normalized(normalize_scale),
+                src.get_unchecked(px + image_configuration.get_b_channel_offset())
+                    .to_normalized(normalize_scale),
+            );
+            let oklab = Oklab::from_linear_rgb(linearize_rgb(rgb, transfer_function));
+            let value = (oklab.l * full_scale).round().min(scale) as u16;
+            dst_ptr.add(x).write_unaligned(value);
+            // Just for storing in u16 adding 500 to subtract 500 after to keep values in positive range
+            *color.get_unchecked_mut(cx) = oklab.a;
+            *color.get_unchecked_mut(cx + 1) = oklab.b;
+            if image_configuration.has_alpha() {
+                let a = *src.get_unchecked(px + image_configuration.get_a_channel_offset());
+                *color.get_unchecked_mut(cx + 2) = a.to_normalized(normalize_scale);
             }
-        });
+        }
+    });
+    headroom
 }
 
-pub(crate) fn oklab_to_generic_image<const IMAGE: u8>(
+pub(crate) fn oklab_to_generic_image<T: ImageSample, const IMAGE: u8>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
     let image_configuration: ImageConfiguration = IMAGE.into();
     let channels = image_configuration.get_channels_count();
 
     let full_scale = 1. / scale;
+    let max_value = max_value_for_bit_depth(bit_depth);
 
     let color_planes_stride = if image_configuration.has_alpha() {
         width as usize * 3usize
@@ -98,40 +132,55 @@ pub(crate) fn oklab_to_generic_image<const IMAGE: u8>(
     let src_slice_safe_align = unsafe {
         slice::from_raw_parts(
             src.as_ptr() as *const u8,
-            dst_stride as usize * height as usize,
+            src_stride as usize * height as usize,
         )
     };
 
-    dst.par_chunks_exact_mut(dst_stride as usize)
-        .zip(color_planes.par_chunks_exact(color_planes_stride))
-        .zip(src_slice_safe_align.par_chunks_exact(src_stride as usize))
-        .for_each(|((dst, color), src)| unsafe {
-            let src_ptr = src.as_ptr() as *const u16;
-            for x in 0..width as usize {
-                let px = x * channels;
-                let cx = x * color_planes_channels;
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .par_chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.par_chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.par_chunks_exact(src_stride as usize));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .chunks_exact_mut(dst_stride as usize)
+            .zip(color_planes.chunks_exact(color_planes_stride))
+            .zip(src_slice_safe_align.chunks_exact(src_stride as usize));
+    }
+    iter.for_each(|((dst, color), src)| unsafe {
+        let src_ptr = src.as_ptr() as *const u16;
+        for x in 0..width as usize {
+            let px = x * channels;
+            let cx = x * color_planes_channels;
 
-                let l = src_ptr.add(x).read_unaligned() as f32 * full_scale;
+            let l = src_ptr.add(x).read_unaligned() as f32 * full_scale;
 
-                let a = *color.get_unchecked(cx);
-                let b = *color.get_unchecked(cx + 1);
+            let a = *color.get_unchecked(cx);
+            let b = *color.get_unchecked(cx + 1);
 
-                let rgb = Oklab::new(l, a, b);
-                let rgb = rgb.to_rgb(TransferFunction::Srgb);
-                *dst.get_unchecked_mut(px + image_configuration.get_r_channel_offset()) = rgb.r;
-                *dst.get_unchecked_mut(px + image_configuration.get_g_channel_offset()) = rgb.g;
-                *dst.get_unchecked_mut(px + image_configuration.get_b_channel_offset()) = rgb.b;
-                if image_configuration.has_alpha() {
-                    let a = *color.get_unchecked(cx + 2);
-                    *dst.get_unchecked_mut(px + image_configuration.get_a_channel_offset()) =
-                        a as u8;
-                }
+            let rgb = Oklab::new(l, a, b);
+            let rgb = gamma_rgb(rgb.to_linear_rgb(), transfer_function);
+            *dst.get_unchecked_mut(px + image_configuration.get_r_channel_offset()) =
+                T::from_normalized(rgb.r, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_g_channel_offset()) =
+                T::from_normalized(rgb.g, max_value);
+            *dst.get_unchecked_mut(px + image_configuration.get_b_channel_offset()) =
+                T::from_normalized(rgb.b, max_value);
+            if image_configuration.has_alpha() {
+                let a = *color.get_unchecked(cx + 2);
+                *dst.get_unchecked_mut(px + image_configuration.get_a_channel_offset()) =
+                    T::from_normalized(a, max_value);
             }
-        });
+        }
+    });
 }
 
-pub(crate) fn rgb_to_oklab(
-    src: &[u8],
+pub(crate) fn rgb_to_oklab<T: ImageSample>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -139,8 +188,10 @@ pub(crate) fn rgb_to_oklab(
     width: u32,
     height: u32,
     scale: f32,
-) {
-    generic_image_to_oklab::<{ ImageConfiguration::Rgb as u8 }>(
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_oklab::<T, { ImageConfiguration::Rgb as u8 }>(
         src,
         src_stride,
         dst,
@@ -149,11 +200,13 @@ pub(crate) fn rgb_to_oklab(
         width,
         height,
         scale,
-    );
+        bit_depth,
+        transfer_function,
+    )
 }
 
-pub(crate) fn bgra_to_oklab(
-    src: &[u8],
+pub(crate) fn bgr_to_oklab<T: ImageSample>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -161,8 +214,10 @@ pub(crate) fn bgra_to_oklab(
     width: u32,
     height: u32,
     scale: f32,
-) {
-    generic_image_to_oklab::<{ ImageConfiguration::Bgra as u8 }>(
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_oklab::<T, { ImageConfiguration::Bgr as u8 }>(
         src,
         src_stride,
         dst,
@@ -171,11 +226,13 @@ pub(crate) fn bgra_to_oklab(
         width,
         height,
         scale,
-    );
+        bit_depth,
+        transfer_function,
+    )
 }
 
-pub(crate) fn rgba_to_oklab(
-    src: &[u8],
+pub(crate) fn bgra_to_oklab<T: ImageSample>(
+    src: &[T],
     src_stride: u32,
     dst: &mut [u16],
     dst_stride: u32,
@@ -183,8 +240,10 @@ pub(crate) fn rgba_to_oklab(
     width: u32,
     height: u32,
     scale: f32,
-) {
-    generic_image_to_oklab::<{ ImageConfiguration::Rgba as u8 }>(
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_oklab::<T, { ImageConfiguration::Bgra as u8 }>(
         src,
         src_stride,
         dst,
@@ -193,20 +252,76 @@ pub(crate) fn rgba_to_oklab(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
+    )
+}
+
+pub(crate) fn rgba_to_oklab<T: ImageSample>(
+    src: &[T],
+    src_stride: u32,
+    dst: &mut [u16],
+    dst_stride: u32,
+    color_planes: &mut [f32],
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) -> f32 {
+    generic_image_to_oklab::<T, { ImageConfiguration::Rgba as u8 }>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        color_planes,
+        width,
+        height,
+        scale,
+        bit_depth,
+        transfer_function,
+    )
+}
+
+pub(crate) fn oklab_to_rgb<T: ImageSample>(
+    src: &[u16],
+    src_stride: u32,
+    color_planes: &[f32],
+    dst: &mut [T],
+    dst_stride: u32,
+    width: u32,
+    height: u32,
+    scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
+) {
+    oklab_to_generic_image::<T, { ImageConfiguration::Rgb as u8 }>(
+        src,
+        src_stride,
+        color_planes,
+        dst,
+        dst_stride,
+        width,
+        height,
+        scale,
+        bit_depth,
+        transfer_function,
     );
 }
 
-pub(crate) fn oklab_to_rgb(
+pub(crate) fn oklab_to_bgr<T: ImageSample>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
-    oklab_to_generic_image::<{ ImageConfiguration::Rgb as u8 }>(
+    oklab_to_generic_image::<T, { ImageConfiguration::Bgr as u8 }>(
         src,
         src_stride,
         color_planes,
@@ -215,20 +330,24 @@ pub(crate) fn oklab_to_rgb(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
     );
 }
 
-pub(crate) fn oklab_to_bgra(
+pub(crate) fn oklab_to_bgra<T: ImageSample>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
-    oklab_to_generic_image::<{ ImageConfiguration::Bgra as u8 }>(
+    oklab_to_generic_image::<T, { ImageConfiguration::Bgra as u8 }>(
         src,
         src_stride,
         color_planes,
@@ -237,20 +356,24 @@ pub(crate) fn oklab_to_bgra(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
     );
 }
 
-pub(crate) fn oklab_to_rgba(
+pub(crate) fn oklab_to_rgba<T: ImageSample>(
     src: &[u16],
     src_stride: u32,
     color_planes: &[f32],
-    dst: &mut [u8],
+    dst: &mut [T],
     dst_stride: u32,
     width: u32,
     height: u32,
     scale: f32,
+    bit_depth: u32,
+    transfer_function: TransferFunction,
 ) {
-    oklab_to_generic_image::<{ ImageConfiguration::Rgba as u8 }>(
+    oklab_to_generic_image::<T, { ImageConfiguration::Rgba as u8 }>(
         src,
         src_stride,
         color_planes,
@@ -259,5 +382,7 @@ pub(crate) fn oklab_to_rgba(
         width,
         height,
         scale,
+        bit_depth,
+        transfer_function,
     );
 }
@@ -0,0 +1,240 @@
+use crate::image_configuration::ImageConfiguration;
+use colorutils_rs::{Rgb, TransferFunction};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+#[cfg(feature = "rayon")]
+use rayon::prelude::{ParallelSlice, ParallelSliceMut};
+
+/// Storage type of a single image component
+pub(crate) trait ImageSample: Copy + Default + Send + Sync + 'static {
+    /// Values may exceed [0, 1], so lightness may exceed reference white
+    const HDR: bool = false;
+
+    /// Converts stored value into [0, 1] range, `scale` is `1 / max_value`
+    fn to_normalized(self, scale: f32) -> f32;
+
+    /// Converts normalized value back into storage range [0, max_value]
+    fn from_normalized(value: f32, max_value: f32) -> Self;
+}
+
+impl ImageSample for u8 {
+    #[inline(always)]
+    fn to_normalized(self, scale: f32) -> f32 {
+        self as f32 * scale
+    }
+
+    #[inline(always)]
+    fn from_normalized(value: f32, max_value: f32) -> Self {
+        (value * max_value).max(0f32).round().min(max_value) as u8
+    }
+}
+
+impl ImageSample for u16 {
+    #[inline(always)]
+    fn to_normalized(self, scale: f32) -> f32 {
+        self as f32 * scale
+    }
+
+    #[inline(always)]
+    fn from_normalized(value: f32, max_value: f32) -> Self {
+        (value * max_value).max(0f32).round().min(max_value) as u16
+    }
+}
+
+/// Floating point samples are already normalized, scale and max value are ignored,
+/// values outside [0, 1] are kept as they are for HDR content
+impl ImageSample for f32 {
+    const HDR: bool = true;
+
+    #[inline(always)]
+    fn to_normalized(self, _: f32) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn from_normalized(value: f32, _: f32) -> Self {
+        value
+    }
+}
+
+#[inline]
+pub(crate) fn max_value_for_bit_depth(bit_depth: u32) -> f32 {
+    ((1u32 << bit_depth) - 1) as f32
+}
+
+/// Linearizes RGB, `TransferFunction::Linear` is passed through as it is,
+/// because colorutils clamps it into [0, 1] and HDR values would be lost
+#[inline(always)]
+pub(crate) fn linearize_rgb(rgb: Rgb<f32>, transfer_function: TransferFunction) -> Rgb<f32> {
+    match transfer_function {
+        TransferFunction::Linear => rgb,
+        _ => rgb.linearize(transfer_function),
+    }
+}
+
+/// Applies gamma to linear RGB, `TransferFunction::Linear` is passed through as it is
+#[inline(always)]
+pub(crate) fn gamma_rgb(rgb: Rgb<f32>, transfer_function: TransferFunction) -> Rgb<f32> {
+    match transfer_function {
+        TransferFunction::Linear => rgb,
+        _ => rgb.gamma(transfer_function),
+    }
+}
+
+/// Peak lightness of `src` as a share of reference white, at least 1, where `lightness` maps
+/// linear RGB onto lightness with reference white at 1. Only floating point samples exceed
+/// reference white, so other images aren't scanned
+pub(crate) fn lightness_headroom<T: ImageSample, const IMAGE: u8>(
+    src: &[T],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    transfer_function: TransferFunction,
+    lightness: impl Fn(Rgb<f32>) -> f32 + Send + Sync,
+) -> f32 {
+    if !T::HDR {
+        return 1f32;
+    }
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let channels = image_configuration.get_channels_count();
+
+    let row_peak = |row: &[T]| {
+        row[..width as usize * channels]
+            .chunks_exact(channels)
+            .map(|px| {
+                let rgb = Rgb::<f32>::new(
+                    px[image_configuration.get_r_channel_offset()].to_normalized(1f32),
+                    px[image_configuration.get_g_channel_offset()].to_normalized(1f32),
+                    px[image_configuration.get_b_channel_offset()].to_normalized(1f32),
+                );
+                lightness(linearize_rgb(rgb, transfer_function))
+            })
+            .filter(|l| l.is_finite())
+            .fold(1f32, f32::max)
+    };
+
+    #[cfg(feature = "rayon")]
+    {
+        src.par_chunks(src_stride as usize)
+            .take(height as usize)
+            .map(row_peak)
+            .reduce(|| 1f32, f32::max)
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        src.chunks(src_stride as usize)
+            .take(height as usize)
+            .map(row_peak)
+            .fold(1f32, f32::max)
+    }
+}
+
+/// Restores straight alpha color of premultiplied `src` into `dst`, rounded as a straight alpha
+/// image would be stored, fully transparent pixels are left as they are
+pub(crate) fn unpremultiply_image(
+    src: &[u8],
+    src_stride: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    width: usize,
+    height: usize,
+    image_configuration: ImageConfiguration,
+) {
+    let channels = image_configuration.get_channels_count();
+    let a_offset = image_configuration.get_a_channel_offset();
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = dst
+            .par_chunks_mut(dst_stride)
+            .take(height)
+            .zip(src.par_chunks(src_stride).take(height));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = dst
+            .chunks_mut(dst_stride)
+            .take(height)
+            .zip(src.chunks(src_stride).take(height));
+    }
+    iter.for_each(|(dst, src)| {
+        for (dst, src) in dst[..width * channels]
+            .chunks_exact_mut(channels)
+            .zip(src[..width * channels].chunks_exact(channels))
+        {
+            let a = src[a_offset] as u32;
+            for (dst, &src) in dst.iter_mut().zip(src.iter()) {
+                *dst = (src as u32 * 255 + a / 2)
+                    .checked_div(a)
+                    .map_or(src, |v| v.min(255) as u8);
+            }
+            dst[a_offset] = a as u8;
+        }
+    });
+}
+
+/// Multiplies color of straight alpha `image` by its alpha in place
+pub(crate) fn premultiply_image(
+    image: &mut [u8],
+    stride: usize,
+    width: usize,
+    height: usize,
+    image_configuration: ImageConfiguration,
+) {
+    let channels = image_configuration.get_channels_count();
+    let a_offset = image_configuration.get_a_channel_offset();
+
+    let iter;
+    #[cfg(feature = "rayon")]
+    {
+        iter = image.par_chunks_mut(stride).take(height);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        iter = image.chunks_mut(stride).take(height);
+    }
+    iter.for_each(|row| {
+        for pixel in row[..width * channels].chunks_exact_mut(channels) {
+            let a = pixel[a_offset] as u32;
+            for (channel, value) in pixel.iter_mut().enumerate() {
+                if channel != a_offset {
+                    *value = ((*value as u32 * a + 127) / 255) as u8;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpremultiply_keeps_rows_after_image() {
+        let (width, height, stride) = (3usize, 2usize, 16usize);
+        // Buffers hold two more rows than the image
+        let src: Vec<u8> = (0..stride * (height + 2))
+            .map(|i| if i % 4 == 3 { 128 } else { 50 })
+            .collect();
+        let mut dst = vec![7u8; src.len()];
+        unpremultiply_image(
+            &src,
+            stride,
+            &mut dst,
+            stride,
+            width,
+            height,
+            ImageConfiguration::Rgba,
+        );
+        for (y, row) in dst.chunks(stride).enumerate() {
+            let (image, padding) = row.split_at(width * 4);
+            assert!(padding.iter().all(|&v| v == 7));
+            if y < height {
+                assert_eq!(image, [100, 100, 100, 128].repeat(width));
+            } else {
+                assert!(image.iter().all(|&v| v == 7));
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod blerp;
+mod histogram;
+
+pub(crate) use blerp::sse_blerp_block;
+pub(crate) use histogram::{sse_count_row_u16, sse_count_row_u8};
@@ -0,0 +1,291 @@
+use crate::context::EqualizerContext;
+use crate::equalizer::{EqualizationMethod, Equalizer};
+use crate::error::{check_smoothing, HistogramError};
+use crate::hist_support::{ClaheGridSize, ClaheMode, ClaheOptions};
+use crate::yuv_planar_impl::{equalize_yuv_biplanar_impl, equalize_yuv_planar_impl};
+use num_traits::AsPrimitive;
+use yuv::{YuvBiPlanarImageMut, YuvChromaSubsampling, YuvPlanarImageMut};
+
+/// Tile LUTs of previous frames blended by exponential moving average
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LutHistory {
+    luts: Vec<f32>,
+    smoothing: f32,
+    scene_cut_threshold: f32,
+    scene_cut: bool,
+}
+
+impl LutHistory {
+    fn new(smoothing: f32, scene_cut_threshold: f32) -> LutHistory {
+        LutHistory {
+            luts: Vec::new(),
+            smoothing,
+            scene_cut_threshold,
+            scene_cut: false,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.luts.clear();
+        self.scene_cut = false;
+    }
+
+    /// Blends `luts` of the current frame into the history and replaces them with the result.
+    ///
+    /// History starts over from `luts` on the first frame, when the count of LUTs changes, or
+    /// when mean difference from the history exceeds `scene_cut_threshold` of `max_bins`.
+    pub(crate) fn smooth<T: Copy + AsPrimitive<f32>>(&mut self, luts: &mut [T], max_bins: usize)
+    where
+        f32: AsPrimitive<T>,
+    {
+        if self.luts.len() != luts.len() {
+            self.luts.clear();
+            self.luts
+                .extend(luts.iter().map(|&v| AsPrimitive::<f32>::as_(v)));
+            self.scene_cut = false;
+            return;
+        }
+
+        let difference = self
+            .luts
+            .iter()
+            .zip(luts.iter())
+            .map(|(&previous, &current)| (previous - current.as_()).abs() as f64)
+            .sum::<f64>()
+            / (luts.len() as f64 * max_bins as f64);
+        self.scene_cut = difference > self.scene_cut_threshold as f64;
+        if self.scene_cut {
+            for (dst, &src) in self.luts.iter_mut().zip(luts.iter()) {
+                *dst = src.as_();
+            }
+            return;
+        }
+
+        let previous_weight = self.smoothing;
+        let current_weight = 1f32 - self.smoothing;
+        for (state, lut) in self.luts.iter_mut().zip(luts.iter_mut()) {
+            let current: f32 = (*lut).as_();
+            *state = *state * previous_weight + current * current_weight;
+            *lut = state.round().min(max_bins as f32).as_();
+        }
+    }
+}
+
+/// Equalizer for consecutive frames of a video.
+///
+/// Tile LUTs of every frame are blended with the previous ones by exponential moving average,
+/// so contrast doesn't flicker when frames are equalized one by one. When LUTs change more
+/// than the scene cut threshold the history is dropped and the frame is equalized on its own.
+///
+/// Only [ClaheMode::Native] of AHE and CLAHE builds tile LUTs, global histogram equalization
+/// is performed as AHE with a single tile. Working buffers are kept between frames as in
+/// [EqualizerContext], so frames of the same size are equalized without allocations.
+///
+/// # Example
+///
+/// ```
+/// use histogram_equalization::{EqualizationColorSpace, Equalizer, VideoEqualizer};
+///
+/// let (width, height) = (64u32, 64u32);
+/// let mut video = VideoEqualizer::new(
+///     Equalizer::new().with_color_space(EqualizationColorSpace::Yuv),
+/// )
+/// .with_smoothing(0.8f32);
+/// let frame = vec![127u8; width as usize * height as usize * 3];
+/// let mut dst = vec![0u8; frame.len()];
+/// for _ in 0..3 {
+///     video
+///         .apply(&frame, width * 3, &mut dst, width * 3, width, height)
+///         .unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct VideoEqualizer {
+    equalizer: Equalizer,
+    history: LutHistory,
+    context: EqualizerContext,
+}
+
+/// Working buffers don't take part in comparison
+impl PartialEq for VideoEqualizer {
+    fn eq(&self, other: &Self) -> bool {
+        self.equalizer == other.equalizer && self.history == other.history
+    }
+}
+
+impl Default for VideoEqualizer {
+    fn default() -> Self {
+        VideoEqualizer::new(Equalizer::default())
+    }
+}
+
+impl VideoEqualizer {
+    /// Creates video equalizer with configuration of `equalizer`, smoothing 0.8 and
+    /// scene cut threshold 0.15
+    pub fn new(equalizer: Equalizer) -> Self {
+        VideoEqualizer {
+            equalizer,
+            history: LutHistory::new(0.8f32, 0.15f32),
+            context: EqualizerContext::default(),
+        }
+    }
+
+    /// Weight of previous frames in [0, 1), 0 equalizes every frame on its own
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.history.smoothing = smoothing;
+        self
+    }
+
+    /// Mean change of tile LUTs, as a fraction of the lightness range, which is treated as a
+    /// scene cut. 1 or more disables scene cut detection.
+    pub fn with_scene_cut_threshold(mut self, scene_cut_threshold: f32) -> Self {
+        self.history.scene_cut_threshold = scene_cut_threshold;
+        self
+    }
+
+    pub fn equalizer(&self) -> Equalizer {
+        self.equalizer
+    }
+
+    pub fn smoothing(&self) -> f32 {
+        self.history.smoothing
+    }
+
+    pub fn scene_cut_threshold(&self) -> f32 {
+        self.history.scene_cut_threshold
+    }
+
+    /// Returns true if the last frame was detected as a scene cut
+    pub fn is_scene_cut(&self) -> bool {
+        self.history.scene_cut
+    }
+
+    /// Drops history of previous frames, next frame is equalized on its own
+    pub fn reset(&mut self) {
+        self.history.reset();
+    }
+
+    /// Performs configured equalization of the next frame from `src` into `dst`,
+    /// see [Equalizer::apply]
+    ///
+    /// # Arguments
+    ///
+    /// * `src_stride` - Bytes per row of `src`
+    /// * `dst_stride` - Bytes per row of `dst`
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes or the input data are not valid based
+    /// on the specified width, height, and strides, if parameters are out of supported range,
+    /// or if AHE and CLAHE are configured with other mode than [ClaheMode::Native]
+    pub fn apply(
+        &mut self,
+        src: &[u8],
+        src_stride: u32,
+        dst: &mut [u8],
+        dst_stride: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), HistogramError> {
+        let equalizer = self.frame_equalizer()?;
+        equalizer.apply_with_mask(
+            src,
+            src_stride,
+            dst,
+            dst_stride,
+            width,
+            height,
+            None,
+            Some(&mut self.history),
+            &mut self.context,
+        )
+    }
+
+    /// Performs configured equalization of the luma plane of the next planar YUV frame
+    /// in place, see [Equalizer::apply_yuv_planar]
+    ///
+    /// # Arguments
+    ///
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma planes
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, if parameters are out of supported range,
+    /// or if AHE and CLAHE are configured with other mode than [ClaheMode::Native]
+    pub fn apply_yuv_planar(
+        &mut self,
+        image: &mut YuvPlanarImageMut<u8>,
+        subsampling: YuvChromaSubsampling,
+    ) -> Result<(), HistogramError> {
+        let equalizer = self.frame_equalizer()?;
+        equalize_yuv_planar_impl(
+            image,
+            subsampling,
+            equalizer.yuv_range(),
+            8,
+            equalizer.method(),
+            equalizer.threshold(),
+            equalizer.grid_size(),
+            ClaheOptions {
+                clip_redistribution: equalizer.clip_redistribution(),
+                mode: equalizer.clahe_mode(),
+            },
+            Some(&mut self.history),
+            &mut self.context.yuv.tiles,
+        )
+    }
+
+    /// Performs configured equalization of the luma plane of the next bi-planar YUV frame
+    /// in place, see [Equalizer::apply_yuv_biplanar]
+    ///
+    /// # Arguments
+    ///
+    /// * `subsampling` - Chroma subsampling of the frame, used only to check chroma plane
+    ///
+    /// # Errors
+    ///
+    /// Returns [HistogramError] if the lengths of the planes are not valid based on the frame
+    /// width, height, strides and subsampling, if parameters are out of supported range,
+    /// or if AHE and CLAHE are configured with other mode than [ClaheMode::Native]
+    pub fn apply_yuv_biplanar(
+        &mut self,
+        image: &mut YuvBiPlanarImageMut<u8>,
+        subsampling: YuvChromaSubsampling,
+    ) -> Result<(), HistogramError> {
+        let equalizer = self.frame_equalizer()?;
+        equalize_yuv_biplanar_impl(
+            image,
+            subsampling,
+            equalizer.yuv_range(),
+            8,
+            equalizer.method(),
+            equalizer.threshold(),
+            equalizer.grid_size(),
+            ClaheOptions {
+                clip_redistribution: equalizer.clip_redistribution(),
+                mode: equalizer.clahe_mode(),
+            },
+            Some(&mut self.history),
+            &mut self.context.yuv.tiles,
+        )
+    }
+
+    /// Equalizer used for frames, global equalization is replaced by AHE with a single tile
+    fn frame_equalizer(&self) -> Result<Equalizer, HistogramError> {
+        check_smoothing(self.history.smoothing)?;
+        if self.equalizer.method() == EqualizationMethod::HistogramEqualization {
+            return Ok(self
+                .equalizer
+                .with_method(EqualizationMethod::Ahe)
+                .with_grid_size(ClaheGridSize::new(1, 1))
+                .with_clahe_mode(ClaheMode::Native));
+        }
+        if self.equalizer.clahe_mode() != ClaheMode::Native {
+            return Err(HistogramError::UnsupportedVideoMode(
+                self.equalizer.clahe_mode(),
+            ));
+        }
+        Ok(self.equalizer)
+    }
+}
@@ -0,0 +1,193 @@
+use crate::clahe_gray_impl::clahe_gray_in_place;
+use crate::clahe_tiles::TileScratch;
+use crate::dispatch::HistogramSample;
+use crate::error::{check_grid_size, check_image_size, HistogramError};
+use crate::hist_equal_gray_impl::equalize_histogram_gray_in_place;
+use crate::hist_support::{AheImplementation, ClaheOptions};
+use crate::video_equalizer::LutHistory;
+use crate::yuv_support::{luma_from_levels, luma_levels, luma_to_levels};
+use crate::{ClaheGridSize, EqualizationMethod};
+use num_traits::{AsPrimitive, FromPrimitive};
+use std::fmt::Debug;
+use yuv::{YuvBiPlanarImageMut, YuvChromaSubsampling, YuvPlanarImageMut, YuvRange};
+
+/// Equalizes luma plane of a planar YUV frame in place, chroma planes are checked
+/// against `subsampling` but never touched
+pub(crate) fn equalize_yuv_planar_impl<
+    T: Copy
+        + Debug
+        + Ord
+        + FromPrimitive
+        + Default
+        + HistogramSample
+        + AsPrimitive<f32>
+        + AsPrimitive<u32>
+        + Send
+        + Sync
+        + 'static,
+>(
+    image: &mut YuvPlanarImageMut<T>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    bit_depth: u32,
+    method: EqualizationMethod,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    clahe_options: ClaheOptions,
+    history: Option<&mut LutHistory>,
+    scratch: &mut TileScratch<T>,
+) -> Result<(), HistogramError>
+where
+    f32: AsPrimitive<T>,
+    u32: AsPrimitive<T>,
+    u64: AsPrimitive<T>,
+{
+    check_image_size(image.width, image.height)?;
+    image.check_constraints(subsampling)?;
+    equalize_luma_in_place(
+        image.y_plane.borrow_mut(),
+        image.y_stride,
+        image.width,
+        image.height,
+        range,
+        bit_depth,
+        method,
+        threshold,
+        grid_size,
+        clahe_options,
+        history,
+        scratch,
+    )
+}
+
+/// Equalizes luma plane of a bi-planar YUV frame in place, interleaved chroma plane is checked
+/// against `subsampling` but never touched
+pub(crate) fn equalize_yuv_biplanar_impl<
+    T: Copy
+        + Debug
+        + Ord
+        + FromPrimitive
+        + Default
+        + HistogramSample
+        + AsPrimitive<f32>
+        + AsPrimitive<u32>
+        + Send
+        + Sync
+        + 'static,
+>(
+    image: &mut YuvBiPlanarImageMut<T>,
+    subsampling: YuvChromaSubsampling,
+    range: YuvRange,
+    bit_depth: u32,
+    method: EqualizationMethod,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    clahe_options: ClaheOptions,
+    history: Option<&mut LutHistory>,
+    scratch: &mut TileScratch<T>,
+) -> Result<(), HistogramError>
+where
+    f32: AsPrimitive<T>,
+    u32: AsPrimitive<T>,
+    u64: AsPrimitive<T>,
+{
+    check_image_size(image.width, image.height)?;
+    image.check_constraints(subsampling)?;
+    equalize_luma_in_place(
+        image.y_plane.borrow_mut(),
+        image.y_stride,
+        image.width,
+        image.height,
+        range,
+        bit_depth,
+        method,
+        threshold,
+        grid_size,
+        clahe_options,
+        history,
+        scratch,
+    )
+}
+
+/// Luma is equalized as a gray image with a bin for every level of `range` at `bit_depth`
+fn equalize_luma_in_place<
+    T: Copy
+        + Ord
+        + FromPrimitive
+        + Default
+        + HistogramSample
+        + AsPrimitive<f32>
+        + AsPrimitive<u32>
+        + Send
+        + Sync
+        + 'static,
+>(
+    y_plane: &mut [T],
+    y_stride: u32,
+    width: u32,
+    height: u32,
+    range: YuvRange,
+    bit_depth: u32,
+    method: EqualizationMethod,
+    threshold: f32,
+    grid_size: ClaheGridSize,
+    clahe_options: ClaheOptions,
+    history: Option<&mut LutHistory>,
+    scratch: &mut TileScratch<T>,
+) -> Result<(), HistogramError>
+where
+    f32: AsPrimitive<T>,
+    u32: AsPrimitive<T>,
+    u64: AsPrimitive<T>,
+{
+    if method != EqualizationMethod::HistogramEqualization {
+        check_grid_size(grid_size)?;
+    }
+    let (stride, width, height) = (y_stride as usize, width as usize, height as usize);
+    let (_, bins_count) = luma_levels(range, bit_depth);
+    luma_to_levels(y_plane, stride, width, height, range, bit_depth);
+    match method {
+        EqualizationMethod::HistogramEqualization => {
+            equalize_histogram_gray_in_place::<T>(
+                y_plane,
+                stride,
+                width,
+                height,
+                bins_count,
+                &mut scratch.histograms,
+            );
+        }
+        EqualizationMethod::Ahe => {
+            clahe_gray_in_place::<T, { AheImplementation::Ahe as u8 }>(
+                y_plane,
+                stride,
+                width,
+                height,
+                0f32,
+                grid_size,
+                clahe_options,
+                bins_count,
+                None,
+                history,
+                scratch,
+            );
+        }
+        EqualizationMethod::Clahe => {
+            clahe_gray_in_place::<T, { AheImplementation::Clahe as u8 }>(
+                y_plane,
+                stride,
+                width,
+                height,
+                threshold,
+                grid_size,
+                clahe_options,
+                bins_count,
+                None,
+                history,
+                scratch,
+            );
+        }
+    }
+    luma_from_levels(y_plane, stride, width, height, range, bit_depth);
+    Ok(())
+}
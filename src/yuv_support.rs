@@ -0,0 +1,458 @@
+use crate::context::working_buffer;
+use crate::image_configuration::ImageConfiguration;
+use num_traits::AsPrimitive;
+use yuv::{
+    bgr_to_ycgco444, bgr_to_yuv444, bgra_to_ycgco444, bgra_to_yuv444, i410_to_rgb10,
+    i410_to_rgba10, i412_to_rgb12, i412_to_rgba12, i414_to_rgb14, i414_to_rgba14, i416_to_rgb16,
+    i416_to_rgba16, icgc410_to_rgb10, icgc410_to_rgba10, icgc412_to_rgb12, icgc412_to_rgba12,
+    rgb10_to_i410, rgb10_to_icgc410, rgb12_to_i412, rgb14_to_i414, rgb16_to_i416, rgb_to_ycgco444,
+    rgb_to_yuv444, rgba10_to_i410, rgba10_to_icgc410, rgba12_to_i412, rgba12_to_icgc412,
+    rgba14_to_i414, rgba16_to_i416, rgba_to_ycgco444, rgba_to_yuv444, ycgco444_alpha_to_bgra,
+    ycgco444_alpha_to_rgba, yuv444_alpha_to_bgra, yuv444_alpha_to_rgba, yuv444_to_bgr,
+    yuv444_to_rgb, BufferStoreMut, YuvConversionMode, YuvError, YuvPlanarImage, YuvPlanarImageMut,
+    YuvPlanarImageWithAlpha, YuvRange, YuvStandardMatrix,
+};
+
+/// Matrix of conversion between RGB and YUV
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum YuvMatrix {
+    Bt601,
+    Bt709,
+    Bt2020,
+    /// YCgCo, default of the crate
+    #[default]
+    YCgCo,
+}
+
+impl YuvMatrix {
+    /// Standard matrix of `yuv`, YCgCo has its own converters
+    fn standard_matrix(self) -> Option<YuvStandardMatrix> {
+        match self {
+            YuvMatrix::Bt601 => Some(YuvStandardMatrix::Bt601),
+            YuvMatrix::Bt709 => Some(YuvStandardMatrix::Bt709),
+            YuvMatrix::Bt2020 => Some(YuvStandardMatrix::Bt2020),
+            YuvMatrix::YCgCo => None,
+        }
+    }
+}
+
+/// Matrix, range and bit depth shared by all YUV cores
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct YuvOptions {
+    pub(crate) matrix: YuvMatrix,
+    pub(crate) range: YuvRange,
+    pub(crate) bit_depth: u32,
+}
+
+impl Default for YuvOptions {
+    fn default() -> Self {
+        YuvOptions {
+            matrix: YuvMatrix::YCgCo,
+            range: YuvRange::Full,
+            bit_depth: 8,
+        }
+    }
+}
+
+/// Converts interleaved `IMAGE` into YUV 4:4:4 with matrix and range of `options`,
+/// alpha-first layouts are not supported
+pub(crate) fn image_to_yuv444<const IMAGE: u8>(
+    planar_image: &mut YuvPlanarImageMut<u8>,
+    src: &[u8],
+    src_stride: u32,
+    options: YuvOptions,
+) -> Result<(), YuvError> {
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let range = options.range;
+    let mode = YuvConversionMode::Balanced;
+    match (image_configuration, options.matrix.standard_matrix()) {
+        (ImageConfiguration::Rgb, None) => rgb_to_ycgco444(planar_image, src, src_stride, range),
+        (ImageConfiguration::Rgba, None) => rgba_to_ycgco444(planar_image, src, src_stride, range),
+        (ImageConfiguration::Bgra, None) => bgra_to_ycgco444(planar_image, src, src_stride, range),
+        (ImageConfiguration::Bgr, None) => bgr_to_ycgco444(planar_image, src, src_stride, range),
+        (ImageConfiguration::Rgb, Some(matrix)) => {
+            rgb_to_yuv444(planar_image, src, src_stride, range, matrix, mode)
+        }
+        (ImageConfiguration::Rgba, Some(matrix)) => {
+            rgba_to_yuv444(planar_image, src, src_stride, range, matrix, mode)
+        }
+        (ImageConfiguration::Bgra, Some(matrix)) => {
+            bgra_to_yuv444(planar_image, src, src_stride, range, matrix, mode)
+        }
+        (ImageConfiguration::Bgr, Some(matrix)) => {
+            bgr_to_yuv444(planar_image, src, src_stride, range, matrix, mode)
+        }
+        (ImageConfiguration::Argb | ImageConfiguration::Abgr, _) => {
+            unreachable!("Alpha-first layouts are not supported by YUV")
+        }
+    }
+}
+
+/// Converts YUV 4:4:4 back into interleaved `IMAGE` with matrix and range of `options`,
+/// alpha plane is used only by layouts with alpha
+pub(crate) fn yuv444_to_image<const IMAGE: u8>(
+    planar_image: &YuvPlanarImageWithAlpha<u8>,
+    dst: &mut [u8],
+    dst_stride: u32,
+    options: YuvOptions,
+) -> Result<(), YuvError> {
+    let image_configuration: ImageConfiguration = IMAGE.into();
+    let range = options.range;
+    let without_alpha = YuvPlanarImage {
+        y_plane: planar_image.y_plane,
+        y_stride: planar_image.y_stride,
+        u_plane: planar_image.u_plane,
+        u_stride: planar_image.u_stride,
+        v_plane: planar_image.v_plane,
+        v_stride: planar_image.v_stride,
+        width: planar_image.width,
+        height: planar_image.height,
+    };
+    match (image_configuration, options.matrix.standard_matrix()) {
+        (ImageConfiguration::Rgb, None) => {
+            ycgco444_to_rgb_segments::<false>(&without_alpha, dst, dst_stride, range)
+        }
+        (ImageConfiguration::Bgr, None) => {
+            ycgco444_to_rgb_segments::<true>(&without_alpha, dst, dst_stride, range)
+        }
+        (ImageConfiguration::Rgba, None) => {
+            ycgco444_alpha_to_rgba(planar_image, dst, dst_stride, range)
+        }
+        (ImageConfiguration::Bgra, None) => {
+            ycgco444_alpha_to_bgra(planar_image, dst, dst_stride, range)
+        }
+        (ImageConfiguration::Rgb, Some(matrix)) => {
+            yuv444_to_rgb(&without_alpha, dst, dst_stride, range, matrix)
+        }
+        (ImageConfiguration::Bgr, Some(matrix)) => {
+            yuv444_to_bgr(&without_alpha, dst, dst_stride, range, matrix)
+        }
+        (ImageConfiguration::Rgba, Some(matrix)) => {
+            yuv444_alpha_to_rgba(planar_image, dst, dst_stride, range, matrix, false)
+        }
+        (ImageConfiguration::Bgra, Some(matrix)) => {
+            yuv444_alpha_to_bgra(planar_image, dst, dst_stride, range, matrix, false)
+        }
+        (ImageConfiguration::Argb | ImageConfiguration::Abgr, _) => {
+            unreachable!("Alpha-first layouts are not supported by YUV")
+        }
+    }
+}
+
+/// Pixels of a row converted at once on the stack
+const SEGMENT_PIXELS: usize = 256;
+
+/// Converts YCgCo 4:4:4 into RGB, or BGR when `BGR` is set, through the converter with alpha
+/// segment by segment on the stack, as YCgCo converters without alpha allocate on every call
+fn ycgco444_to_rgb_segments<const BGR: bool>(
+    image: &YuvPlanarImage<u8>,
+    dst: &mut [u8],
+    dst_stride: u32,
+    range: YuvRange,
+) -> Result<(), YuvError> {
+    let (width, height) = (image.width as usize, image.height as usize);
+    let (y_stride, u_stride, v_stride) = (
+        image.y_stride as usize,
+        image.u_stride as usize,
+        image.v_stride as usize,
+    );
+    let opaque = [u8::MAX; SEGMENT_PIXELS];
+    let mut segment = [0u8; SEGMENT_PIXELS * 4];
+    for (y, dst_row) in dst.chunks_mut(dst_stride as usize).take(height).enumerate() {
+        for x in (0..width).step_by(SEGMENT_PIXELS) {
+            let pixels = SEGMENT_PIXELS.min(width - x);
+            let segment_image = YuvPlanarImageWithAlpha {
+                y_plane: &image.y_plane[y * y_stride + x..y * y_stride + x + pixels],
+                y_stride: pixels as u32,
+                u_plane: &image.u_plane[y * u_stride + x..y * u_stride + x + pixels],
+                u_stride: pixels as u32,
+                v_plane: &image.v_plane[y * v_stride + x..y * v_stride + x + pixels],
+                v_stride: pixels as u32,
+                a_plane: &opaque[..pixels],
+                a_stride: pixels as u32,
+                width: pixels as u32,
+                height: 1,
+            };
+            let segment = &mut segment[..pixels * 4];
+            if BGR {
+                ycgco444_alpha_to_bgra(&segment_image, segment, pixels as u32 * 4, range)?;
+            } else {
+                ycgco444_alpha_to_rgba(&segment_image, segment, pixels as u32 * 4, range)?;
+            }
+            for (dst, src) in dst_row[x * 3..(x + pixels) * 3]
+                .chunks_exact_mut(3)
+                .zip(segment.chunks_exact(4))
+            {
+                dst.copy_from_slice(&src[..3]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether layout of high bit depth YUV has alpha and stores blue first, alpha-first layouts
+/// are not supported
+fn rgb_order(image_configuration: ImageConfiguration) -> (bool, bool) {
+    match image_configuration {
+        ImageConfiguration::Rgb => (false, false),
+        ImageConfiguration::Rgba => (true, false),
+        ImageConfiguration::Bgr => (false, true),
+        ImageConfiguration::Bgra => (true, true),
+        ImageConfiguration::Argb | ImageConfiguration::Abgr => {
+            unreachable!("Alpha-first layouts are not supported by YUV")
+        }
+    }
+}
+
+/// Converts interleaved `SRC_CHANNELS` image by RGB or RGBA converter of `DST_CHANNELS`,
+/// rows are reordered segment by segment on the stack: red and blue are swapped when
+/// `swap_rb` is set and missing alpha is filled with `opaque`
+fn convert_reordered<const SRC_CHANNELS: usize, const DST_CHANNELS: usize>(
+    planar_image: &mut YuvPlanarImageMut<u16>,
+    src: &[u16],
+    src_stride: u32,
+    swap_rb: bool,
+    opaque: u16,
+    convert: impl Fn(&mut YuvPlanarImageMut<u16>, &[u16], u32) -> Result<(), YuvError>,
+) -> Result<(), YuvError> {
+    let (width, height) = (planar_image.width as usize, planar_image.height as usize);
+    let y_stride = planar_image.y_stride as usize;
+    let u_stride = planar_image.u_stride as usize;
+    let v_stride = planar_image.v_stride as usize;
+    let y_plane = planar_image.y_plane.borrow_mut();
+    let u_plane = planar_image.u_plane.borrow_mut();
+    let v_plane = planar_image.v_plane.borrow_mut();
+    let (r, b) = if swap_rb { (2, 0) } else { (0, 2) };
+    let mut segment = [0u16; SEGMENT_PIXELS * 4];
+    for (y, src_row) in src.chunks(src_stride as usize).take(height).enumerate() {
+        for x in (0..width).step_by(SEGMENT_PIXELS) {
+            let pixels = SEGMENT_PIXELS.min(width - x);
+            let src_pixels = &src_row[x * SRC_CHANNELS..(x + pixels) * SRC_CHANNELS];
+            for (dst, src) in segment
+                .chunks_exact_mut(DST_CHANNELS)
+                .zip(src_pixels.chunks_exact(SRC_CHANNELS))
+            {
+                dst[0] = src[r];
+                dst[1] = src[1];
+                dst[2] = src[b];
+                if DST_CHANNELS == 4 {
+                    dst[3] = if SRC_CHANNELS == 4 { src[3] } else { opaque };
+                }
+            }
+            let mut segment_image = YuvPlanarImageMut {
+                y_plane: BufferStoreMut::Borrowed(
+                    &mut y_plane[y * y_stride + x..y * y_stride + x + pixels],
+                ),
+                y_stride: pixels as u32,
+                u_plane: BufferStoreMut::Borrowed(
+                    &mut u_plane[y * u_stride + x..y * u_stride + x + pixels],
+                ),
+                u_stride: pixels as u32,
+                v_plane: BufferStoreMut::Borrowed(
+                    &mut v_plane[y * v_stride + x..y * v_stride + x + pixels],
+                ),
+                v_stride: pixels as u32,
+                width: pixels as u32,
+                height: 1,
+            };
+            convert(
+                &mut segment_image,
+                &segment[..pixels * DST_CHANNELS],
+                (pixels * DST_CHANNELS) as u32,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Converts interleaved high bit depth `IMAGE` into YUV 4:4:4 with matrix, range and
+/// bit depth of `options`, alpha-first layouts are not supported
+pub(crate) fn image_to_yuv444_u16<const IMAGE: u8>(
+    planar_image: &mut YuvPlanarImageMut<u16>,
+    src: &[u16],
+    src_stride: u32,
+    options: YuvOptions,
+) -> Result<(), YuvError> {
+    let (has_alpha, swap_rb) = rgb_order(IMAGE.into());
+    let range = options.range;
+    let matrix = options.matrix.standard_matrix();
+    // There is no 12 bit YCgCo converter from RGB, RGB is expanded to RGBA
+    let converter_alpha = has_alpha || (options.bit_depth == 12 && matrix.is_none());
+    let convert = |image: &mut YuvPlanarImageMut<u16>, src: &[u16], stride: u32| match (
+        options.bit_depth,
+        matrix,
+        converter_alpha,
+    ) {
+        (10, None, false) => rgb10_to_icgc410(image, src, stride, range),
+        (10, None, true) => rgba10_to_icgc410(image, src, stride, range),
+        (12, None, true) => rgba12_to_icgc412(image, src, stride, range),
+        (10, Some(matrix), false) => rgb10_to_i410(image, src, stride, range, matrix),
+        (10, Some(matrix), true) => rgba10_to_i410(image, src, stride, range, matrix),
+        (12, Some(matrix), false) => rgb12_to_i412(image, src, stride, range, matrix),
+        (12, Some(matrix), true) => rgba12_to_i412(image, src, stride, range, matrix),
+        (14, Some(matrix), false) => rgb14_to_i414(image, src, stride, range, matrix),
+        (14, Some(matrix), true) => rgba14_to_i414(image, src, stride, range, matrix),
+        (16, Some(matrix), false) => rgb16_to_i416(image, src, stride, range, matrix),
+        (16, Some(matrix), true) => rgba16_to_i416(image, src, stride, range, matrix),
+        _ => unreachable!("Bit depth and matrix are validated before conversion"),
+    };
+    let opaque = ((1u32 << options.bit_depth) - 1) as u16;
+    match (has_alpha, converter_alpha, swap_rb) {
+        (_, _, false) if has_alpha == converter_alpha => convert(planar_image, src, src_stride),
+        (false, false, _) => {
+            convert_reordered::<3, 3>(planar_image, src, src_stride, swap_rb, opaque, convert)
+        }
+        (false, true, _) => {
+            convert_reordered::<3, 4>(planar_image, src, src_stride, swap_rb, opaque, convert)
+        }
+        _ => convert_reordered::<4, 4>(planar_image, src, src_stride, swap_rb, opaque, convert),
+    }
+}
+
+/// Converts high bit depth YUV 4:4:4 back into interleaved `IMAGE` with matrix, range and
+/// bit depth of `options`, alpha plane is used only by layouts with alpha
+pub(crate) fn yuv444_to_image_u16<const IMAGE: u8>(
+    planar_image: &YuvPlanarImageWithAlpha<u16>,
+    dst: &mut [u16],
+    dst_stride: u32,
+    options: YuvOptions,
+) -> Result<(), YuvError> {
+    let (has_alpha, swap_rb) = rgb_order(IMAGE.into());
+    let range = options.range;
+    let image = YuvPlanarImage {
+        y_plane: planar_image.y_plane,
+        y_stride: planar_image.y_stride,
+        u_plane: planar_image.u_plane,
+        u_stride: planar_image.u_stride,
+        v_plane: planar_image.v_plane,
+        v_stride: planar_image.v_stride,
+        width: planar_image.width,
+        height: planar_image.height,
+    };
+    let stride = dst_stride;
+    match (
+        options.bit_depth,
+        options.matrix.standard_matrix(),
+        has_alpha,
+    ) {
+        (10, None, false) => icgc410_to_rgb10(&image, dst, stride, range),
+        (10, None, true) => icgc410_to_rgba10(&image, dst, stride, range),
+        (12, None, false) => icgc412_to_rgb12(&image, dst, stride, range),
+        (12, None, true) => icgc412_to_rgba12(&image, dst, stride, range),
+        (10, Some(matrix), false) => i410_to_rgb10(&image, dst, stride, range, matrix),
+        (10, Some(matrix), true) => i410_to_rgba10(&image, dst, stride, range, matrix),
+        (12, Some(matrix), false) => i412_to_rgb12(&image, dst, stride, range, matrix),
+        (12, Some(matrix), true) => i412_to_rgba12(&image, dst, stride, range, matrix),
+        (14, Some(matrix), false) => i414_to_rgb14(&image, dst, stride, range, matrix),
+        (14, Some(matrix), true) => i414_to_rgba14(&image, dst, stride, range, matrix),
+        (16, Some(matrix), false) => i416_to_rgb16(&image, dst, stride, range, matrix),
+        (16, Some(matrix), true) => i416_to_rgba16(&image, dst, stride, range, matrix),
+        _ => unreachable!("Bit depth and matrix are validated before conversion"),
+    }?;
+
+    // High bit depth converters with alpha are not available for every bit depth and
+    // do not keep alpha on every path, so alpha is restored from the plane. Blue first
+    // layouts are converted as RGB and swapped in place.
+    let width = planar_image.width as usize;
+    let channels = if has_alpha { 4 } else { 3 };
+    if has_alpha || swap_rb {
+        for (y, dst) in dst
+            .chunks_mut(dst_stride as usize)
+            .take(planar_image.height as usize)
+            .enumerate()
+        {
+            for (x, pixel) in dst[..width * channels]
+                .chunks_exact_mut(channels)
+                .enumerate()
+            {
+                if swap_rb {
+                    pixel.swap(0, 2);
+                }
+                if has_alpha {
+                    pixel[3] = planar_image.a_plane[y * planar_image.a_stride as usize + x];
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Alpha of interleaved `CHANNELS` image as a plane of `width` values per row written
+/// into `a_plane`, empty for images without alpha
+pub(crate) fn alpha_plane<'a, T: Copy + Default, const CHANNELS: usize>(
+    src: &[T],
+    src_stride: u32,
+    width: u32,
+    height: u32,
+    a_plane: &'a mut Vec<T>,
+) -> &'a [T] {
+    if CHANNELS != 4 {
+        a_plane.clear();
+        return a_plane;
+    }
+    let a_plane = working_buffer(a_plane, width as usize * height as usize);
+    for (a_row, src) in a_plane
+        .chunks_exact_mut(width as usize)
+        .zip(src.chunks(src_stride as usize))
+    {
+        for (dst, src) in a_row.iter_mut().zip(src.chunks_exact(4)) {
+            *dst = src[3];
+        }
+    }
+    a_plane
+}
+
+/// First luma value and count of luma levels of `range` at `bit_depth`
+#[inline]
+pub(crate) fn luma_levels(range: YuvRange, bit_depth: u32) -> (u32, usize) {
+    match range {
+        YuvRange::Full => (0, 1usize << bit_depth),
+        YuvRange::Limited => (16 << (bit_depth - 8), (219usize << (bit_depth - 8)) + 1),
+    }
+}
+
+/// Shifts limited range luma to start from zero so histograms span only its levels,
+/// values outside of the range are clamped. Full range is left as is.
+pub(crate) fn luma_to_levels<T: Copy + AsPrimitive<u32> + 'static>(
+    plane: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+    range: YuvRange,
+    bit_depth: u32,
+) where
+    u32: AsPrimitive<T>,
+{
+    if range == YuvRange::Full {
+        return;
+    }
+    let (first, levels) = luma_levels(range, bit_depth);
+    let last = first + levels as u32 - 1;
+    for row in plane.chunks_mut(stride).take(height) {
+        for value in row[..width].iter_mut() {
+            *value = (value.as_().clamp(first, last) - first).as_();
+        }
+    }
+}
+
+/// Reverts [luma_to_levels]
+pub(crate) fn luma_from_levels<T: Copy + AsPrimitive<u32> + 'static>(
+    plane: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+    range: YuvRange,
+    bit_depth: u32,
+) where
+    u32: AsPrimitive<T>,
+{
+    if range == YuvRange::Full {
+        return;
+    }
+    let (first, _) = luma_levels(range, bit_depth);
+    for row in plane.chunks_mut(stride).take(height) {
+        for value in row[..width].iter_mut() {
+            *value = (value.as_() + first).as_();
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use histogram_equalization::{
+    ClaheGridSize, EqualizationColorSpace, EqualizationMethod, Equalizer, ImageConfiguration,
+};
+
+const WIDTH: usize = 41;
+const HEIGHT: usize = 27;
+
+const COLOR_SPACES: [EqualizationColorSpace; 8] = [
+    EqualizationColorSpace::Yuv,
+    EqualizationColorSpace::Hsv,
+    EqualizationColorSpace::Hsl,
+    EqualizationColorSpace::Lab,
+    EqualizationColorSpace::Luv,
+    EqualizationColorSpace::Oklab,
+    EqualizationColorSpace::Oklch,
+    EqualizationColorSpace::Jzazbz,
+];
+
+const METHODS: [EqualizationMethod; 3] = [
+    EqualizationMethod::HistogramEqualization,
+    EqualizationMethod::Ahe,
+    EqualizationMethod::Clahe,
+];
+
+/// RGBA with every alpha value from 0 to 255 spread over the image
+fn rgba() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .flat_map(|i| {
+            let y = i / WIDTH;
+            [
+                ((i * 7919 + y * 31) % 211 + 20) as u8,
+                ((i * 104729 + y * 17) % 199 + 30) as u8,
+                ((i * 1299709 + y * 7) % 223 + 16) as u8,
+                (i * 113 % 256) as u8,
+            ]
+        })
+        .collect()
+}
+
+/// Reorders RGBA pixels into `layout`
+fn to_layout(rgba: &[u8], layout: ImageConfiguration) -> Vec<u8> {
+    rgba.chunks_exact(4)
+        .flat_map(|p| match layout {
+            ImageConfiguration::Rgba => [p[0], p[1], p[2], p[3]],
+            ImageConfiguration::Bgra => [p[2], p[1], p[0], p[3]],
+            ImageConfiguration::Argb => [p[3], p[0], p[1], p[2]],
+            ImageConfiguration::Abgr => [p[3], p[2], p[1], p[0]],
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Reorders pixels of `layout` back into RGBA
+fn to_rgba(image: &[u8], layout: ImageConfiguration) -> Vec<u8> {
+    image
+        .chunks_exact(4)
+        .flat_map(|p| match layout {
+            ImageConfiguration::Rgba => [p[0], p[1], p[2], p[3]],
+            ImageConfiguration::Bgra => [p[2], p[1], p[0], p[3]],
+            ImageConfiguration::Argb => [p[1], p[2], p[3], p[0]],
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[test]
+fn alpha_is_preserved() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let stride = w * 4;
+    let rgba = rgba();
+    for color_space in COLOR_SPACES {
+        let layouts: &[ImageConfiguration] = if color_space == EqualizationColorSpace::Yuv {
+            &[ImageConfiguration::Rgba, ImageConfiguration::Bgra]
+        } else {
+            &[
+                ImageConfiguration::Rgba,
+                ImageConfiguration::Bgra,
+                ImageConfiguration::Argb,
+                ImageConfiguration::Abgr,
+            ]
+        };
+        for &layout in layouts {
+            let src = to_layout(&rgba, layout);
+            let alpha_offset = layout.get_a_channel_offset();
+            for method in METHODS {
+                let mut dst = vec![0u8; src.len()];
+                Equalizer::new()
+                    .with_color_space(color_space)
+                    .with_layout(layout)
+                    .with_method(method)
+                    .with_grid_size(ClaheGridSize::new(3, 3))
+                    .apply(&src, stride, &mut dst, stride, w, h)
+                    .unwrap();
+                for (src, dst) in src.chunks_exact(4).zip(dst.chunks_exact(4)) {
+                    assert_eq!(
+                        src[alpha_offset], dst[alpha_offset],
+                        "{color_space:?} {layout:?} {method:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn premultiplied_matches_straight_alpha() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let stride = w * 4;
+    let premultiplied: Vec<u8> = rgba()
+        .chunks_exact(4)
+        .flat_map(|p| {
+            let a = p[3] as f32 / 255.;
+            [
+                (p[0] as f32 * a).round() as u8,
+                (p[1] as f32 * a).round() as u8,
+                (p[2] as f32 * a).round() as u8,
+                p[3],
+            ]
+        })
+        .collect();
+    let unpremultiplied: Vec<u8> = premultiplied
+        .chunks_exact(4)
+        .flat_map(|p| {
+            let a = p[3] as u32;
+            let unpremultiply = |v: u8| {
+                (v as u32 * 255 + a / 2)
+                    .checked_div(a)
+                    .map_or(v, |v| v.min(255) as u8)
+            };
+            [
+                unpremultiply(p[0]),
+                unpremultiply(p[1]),
+                unpremultiply(p[2]),
+                p[3],
+            ]
+        })
+        .collect();
+
+    for color_space in COLOR_SPACES {
+        let layouts = if color_space == EqualizationColorSpace::Yuv {
+            [ImageConfiguration::Rgba, ImageConfiguration::Bgra]
+        } else {
+            [ImageConfiguration::Rgba, ImageConfiguration::Argb]
+        };
+        for layout in layouts {
+            for method in METHODS {
+                let equalizer = Equalizer::new()
+                    .with_color_space(color_space)
+                    .with_layout(layout)
+                    .with_method(method)
+                    .with_grid_size(ClaheGridSize::new(3, 3));
+
+                let mut straight = vec![0u8; unpremultiplied.len()];
+                equalizer
+                    .apply(
+                        &to_layout(&unpremultiplied, layout),
+                        stride,
+                        &mut straight,
+                        stride,
+                        w,
+                        h,
+                    )
+                    .unwrap();
+
+                let mut dst = vec![0u8; premultiplied.len()];
+                equalizer
+                    .with_premultiplied_alpha(true)
+                    .apply(
+                        &to_layout(&premultiplied, layout),
+                        stride,
+                        &mut dst,
+                        stride,
+                        w,
+                        h,
+                    )
+                    .unwrap();
+
+                let mut max_difference = 0;
+                for (dst, straight) in to_rgba(&dst, layout)
+                    .chunks_exact(4)
+                    .zip(to_rgba(&straight, layout).chunks_exact(4))
+                {
+                    let a = straight[3] as f32 / 255.;
+                    for c in 0..3 {
+                        let expected = (straight[c] as f32 * a).round() as i32;
+                        max_difference = max_difference.max((dst[c] as i32 - expected).abs());
+                    }
+                    assert_eq!(dst[3], straight[3]);
+                }
+                assert!(
+                    max_difference <= 1,
+                    "{color_space:?} {layout:?} {method:?} {max_difference}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+#![allow(deprecated)]
+
+use histogram_equalization::{
+    clahe_lab_bgr, clahe_lab_rgb, hist_equal_yuv_bgra, hist_equal_yuv_rgba, ClaheGridSize,
+    EqualizationColorSpace, EqualizationMethod, Equalizer, ImageConfiguration,
+};
+
+const WIDTH: usize = 37;
+const HEIGHT: usize = 23;
+
+fn image(channels: usize) -> Vec<u8> {
+    (0..WIDTH * HEIGHT * channels)
+        .map(|i| ((i * 7919 + (i / (WIDTH * channels)) * 31) % 211 + 20) as u8)
+        .collect()
+}
+
+/// Exchanges red and blue of every pixel
+fn swap_red_blue(image: &[u8], channels: usize) -> Vec<u8> {
+    let mut swapped = image.to_vec();
+    for pixel in swapped.chunks_exact_mut(channels) {
+        pixel.swap(0, 2);
+    }
+    swapped
+}
+
+#[test]
+fn bgr_matches_swapped_rgb() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    for color_space in [
+        EqualizationColorSpace::Yuv,
+        EqualizationColorSpace::Hsv,
+        EqualizationColorSpace::Hsl,
+        EqualizationColorSpace::Lab,
+        EqualizationColorSpace::Luv,
+        EqualizationColorSpace::Oklab,
+        EqualizationColorSpace::Oklch,
+        EqualizationColorSpace::Jzazbz,
+    ] {
+        for (rgb, bgr) in [
+            (ImageConfiguration::Rgb, ImageConfiguration::Bgr),
+            (ImageConfiguration::Rgba, ImageConfiguration::Bgra),
+        ] {
+            let channels = rgb.get_channels_count();
+            let stride = w * channels as u32;
+            let src = image(channels);
+            let swapped_src = swap_red_blue(&src, channels);
+            for method in [
+                EqualizationMethod::HistogramEqualization,
+                EqualizationMethod::Ahe,
+                EqualizationMethod::Clahe,
+            ] {
+                let equalizer = Equalizer::new()
+                    .with_color_space(color_space)
+                    .with_method(method)
+                    .with_grid_size(ClaheGridSize::new(3, 2));
+
+                let mut rgb_dst = vec![0u8; src.len()];
+                equalizer
+                    .with_layout(rgb)
+                    .apply(&src, stride, &mut rgb_dst, stride, w, h)
+                    .unwrap();
+                let mut bgr_dst = vec![0u8; src.len()];
+                equalizer
+                    .with_layout(bgr)
+                    .apply(&swapped_src, stride, &mut bgr_dst, stride, w, h)
+                    .unwrap();
+                assert_eq!(
+                    bgr_dst,
+                    swap_red_blue(&rgb_dst, channels),
+                    "{color_space:?} {bgr:?} {method:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn bgr_free_functions_match_swapped_rgb() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let grid = ClaheGridSize::new(4, 4);
+
+    let src = image(3);
+    let stride = w * 3;
+    let mut rgb_dst = vec![0u8; src.len()];
+    clahe_lab_rgb(&src, stride, &mut rgb_dst, stride, w, h, 2f32, grid, 128).unwrap();
+    let mut bgr_dst = vec![0u8; src.len()];
+    clahe_lab_bgr(
+        &swap_red_blue(&src, 3),
+        stride,
+        &mut bgr_dst,
+        stride,
+        w,
+        h,
+        2f32,
+        grid,
+        128,
+    )
+    .unwrap();
+    assert_eq!(bgr_dst, swap_red_blue(&rgb_dst, 3));
+
+    let src = image(4);
+    let stride = w * 4;
+    let mut rgba_dst = vec![0u8; src.len()];
+    hist_equal_yuv_rgba(&src, stride, &mut rgba_dst, stride, w, h).unwrap();
+    let mut bgra_dst = vec![0u8; src.len()];
+    hist_equal_yuv_bgra(&swap_red_blue(&src, 4), stride, &mut bgra_dst, stride, w, h).unwrap();
+    assert_eq!(bgra_dst, swap_red_blue(&rgba_dst, 4));
+}
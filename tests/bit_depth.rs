@@ -0,0 +1,89 @@
+use histogram_equalization::{
+    ahe_gray_u16, clahe_gray_u16, clahe_hsv_rgb_u16, clahe_lab_rgb_u16, clahe_oklab_rgb_u16,
+    hist_equal_gray_u16, hist_equal_hsv_rgb_u16, hist_equal_lab_rgb_u16, ClaheGridSize,
+    HistogramError,
+};
+
+const WIDTH: usize = 29;
+const HEIGHT: usize = 17;
+
+/// Values over the whole range of `bit_depth`, including the maximum
+fn image(bit_depth: u32, channels: usize) -> Vec<u16> {
+    let levels = 1usize << bit_depth;
+    (0..WIDTH * HEIGHT * channels)
+        .map(|i| ((i * 7919 + (i / (WIDTH * channels)) * 31) % levels) as u16)
+        .collect()
+}
+
+#[test]
+fn output_stays_within_bit_depth() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let grid = ClaheGridSize::new(3, 2);
+    for bit_depth in [10, 12] {
+        let max = (1u16 << bit_depth) - 1;
+
+        let src = image(bit_depth, 1);
+        let mut dst = vec![0u16; src.len()];
+        hist_equal_gray_u16(&src, w, &mut dst, w, w, h, bit_depth).unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "gray HE {bit_depth}");
+        ahe_gray_u16(&src, w, &mut dst, w, w, h, bit_depth, grid).unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "gray AHE {bit_depth}");
+        clahe_gray_u16(&src, w, &mut dst, w, w, h, bit_depth, 2f32, grid).unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "gray CLAHE {bit_depth}");
+
+        let src = image(bit_depth, 3);
+        let stride = w * 3;
+        let mut dst = vec![0u16; src.len()];
+        let bins = 1usize << bit_depth;
+        hist_equal_hsv_rgb_u16(&src, stride, &mut dst, stride, w, h, bit_depth, bins).unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "HSV HE {bit_depth}");
+        hist_equal_lab_rgb_u16(&src, stride, &mut dst, stride, w, h, bit_depth, bins).unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "Lab HE {bit_depth}");
+        clahe_hsv_rgb_u16(
+            &src, stride, &mut dst, stride, w, h, bit_depth, 2f32, grid, bins,
+        )
+        .unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "HSV CLAHE {bit_depth}");
+        clahe_lab_rgb_u16(
+            &src, stride, &mut dst, stride, w, h, bit_depth, 2f32, grid, bins,
+        )
+        .unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "Lab CLAHE {bit_depth}");
+        clahe_oklab_rgb_u16(
+            &src, stride, &mut dst, stride, w, h, bit_depth, 2f32, grid, bins,
+        )
+        .unwrap();
+        assert!(dst.iter().all(|&v| v <= max), "Oklab CLAHE {bit_depth}");
+    }
+}
+
+#[test]
+fn out_of_range_bit_depth_is_rejected() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let grid = ClaheGridSize::new(3, 2);
+    let src = vec![0u16; WIDTH * HEIGHT * 3];
+    let mut dst = vec![0u16; src.len()];
+    let stride = w * 3;
+    for bit_depth in [0, 17, 32] {
+        assert!(matches!(
+            hist_equal_gray_u16(&src, w, &mut dst, w, w, h, bit_depth),
+            Err(HistogramError::UnsupportedBitDepth(depth)) if depth == bit_depth
+        ));
+        assert!(matches!(
+            ahe_gray_u16(&src, w, &mut dst, w, w, h, bit_depth, grid),
+            Err(HistogramError::UnsupportedBitDepth(depth)) if depth == bit_depth
+        ));
+        assert!(matches!(
+            clahe_gray_u16(&src, w, &mut dst, w, w, h, bit_depth, 2f32, grid),
+            Err(HistogramError::UnsupportedBitDepth(depth)) if depth == bit_depth
+        ));
+        assert!(matches!(
+            hist_equal_hsv_rgb_u16(&src, stride, &mut dst, stride, w, h, bit_depth, 128),
+            Err(HistogramError::UnsupportedBitDepth(depth)) if depth == bit_depth
+        ));
+        assert!(matches!(
+            clahe_lab_rgb_u16(&src, stride, &mut dst, stride, w, h, bit_depth, 2f32, grid, 128),
+            Err(HistogramError::UnsupportedBitDepth(depth)) if depth == bit_depth
+        ));
+    }
+}
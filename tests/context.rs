@@ -0,0 +1,70 @@
+use histogram_equalization::{
+    ClaheGridSize, ClaheMode, EqualizationColorSpace, EqualizationMethod, Equalizer,
+    EqualizerContext, ImageConfiguration,
+};
+
+fn image(width: usize, height: usize, channels: usize, seed: usize) -> Vec<u8> {
+    (0..width * height * channels)
+        .map(|i| ((i * 7919 + (i / (width * channels)) * 31 + seed) % 211 + 20) as u8)
+        .collect()
+}
+
+/// Context is reused across configurations and sizes, shrinking and growing,
+/// output is always the same as without it
+#[test]
+fn context_matches_apply() {
+    let mut context = EqualizerContext::new();
+    let sizes = [(37usize, 21usize), (16, 9), (37, 21), (53, 30)];
+
+    for color_space in [
+        EqualizationColorSpace::Yuv,
+        EqualizationColorSpace::Hsv,
+        EqualizationColorSpace::Lab,
+        EqualizationColorSpace::Oklch,
+    ] {
+        for layout in [ImageConfiguration::Rgb, ImageConfiguration::Rgba] {
+            for method in [
+                EqualizationMethod::HistogramEqualization,
+                EqualizationMethod::Ahe,
+                EqualizationMethod::Clahe,
+            ] {
+                let equalizer = Equalizer::new()
+                    .with_color_space(color_space)
+                    .with_layout(layout)
+                    .with_method(method)
+                    .with_grid_size(ClaheGridSize::new(3, 2));
+                let channels = layout.get_channels_count();
+
+                for (seed, &(width, height)) in sizes.iter().enumerate() {
+                    let src = image(width, height, channels, seed);
+                    let (w, h, stride) = (width as u32, height as u32, (width * channels) as u32);
+
+                    let mut expected = vec![0u8; src.len()];
+                    equalizer
+                        .apply(&src, stride, &mut expected, stride, w, h)
+                        .unwrap();
+                    let mut dst = vec![0u8; src.len()];
+                    equalizer
+                        .apply_with_context(&mut context, &src, stride, &mut dst, stride, w, h)
+                        .unwrap();
+                    assert_eq!(dst, expected, "{color_space:?} {layout:?} {method:?}");
+                }
+            }
+        }
+    }
+
+    // Modes with their own buffers work with context as well
+    for mode in [ClaheMode::OpenCv, ClaheMode::SlidingWindow] {
+        let equalizer = Equalizer::new().with_clahe_mode(mode);
+        let src = image(37, 21, 3, 5);
+        let mut expected = vec![0u8; src.len()];
+        equalizer
+            .apply(&src, 37 * 3, &mut expected, 37 * 3, 37, 21)
+            .unwrap();
+        let mut dst = vec![0u8; src.len()];
+        equalizer
+            .apply_with_context(&mut context, &src, 37 * 3, &mut dst, 37 * 3, 37, 21)
+            .unwrap();
+        assert_eq!(dst, expected, "{mode:?}");
+    }
+}
@@ -0,0 +1,207 @@
+use histogram_equalization::{
+    ClaheMode, EqualizationColorSpace, EqualizationMethod, Equalizer, EqualizerContext,
+    ImageConfiguration, VideoEqualizer, YuvBiPlanarImageMut, YuvChromaSubsampling, YuvMatrix,
+    YuvPlanarImageMut,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts allocations of the whole test binary, so it holds a single test
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const WIDTH: usize = 64;
+const HEIGHT: usize = 48;
+
+/// Allocations made by `run` after the first call of it
+fn steady_state_allocations(mut run: impl FnMut()) -> usize {
+    run();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..3 {
+        run();
+    }
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Rayon allocates work queued into its pool from outside and while workers warm up,
+/// so frames are equalized from inside a pool of a single worker
+#[test]
+fn steady_state_does_not_allocate() {
+    #[cfg(feature = "rayon")]
+    {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(equalize_frames);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        equalize_frames();
+    }
+}
+
+fn equalize_frames() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+
+    for color_space in [
+        EqualizationColorSpace::Hsv,
+        EqualizationColorSpace::Lab,
+        EqualizationColorSpace::Jzazbz,
+        EqualizationColorSpace::Oklab,
+        EqualizationColorSpace::Yuv,
+    ] {
+        for layout in [
+            ImageConfiguration::Rgb,
+            ImageConfiguration::Rgba,
+            ImageConfiguration::Bgr,
+        ] {
+            for (method, mode) in [
+                (EqualizationMethod::HistogramEqualization, ClaheMode::Native),
+                (EqualizationMethod::Ahe, ClaheMode::Native),
+                (EqualizationMethod::Clahe, ClaheMode::Native),
+                (EqualizationMethod::Ahe, ClaheMode::OpenCv),
+                (EqualizationMethod::Clahe, ClaheMode::OpenCv),
+                (EqualizationMethod::Ahe, ClaheMode::SlidingWindow),
+                (EqualizationMethod::Clahe, ClaheMode::SlidingWindow),
+            ] {
+                let channels = layout.get_channels_count();
+                let src: Vec<u8> = (0..WIDTH * HEIGHT * channels)
+                    .map(|i| (i * 7919 % 251) as u8)
+                    .collect();
+                let mut dst = vec![0u8; src.len()];
+                let stride = w * channels as u32;
+                let equalizer = Equalizer::new()
+                    .with_color_space(color_space)
+                    .with_layout(layout)
+                    .with_method(method)
+                    .with_clahe_mode(mode);
+                let name = format!("{color_space:?} {layout:?} {method:?} {mode:?}");
+
+                let mut context = EqualizerContext::new();
+                let allocations = steady_state_allocations(|| {
+                    equalizer
+                        .apply_with_context(&mut context, &src, stride, &mut dst, stride, w, h)
+                        .unwrap();
+                });
+                assert_eq!(allocations, 0, "{name}");
+
+                // Frames of a video are equalized only by native tiles
+                if mode != ClaheMode::Native {
+                    continue;
+                }
+                let mut video = VideoEqualizer::new(equalizer);
+                let allocations = steady_state_allocations(|| {
+                    video.apply(&src, stride, &mut dst, stride, w, h).unwrap();
+                });
+                assert_eq!(allocations, 0, "{name}");
+            }
+        }
+    }
+
+    // Standard matrices have converters of their own
+    let src: Vec<u8> = (0..WIDTH * HEIGHT * 3)
+        .map(|i| (i * 7919 % 251) as u8)
+        .collect();
+    let mut dst = vec![0u8; src.len()];
+    let equalizer = Equalizer::new()
+        .with_color_space(EqualizationColorSpace::Yuv)
+        .with_yuv_matrix(YuvMatrix::Bt709);
+    let mut context = EqualizerContext::new();
+    let allocations = steady_state_allocations(|| {
+        equalizer
+            .apply_with_context(&mut context, &src, w * 3, &mut dst, w * 3, w, h)
+            .unwrap();
+    });
+    assert_eq!(allocations, 0);
+
+    let mut image = YuvPlanarImageMut::<u8>::alloc(w, h, YuvChromaSubsampling::Yuv420);
+    let mut video = VideoEqualizer::default();
+    let allocations = steady_state_allocations(|| {
+        video
+            .apply_yuv_planar(&mut image, YuvChromaSubsampling::Yuv420)
+            .unwrap();
+    });
+    assert_eq!(allocations, 0);
+
+    let subsampling = YuvChromaSubsampling::Yuv420;
+    let mut planar = YuvPlanarImageMut::<u8>::alloc(w, h, subsampling);
+    let mut biplanar = YuvBiPlanarImageMut::<u8>::alloc(w, h, subsampling);
+    let mut planar_u16 = YuvPlanarImageMut::<u16>::alloc(w, h, subsampling);
+    let mut biplanar_u16 = YuvBiPlanarImageMut::<u16>::alloc(w, h, subsampling);
+    for (i, luma) in planar.y_plane.borrow_mut().iter_mut().enumerate() {
+        *luma = (i * 7919 % 251) as u8;
+    }
+    for (i, luma) in planar_u16.y_plane.borrow_mut().iter_mut().enumerate() {
+        *luma = (i * 7919 % 1021) as u16;
+    }
+    biplanar
+        .y_plane
+        .borrow_mut()
+        .copy_from_slice(planar.y_plane.borrow());
+    biplanar_u16
+        .y_plane
+        .borrow_mut()
+        .copy_from_slice(planar_u16.y_plane.borrow());
+
+    for (method, mode) in [
+        (EqualizationMethod::HistogramEqualization, ClaheMode::Native),
+        (EqualizationMethod::Clahe, ClaheMode::Native),
+        (EqualizationMethod::Clahe, ClaheMode::OpenCv),
+        (EqualizationMethod::Clahe, ClaheMode::SlidingWindow),
+    ] {
+        let equalizer = Equalizer::new().with_method(method).with_clahe_mode(mode);
+        let name = format!("{method:?} {mode:?}");
+        let mut context = EqualizerContext::new();
+
+        let allocations = steady_state_allocations(|| {
+            equalizer
+                .apply_yuv_planar_with_context(&mut context, &mut planar, subsampling)
+                .unwrap();
+        });
+        assert_eq!(allocations, 0, "planar {name}");
+        let allocations = steady_state_allocations(|| {
+            equalizer
+                .apply_yuv_biplanar_with_context(&mut context, &mut biplanar, subsampling)
+                .unwrap();
+        });
+        assert_eq!(allocations, 0, "bi-planar {name}");
+        let allocations = steady_state_allocations(|| {
+            equalizer
+                .apply_yuv_planar_u16_with_context(&mut context, &mut planar_u16, subsampling, 10)
+                .unwrap();
+        });
+        assert_eq!(allocations, 0, "planar u16 {name}");
+        let allocations = steady_state_allocations(|| {
+            equalizer
+                .apply_yuv_biplanar_u16_with_context(
+                    &mut context,
+                    &mut biplanar_u16,
+                    subsampling,
+                    10,
+                )
+                .unwrap();
+        });
+        assert_eq!(allocations, 0, "bi-planar u16 {name}");
+    }
+}
@@ -0,0 +1,148 @@
+#!/usr/bin/env python3
+"""Generates CLAHE regression vectors for `tests/opencv_regression.rs`.
+
+This is a line by line port of `cv::CLAHE::apply` from OpenCV `modules/imgproc/src/clahe.cpp`
+(CLAHE_CalcLut_Body and CLAHE_Interpolation_Body) with float32 arithmetic emulated after
+every operation, so vectors can be regenerated without OpenCV. Vectors of OpenCV itself are
+made by `opencv_reference.py`.
+
+Run from this directory: `python3 clahe_opencv_port.py`
+"""
+
+import struct
+
+
+def f32(value):
+    return struct.unpack('f', struct.pack('f', value))[0]
+
+
+def make_image(width, height, seed, base, value_range):
+    image = []
+    for y in range(height):
+        row = []
+        for x in range(width):
+            h = ((x * 73856093) ^ (y * 19349663) ^ (seed * 83492791)) & 0xFFFFFFFF
+            row.append(base + h % value_range)
+        image.append(row)
+    return image
+
+
+def reflect_101(index, length):
+    if length == 1:
+        return 0
+    period = 2 * (length - 1)
+    index %= period
+    return period - index if index >= length else index
+
+
+def clahe(image, width, height, clip_limit, tiles_x, tiles_y, hist_size):
+    if width % tiles_x == 0 and height % tiles_y == 0:
+        padded_w, padded_h = width, height
+    else:
+        padded_w = width + tiles_x - width % tiles_x
+        padded_h = height + tiles_y - height % tiles_y
+
+    tile_w = padded_w // tiles_x
+    tile_h = padded_h // tiles_y
+    tile_area = tile_w * tile_h
+    max_value = hist_size - 1
+
+    clip = 0
+    if clip_limit > 0.0:
+        clip = max(int(clip_limit * tile_area / hist_size), 1)
+
+    lut_scale = f32(f32(hist_size - 1) / f32(tile_area))
+
+    luts = []
+    for ty in range(tiles_y):
+        for tx in range(tiles_x):
+            hist = [0] * hist_size
+            for y in range(ty * tile_h, (ty + 1) * tile_h):
+                row = image[reflect_101(y, height)]
+                for x in range(tx * tile_w, (tx + 1) * tile_w):
+                    hist[row[reflect_101(x, width)]] += 1
+
+            if clip > 0:
+                clipped = 0
+                for i in range(hist_size):
+                    if hist[i] > clip:
+                        clipped += hist[i] - clip
+                        hist[i] = clip
+                redist_batch = clipped // hist_size
+                residual = clipped - redist_batch * hist_size
+                for i in range(hist_size):
+                    hist[i] += redist_batch
+                if residual != 0:
+                    residual_step = max(hist_size // residual, 1)
+                    i = 0
+                    while i < hist_size and residual > 0:
+                        hist[i] += 1
+                        i += residual_step
+                        residual -= 1
+
+            lut = [0] * hist_size
+            total = 0
+            for i in range(hist_size):
+                total += hist[i]
+                lut[i] = min(max(round(f32(f32(total) * lut_scale)), 0), max_value)
+            luts.append(lut)
+
+    inv_tw = f32(1.0 / tile_w)
+    inv_th = f32(1.0 / tile_h)
+
+    columns = []
+    for x in range(width):
+        txf = f32(f32(f32(x) * inv_tw) - 0.5)
+        tx1 = int(txf // 1)
+        tx2 = tx1 + 1
+        xa = f32(txf - tx1)
+        xa1 = f32(1.0 - xa)
+        columns.append((max(tx1, 0), min(tx2, tiles_x - 1), xa, xa1))
+
+    result = []
+    for y in range(height):
+        tyf = f32(f32(f32(y) * inv_th) - 0.5)
+        ty1 = int(tyf // 1)
+        ty2 = ty1 + 1
+        ya = f32(tyf - ty1)
+        ya1 = f32(1.0 - ya)
+        ty1 = max(ty1, 0)
+        ty2 = min(ty2, tiles_y - 1)
+        row = []
+        for x in range(width):
+            tx1, tx2, xa, xa1 = columns[x]
+            value = image[y][x]
+            l11 = luts[ty1 * tiles_x + tx1][value]
+            l12 = luts[ty1 * tiles_x + tx2][value]
+            l21 = luts[ty2 * tiles_x + tx1][value]
+            l22 = luts[ty2 * tiles_x + tx2][value]
+            top = f32(f32(l11 * xa1) + f32(l12 * xa))
+            bottom = f32(f32(l21 * xa1) + f32(l22 * xa))
+            res = f32(f32(top * ya1) + f32(bottom * ya))
+            row.append(min(max(round(res), 0), max_value))
+        result.append(row)
+    return result
+
+
+# name, width, height, seed, base, range, clip limit, tiles x, tiles y, bins
+CASES = [
+    ("clahe_u8_64x64_8x8", 64, 64, 1, 40, 120, 40.0, 8, 8, 256),
+    ("clahe_u8_37x29_4x3", 37, 29, 2, 0, 256, 2.0, 4, 3, 256),
+    ("clahe_u8_48x30_4x4", 48, 30, 3, 90, 60, 3.0, 4, 4, 256),
+    ("clahe_u8_20x20_2x2_no_clip", 20, 20, 4, 10, 200, 0.0, 2, 2, 256),
+    ("clahe_u16_24x16_3x2", 24, 16, 5, 1000, 3000, 4.0, 3, 2, 65536),
+]
+
+
+def main():
+    for name, width, height, seed, base, value_range, clip_limit, tiles_x, tiles_y, bins in CASES:
+        image = make_image(width, height, seed, base, value_range)
+        result = clahe(image, width, height, clip_limit, tiles_x, tiles_y, bins)
+        fmt = '<%dB' if bins == 256 else '<%dH'
+        with open(name + '.bin', 'wb') as f:
+            for row in result:
+                f.write(struct.pack(fmt % width, *row))
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,60 @@
+#!/usr/bin/env python3
+"""Generates CLAHE reference vectors of OpenCV for `tests/opencv_parity.rs`.
+
+Unlike `clahe_opencv_port.py` this calls `cv2.createCLAHE(clipLimit, tileGridSize).apply`
+itself, so it needs `opencv-python` and `numpy`. Inputs are the same deterministic images as in
+the tests, the reported residual is the count of clipped samples left after the uniform batch,
+cases are chosen so that it is not zero.
+
+Run from this directory: `python3 opencv_reference.py`, then commit `opencv_*.bin` and remove
+`#[ignore]` from the tests.
+"""
+
+import cv2
+import numpy as np
+
+
+def make_image(width, height, seed, base, value_range, dtype):
+    image = np.zeros((height, width), dtype=dtype)
+    for y in range(height):
+        for x in range(width):
+            h = ((x * 73856093) ^ (y * 19349663) ^ (seed * 83492791)) & 0xFFFFFFFF
+            image[y, x] = base + h % value_range
+    return image
+
+
+def residual(image, clip_limit, tiles_x, tiles_y, hist_size):
+    """Residual of the first tile as `cv::CLAHE` computes it, padding included"""
+    height, width = image.shape
+    pad_x = (tiles_x - width % tiles_x) % tiles_x
+    pad_y = (tiles_y - height % tiles_y) % tiles_y
+    padded = cv2.copyMakeBorder(image, 0, pad_y, 0, pad_x, cv2.BORDER_REFLECT_101)
+    tile_w = padded.shape[1] // tiles_x
+    tile_h = padded.shape[0] // tiles_y
+    clip = max(int(clip_limit * tile_w * tile_h / hist_size), 1)
+    hist = np.bincount(padded[:tile_h, :tile_w].ravel(), minlength=hist_size)
+    clipped = int(np.maximum(hist - clip, 0).sum())
+    return clipped % hist_size
+
+
+# name, width, height, seed, base, range, clip limit, tiles x, tiles y, dtype
+CASES = [
+    ("opencv_u8_37x29_4x3", 37, 29, 2, 0, 256, 2.0, 4, 3, np.uint8),
+    ("opencv_u8_64x64_8x8", 64, 64, 1, 40, 120, 40.0, 8, 8, np.uint8),
+    ("opencv_u16_29x23_3x2", 29, 23, 5, 1000, 3000, 4.0, 3, 2, np.uint16),
+]
+
+
+def main():
+    for name, width, height, seed, base, value_range, clip_limit, tiles_x, tiles_y, dtype in CASES:
+        image = make_image(width, height, seed, base, value_range, dtype)
+        clahe = cv2.createCLAHE(clipLimit=clip_limit, tileGridSize=(tiles_x, tiles_y))
+        result = clahe.apply(image)
+        hist_size = 256 if dtype == np.uint8 else 65536
+        print(name, 'residual', residual(image, clip_limit, tiles_x, tiles_y, hist_size))
+        with open(name + '.bin', 'wb') as f:
+            f.write(result.astype(result.dtype.newbyteorder('<')).tobytes())
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,163 @@
+// Deprecated free functions stay until they are removed, so Equalizer is checked against them
+#![allow(deprecated)]
+
+use histogram_equalization::{
+    ahe_hsl_rgb, ahe_hsl_rgba, ahe_hsv_rgb, ahe_hsv_rgba, ahe_jzazbz_rgb, ahe_jzazbz_rgba,
+    ahe_lab_rgb, ahe_lab_rgba, ahe_luv_rgb, ahe_luv_rgba, ahe_oklab_rgb, ahe_oklab_rgba,
+    ahe_oklch_rgb, ahe_oklch_rgba, ahe_yuv_rgb, ahe_yuv_rgba, clahe_hsl_rgb, clahe_hsl_rgba,
+    clahe_hsv_rgb, clahe_hsv_rgba, clahe_jzazbz_rgb, clahe_jzazbz_rgba, clahe_lab_rgb,
+    clahe_lab_rgba, clahe_luv_rgb, clahe_luv_rgba, clahe_oklab_rgb, clahe_oklab_rgba,
+    clahe_oklch_rgb, clahe_oklch_rgba, clahe_yuv_rgb, clahe_yuv_rgba, hist_equal_hsl_rgb,
+    hist_equal_hsl_rgba, hist_equal_hsv_rgb, hist_equal_hsv_rgba, hist_equal_jzazbz_rgb,
+    hist_equal_jzazbz_rgba, hist_equal_lab_rgb, hist_equal_lab_rgba, hist_equal_luv_rgb,
+    hist_equal_luv_rgba, hist_equal_oklab_rgb, hist_equal_oklab_rgba, hist_equal_oklch_rgb,
+    hist_equal_oklch_rgba, hist_equal_yuv_rgb, hist_equal_yuv_rgba, ClaheGridSize,
+    EqualizationColorSpace, EqualizationMethod, Equalizer, HistogramError, ImageConfiguration,
+};
+
+type Clahe = fn(
+    &[u8],
+    u32,
+    &mut [u8],
+    u32,
+    u32,
+    u32,
+    f32,
+    ClaheGridSize,
+    usize,
+) -> Result<(), HistogramError>;
+type Ahe =
+    fn(&[u8], u32, &mut [u8], u32, u32, u32, ClaheGridSize, usize) -> Result<(), HistogramError>;
+type HistEqual = fn(&[u8], u32, &mut [u8], u32, u32, u32, usize) -> Result<(), HistogramError>;
+
+/// Free functions of a color space for RGB and RGBA layouts, YUV has no bins count
+struct FreeFunctions {
+    color_space: EqualizationColorSpace,
+    clahe: [Clahe; 2],
+    ahe: [Ahe; 2],
+    hist_equal: [HistEqual; 2],
+}
+
+fn free_functions() -> [FreeFunctions; 8] {
+    [
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Yuv,
+            clahe: [
+                |s, ss, d, ds, w, h, t, g, _| clahe_yuv_rgb(s, ss, d, ds, w, h, t, g),
+                |s, ss, d, ds, w, h, t, g, _| clahe_yuv_rgba(s, ss, d, ds, w, h, t, g),
+            ],
+            ahe: [
+                |s, ss, d, ds, w, h, g, _| ahe_yuv_rgb(s, ss, d, ds, w, h, g),
+                |s, ss, d, ds, w, h, g, _| ahe_yuv_rgba(s, ss, d, ds, w, h, g),
+            ],
+            hist_equal: [
+                |s, ss, d, ds, w, h, _| hist_equal_yuv_rgb(s, ss, d, ds, w, h),
+                |s, ss, d, ds, w, h, _| hist_equal_yuv_rgba(s, ss, d, ds, w, h),
+            ],
+        },
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Hsv,
+            clahe: [clahe_hsv_rgb, clahe_hsv_rgba],
+            ahe: [ahe_hsv_rgb, ahe_hsv_rgba],
+            hist_equal: [hist_equal_hsv_rgb, hist_equal_hsv_rgba],
+        },
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Hsl,
+            clahe: [clahe_hsl_rgb, clahe_hsl_rgba],
+            ahe: [ahe_hsl_rgb, ahe_hsl_rgba],
+            hist_equal: [hist_equal_hsl_rgb, hist_equal_hsl_rgba],
+        },
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Lab,
+            clahe: [clahe_lab_rgb, clahe_lab_rgba],
+            ahe: [ahe_lab_rgb, ahe_lab_rgba],
+            hist_equal: [hist_equal_lab_rgb, hist_equal_lab_rgba],
+        },
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Luv,
+            clahe: [clahe_luv_rgb, clahe_luv_rgba],
+            ahe: [ahe_luv_rgb, ahe_luv_rgba],
+            hist_equal: [hist_equal_luv_rgb, hist_equal_luv_rgba],
+        },
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Oklab,
+            clahe: [clahe_oklab_rgb, clahe_oklab_rgba],
+            ahe: [ahe_oklab_rgb, ahe_oklab_rgba],
+            hist_equal: [hist_equal_oklab_rgb, hist_equal_oklab_rgba],
+        },
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Oklch,
+            clahe: [clahe_oklch_rgb, clahe_oklch_rgba],
+            ahe: [ahe_oklch_rgb, ahe_oklch_rgba],
+            hist_equal: [hist_equal_oklch_rgb, hist_equal_oklch_rgba],
+        },
+        FreeFunctions {
+            color_space: EqualizationColorSpace::Jzazbz,
+            clahe: [clahe_jzazbz_rgb, clahe_jzazbz_rgba],
+            ahe: [ahe_jzazbz_rgb, ahe_jzazbz_rgba],
+            hist_equal: [hist_equal_jzazbz_rgb, hist_equal_jzazbz_rgba],
+        },
+    ]
+}
+
+#[test]
+fn equalizer_matches_free_functions() {
+    let (width, height) = (45usize, 31usize);
+    let (w, h) = (width as u32, height as u32);
+    let (threshold, grid, bins) = (3f32, ClaheGridSize::new(4, 3), 200);
+
+    for functions in free_functions() {
+        let color_space = functions.color_space;
+        for (index, layout) in [ImageConfiguration::Rgb, ImageConfiguration::Rgba]
+            .into_iter()
+            .enumerate()
+        {
+            let channels = layout.get_channels_count();
+            let stride = w * channels as u32;
+            let src: Vec<u8> = (0..width * height * channels)
+                .map(|i| ((i * 7919 + (i / stride as usize) * 31) % 197 + 30) as u8)
+                .collect();
+            let equalizer = Equalizer::new()
+                .with_color_space(color_space)
+                .with_layout(layout)
+                .with_threshold(threshold)
+                .with_grid_size(grid)
+                .with_bins_count(bins);
+
+            for method in [
+                EqualizationMethod::HistogramEqualization,
+                EqualizationMethod::Ahe,
+                EqualizationMethod::Clahe,
+            ] {
+                let mut expected = vec![0u8; src.len()];
+                match method {
+                    EqualizationMethod::HistogramEqualization => {
+                        functions.hist_equal[index](&src, stride, &mut expected, stride, w, h, bins)
+                    }
+                    EqualizationMethod::Ahe => {
+                        functions.ahe[index](&src, stride, &mut expected, stride, w, h, grid, bins)
+                    }
+                    EqualizationMethod::Clahe => functions.clahe[index](
+                        &src,
+                        stride,
+                        &mut expected,
+                        stride,
+                        w,
+                        h,
+                        threshold,
+                        grid,
+                        bins,
+                    ),
+                }
+                .unwrap();
+
+                let mut dst = vec![0u8; src.len()];
+                equalizer
+                    .with_method(method)
+                    .apply(&src, stride, &mut dst, stride, w, h)
+                    .unwrap();
+                assert_eq!(dst, expected, "{color_space:?} {layout:?} {method:?}");
+            }
+        }
+    }
+}
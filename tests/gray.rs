@@ -0,0 +1,74 @@
+use histogram_equalization::{ahe_gray, clahe_gray, hist_equal_gray, ClaheGridSize};
+
+const WIDTH: usize = 16;
+const HEIGHT: usize = 8;
+
+/// Left half of every row is 60, right half is 180
+fn step() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .map(|i| if i % WIDTH < WIDTH / 2 { 60 } else { 180 })
+        .collect()
+}
+
+fn equalize(src: &[u8], equalize: impl Fn(&[u8], &mut [u8])) -> Vec<u8> {
+    let mut dst = vec![0u8; src.len()];
+    equalize(src, &mut dst);
+    dst
+}
+
+#[test]
+fn constant_image() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let grid = ClaheGridSize::new(2, 2);
+    let src = vec![100u8; WIDTH * HEIGHT];
+
+    // Whole histogram is in one bin, so its cumulative share is 1
+    let he = equalize(&src, |src, dst| {
+        hist_equal_gray(src, w, dst, w, w, h).unwrap()
+    });
+    assert!(he.iter().all(|&v| v == 255));
+    let ahe = equalize(&src, |src, dst| {
+        ahe_gray(src, w, dst, w, w, h, grid).unwrap()
+    });
+    assert!(ahe.iter().all(|&v| v == 255));
+
+    // Tiles of 32 pixels have clip limit 1, 31 clipped samples are given to every 8th bin,
+    // 13 of them below 100, so 100 maps to round(255 * 13 / 31)
+    let clahe = equalize(&src, |src, dst| {
+        clahe_gray(src, w, dst, w, w, h, 2f32, grid).unwrap()
+    });
+    assert!(clahe.iter().all(|&v| v == 107));
+}
+
+#[test]
+fn two_level_step() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let grid = ClaheGridSize::new(2, 2);
+    let src = step();
+
+    // Half of the pixels are at or below 60
+    let he = equalize(&src, |src, dst| {
+        hist_equal_gray(src, w, dst, w, w, h).unwrap()
+    });
+    for row in he.chunks_exact(WIDTH) {
+        assert_eq!(row, [[128u8; 8], [255u8; 8]].concat().as_slice());
+    }
+
+    // Left tiles map 60 to 255, right tiles have no 60 and map it to 0, columns between
+    // the centers of the tiles at 4 and 12 are interpolated, 180 is 255 in both tiles
+    let expected = [
+        255u8, 255, 255, 255, 255, 223, 191, 159, 255, 255, 255, 255, 255, 255, 255, 255,
+    ];
+    let ahe = equalize(&src, |src, dst| {
+        ahe_gray(src, w, dst, w, w, h, grid).unwrap()
+    });
+    for row in ahe.chunks_exact(WIDTH) {
+        assert_eq!(row, expected);
+    }
+
+    // Clip limit above any bin leaves histograms as in AHE
+    let clahe = equalize(&src, |src, dst| {
+        clahe_gray(src, w, dst, w, w, h, 256f32, grid).unwrap()
+    });
+    assert_eq!(clahe, ahe);
+}
@@ -0,0 +1,87 @@
+use histogram_equalization::{channel_histogram, ImageHistogram, ImageRegion};
+
+fn histogram(bins: &[u64]) -> ImageHistogram {
+    ImageHistogram {
+        bins: bins.to_vec(),
+    }
+}
+
+#[test]
+fn single_bin_has_zero_entropy() {
+    let mut single = ImageHistogram::new(256);
+    single.bins[77] = 1000;
+    assert_eq!(single.entropy(), 0f32);
+    assert_eq!(single.min_bin(), Some(77));
+    assert_eq!(single.max_bin(), Some(77));
+    assert_eq!(single.median(), 77);
+    assert_eq!(single.mean(), 77f32);
+}
+
+#[test]
+fn uniform_histogram_has_entropy_of_bits() {
+    let uniform = histogram(&[10u64; 256]);
+    assert!((uniform.entropy() - 8f32).abs() < 1e-5);
+    assert_eq!(uniform.total(), 2560);
+    assert_eq!(*uniform.cdf().last().unwrap(), 2560);
+}
+
+#[test]
+fn two_bin_median() {
+    let mut two_bins = ImageHistogram::new(256);
+    two_bins.bins[10] = 3;
+    two_bins.bins[200] = 5;
+    // 3 of 8 samples are at 10, half of them is reached only at 200
+    assert_eq!(two_bins.median(), 200);
+    two_bins.bins[10] = 5;
+    two_bins.bins[200] = 3;
+    assert_eq!(two_bins.median(), 10);
+    two_bins.bins[10] = 4;
+    two_bins.bins[200] = 4;
+    assert_eq!(two_bins.median(), 10);
+    assert!((two_bins.entropy() - 1f32).abs() < 1e-6);
+}
+
+#[test]
+fn percentile_bounds_are_min_and_max_bins() {
+    let mut sparse = ImageHistogram::new(256);
+    sparse.bins[12] = 7;
+    sparse.bins[90] = 1;
+    sparse.bins[241] = 3;
+    assert_eq!(sparse.percentile(0.0), sparse.min_bin().unwrap());
+    assert_eq!(sparse.percentile(1.0), sparse.max_bin().unwrap());
+    assert_eq!(sparse.percentile(0.0), 12);
+    assert_eq!(sparse.percentile(1.0), 241);
+    // Out of range percentiles are clamped
+    assert_eq!(sparse.percentile(-1.0), 12);
+    assert_eq!(sparse.percentile(2.0), 241);
+}
+
+#[test]
+fn empty_histogram() {
+    let empty = ImageHistogram::new(256);
+    assert_eq!(empty.entropy(), 0f32);
+    assert_eq!(empty.mean(), 0f32);
+    assert_eq!(empty.percentile(0.5), 0);
+    assert_eq!(empty.min_bin(), None);
+    assert_eq!(empty.max_bin(), None);
+}
+
+#[test]
+fn channel_histogram_of_region() {
+    let (width, height) = (8u32, 4u32);
+    let src: Vec<u8> = (0..width * height)
+        .flat_map(|i| [(i % width) as u8, 100, 200])
+        .collect();
+    let red = channel_histogram(&src, width * 3, width, height, 3, 0, None).unwrap();
+    assert_eq!(red.total(), 32);
+    assert_eq!(red.min_bin(), Some(0));
+    assert_eq!(red.max_bin(), Some(7));
+
+    let region = ImageRegion::new(2, 1, 3, 2);
+    let red = channel_histogram(&src, width * 3, width, height, 3, 0, Some(region)).unwrap();
+    assert_eq!(red.total(), 6);
+    assert_eq!(&red.bins[..6], &[0, 0, 2, 2, 2, 0]);
+    let blue = channel_histogram(&src, width * 3, width, height, 3, 2, Some(region)).unwrap();
+    assert_eq!(blue.bins[200], 6);
+    assert_eq!(blue.entropy(), 0f32);
+}
@@ -0,0 +1,244 @@
+use histogram_equalization::{
+    ClaheGridSize, ClaheMode, EqualizationColorSpace, EqualizationMethod, EqualizationRegion,
+    Equalizer, HistogramError, ImageRegion,
+};
+
+const WIDTH: usize = 32;
+const HEIGHT: usize = 20;
+
+fn test_image() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .flat_map(|i| {
+            let v = (i * 7919 + (i / WIDTH) * 31) % 173 + 40;
+            [v as u8, (v / 2) as u8, (255 - v) as u8]
+        })
+        .collect()
+}
+
+fn crop(image: &[u8], region: ImageRegion) -> Vec<u8> {
+    image
+        .chunks_exact(WIDTH * 3)
+        .skip(region.y as usize)
+        .take(region.height as usize)
+        .flat_map(|row| &row[region.x as usize * 3..(region.x + region.width) as usize * 3])
+        .copied()
+        .collect()
+}
+
+/// Rectangle gives the same histograms and windows as the image cropped to it
+#[test]
+fn rect_matches_cropped_image() {
+    let src = test_image();
+    let region = ImageRegion::new(8, 4, 16, 10);
+    let cropped = crop(&src, region);
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+
+    let cases = [
+        (
+            EqualizationMethod::HistogramEqualization,
+            ClaheMode::Native,
+            ClaheGridSize::new(4, 2),
+            ClaheGridSize::new(2, 1),
+        ),
+        (
+            EqualizationMethod::Clahe,
+            ClaheMode::SlidingWindow,
+            ClaheGridSize::new(4, 2),
+            ClaheGridSize::new(2, 1),
+        ),
+    ];
+    for (method, mode, grid_size, cropped_grid_size) in cases {
+        let equalizer = Equalizer::new()
+            .with_color_space(EqualizationColorSpace::Lab)
+            .with_method(method)
+            .with_clahe_mode(mode);
+
+        let mut dst = vec![0u8; src.len()];
+        equalizer
+            .with_grid_size(grid_size)
+            .apply_masked(
+                &src,
+                w * 3,
+                &mut dst,
+                w * 3,
+                w,
+                h,
+                EqualizationRegion::Rect(region),
+            )
+            .unwrap();
+
+        // Grids are chosen to keep sliding windows of the same size
+        let mut expected = vec![0u8; cropped.len()];
+        equalizer
+            .with_grid_size(cropped_grid_size)
+            .apply(
+                &cropped,
+                region.width * 3,
+                &mut expected,
+                region.width * 3,
+                region.width,
+                region.height,
+            )
+            .unwrap();
+
+        assert_eq!(crop(&dst, region), expected);
+        for (y, (dst, src)) in dst
+            .chunks_exact(WIDTH * 3)
+            .zip(src.chunks_exact(WIDTH * 3))
+            .enumerate()
+        {
+            if y < region.y as usize || y >= (region.y + region.height) as usize {
+                assert_eq!(dst, src);
+            } else {
+                let (x0, x1) = (
+                    region.x as usize * 3,
+                    (region.x + region.width) as usize * 3,
+                );
+                assert_eq!(&dst[..x0], &src[..x0]);
+                assert_eq!(&dst[x1..], &src[x1..]);
+            }
+        }
+    }
+}
+
+/// Pixels of the region next to tiles without region pixels are interpolated between LUTs
+/// of region tiles only, as in the run on the image cropped to the region
+#[test]
+fn native_tiles_next_to_unmasked_tiles_match_cropped_image() {
+    let src = test_image();
+    // Left half of the image is made of whole 8x10 tiles of the 4x2 grid
+    let region = ImageRegion::new(0, 0, 16, 20);
+    let cropped = crop(&src, region);
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let mask: Vec<u8> = (0..WIDTH * HEIGHT)
+        .map(|i| if i % WIDTH < 16 { 255 } else { 0 })
+        .collect();
+
+    for method in [EqualizationMethod::Ahe, EqualizationMethod::Clahe] {
+        let equalizer = Equalizer::new()
+            .with_color_space(EqualizationColorSpace::Lab)
+            .with_method(method);
+
+        let mut expected = vec![0u8; cropped.len()];
+        equalizer
+            .with_grid_size(ClaheGridSize::new(2, 2))
+            .apply(
+                &cropped,
+                region.width * 3,
+                &mut expected,
+                region.width * 3,
+                region.width,
+                region.height,
+            )
+            .unwrap();
+
+        for region in [
+            EqualizationRegion::Rect(region),
+            EqualizationRegion::Mask {
+                mask: &mask,
+                stride: w,
+            },
+        ] {
+            let mut dst = vec![0u8; src.len()];
+            equalizer
+                .with_grid_size(ClaheGridSize::new(4, 2))
+                .apply_masked(&src, w * 3, &mut dst, w * 3, w, h, region)
+                .unwrap();
+            assert_eq!(
+                crop(&dst, ImageRegion::new(0, 0, 16, 20)),
+                expected,
+                "{method:?} {region:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn mask_keeps_outside_pixels() {
+    let src = test_image();
+    let mask_stride = WIDTH + 5;
+    let mask: Vec<u8> = (0..mask_stride * HEIGHT)
+        .map(|i| {
+            let (x, y) = ((i % mask_stride) as i32, (i / mask_stride) as i32);
+            if (x - 16) * (x - 16) + (y - 10) * (y - 10) < 64 {
+                255
+            } else {
+                0
+            }
+        })
+        .collect();
+    let region = EqualizationRegion::Mask {
+        mask: &mask,
+        stride: mask_stride as u32,
+    };
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+
+    for (method, feather_radius) in [
+        (EqualizationMethod::Clahe, 0),
+        (EqualizationMethod::Clahe, 3),
+        (EqualizationMethod::HistogramEqualization, 2),
+    ] {
+        let mut dst = vec![0u8; src.len()];
+        Equalizer::new()
+            .with_method(method)
+            .with_feather_radius(feather_radius)
+            .apply_masked(&src, w * 3, &mut dst, w * 3, w, h, region)
+            .unwrap();
+
+        let mut changed = 0usize;
+        for (i, (dst, src)) in dst.chunks_exact(3).zip(src.chunks_exact(3)).enumerate() {
+            if mask[(i / WIDTH) * mask_stride + i % WIDTH] == 0 {
+                assert_eq!(dst, src);
+            } else if dst != src {
+                changed += 1;
+            }
+        }
+        assert!(changed > 0);
+    }
+}
+
+#[test]
+fn invalid_regions_are_rejected() {
+    let src = test_image();
+    let mut dst = vec![0u8; src.len()];
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let equalizer = Equalizer::new();
+    let mask = vec![255u8; WIDTH * HEIGHT];
+
+    let short_stride = EqualizationRegion::Mask {
+        mask: &mask,
+        stride: w - 1,
+    };
+    assert!(matches!(
+        equalizer.apply_masked(&src, w * 3, &mut dst, w * 3, w, h, short_stride),
+        Err(HistogramError::MaskStrideMismatch(_))
+    ));
+
+    let short_mask = EqualizationRegion::Mask {
+        mask: &mask[1..],
+        stride: w,
+    };
+    assert!(matches!(
+        equalizer.apply_masked(&src, w * 3, &mut dst, w * 3, w, h, short_mask),
+        Err(HistogramError::MaskSizeMismatch(_))
+    ));
+
+    let outside = EqualizationRegion::Rect(ImageRegion::new(20, 0, 16, 4));
+    assert!(equalizer
+        .apply_masked(&src, w * 3, &mut dst, w * 3, w, h, outside)
+        .is_err());
+
+    let full = EqualizationRegion::Rect(ImageRegion::new(0, 0, w, h));
+    assert!(matches!(
+        equalizer.with_clahe_mode(ClaheMode::OpenCv).apply_masked(
+            &src,
+            w * 3,
+            &mut dst,
+            w * 3,
+            w,
+            h,
+            full
+        ),
+        Err(HistogramError::UnsupportedMaskMode(ClaheMode::OpenCv))
+    ));
+}
@@ -0,0 +1,63 @@
+#![allow(deprecated)]
+
+use histogram_equalization::{
+    hist_equal_jzazbz_rgb, hist_equal_jzazbz_rgba, hist_equal_oklab_rgb, hist_equal_oklab_rgba,
+    hist_equal_oklch_rgb, hist_equal_oklch_rgba, HistogramError,
+};
+
+const WIDTH: usize = 64;
+const HEIGHT: usize = 8;
+
+type Equalize = fn(&[u8], u32, &mut [u8], u32, u32, u32, usize) -> Result<(), HistogramError>;
+
+/// Gray levels packed into [40, 166) so equalization has to stretch them
+fn gray(x: usize, y: usize) -> u8 {
+    (40 + (x * 2 + y * 3) % 126) as u8
+}
+
+fn assert_neutral(name: &str, dst: &[u8], channels: usize) {
+    for pixel in dst.chunks_exact(channels) {
+        let (r, g, b) = (pixel[0] as i32, pixel[1] as i32, pixel[2] as i32);
+        assert!(
+            (r - g).abs() <= 1 && (g - b).abs() <= 1 && (r - b).abs() <= 1,
+            "{name}: {pixel:?}"
+        );
+    }
+}
+
+#[test]
+fn gray_ramp_stays_neutral() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let rgb: Vec<u8> = (0..WIDTH * HEIGHT)
+        .flat_map(|i| [gray(i % WIDTH, i / WIDTH); 3])
+        .collect();
+    let rgba: Vec<u8> = rgb
+        .chunks_exact(3)
+        .flat_map(|p| [p[0], p[1], p[2], 255])
+        .collect();
+
+    let rgb_functions: [(&str, Equalize); 3] = [
+        ("oklab rgb", hist_equal_oklab_rgb),
+        ("oklch rgb", hist_equal_oklch_rgb),
+        ("jzazbz rgb", hist_equal_jzazbz_rgb),
+    ];
+    for (name, equalize) in rgb_functions {
+        let mut dst = vec![0u8; rgb.len()];
+        equalize(&rgb, w * 3, &mut dst, w * 3, w, h, 256).unwrap();
+        assert_neutral(name, &dst, 3);
+        // Equalization must spread the levels rather than leave them as they are
+        assert!(dst.iter().any(|&v| v > 200), "{name}");
+    }
+
+    let rgba_functions: [(&str, Equalize); 3] = [
+        ("oklab rgba", hist_equal_oklab_rgba),
+        ("oklch rgba", hist_equal_oklch_rgba),
+        ("jzazbz rgba", hist_equal_jzazbz_rgba),
+    ];
+    for (name, equalize) in rgba_functions {
+        let mut dst = vec![0u8; rgba.len()];
+        equalize(&rgba, w * 4, &mut dst, w * 4, w, h, 256).unwrap();
+        assert_neutral(name, &dst, 4);
+        assert!(dst.iter().skip(3).step_by(4).all(|&a| a == 255), "{name}");
+    }
+}
@@ -0,0 +1,135 @@
+//! Parity of `ClaheMode::OpenCv` against `cv::CLAHE::apply` of OpenCV itself.
+//!
+//! Vectors `tests/data/opencv_*.bin` are produced by `tests/data/opencv_reference.py` with
+//! `cv2.createCLAHE` from the same deterministic inputs as below. Tests are ignored until the
+//! vectors are generated and committed, OpenCV isn't a dependency of the build.
+
+use histogram_equalization::{clahe_gray_opencv, clahe_gray_opencv_u16, ClaheGridSize};
+use std::path::Path;
+
+fn make_image(width: usize, height: usize, seed: u32, base: u32, range: u32) -> Vec<u32> {
+    let mut image = Vec::with_capacity(width * height);
+    for y in 0..height as u32 {
+        for x in 0..width as u32 {
+            let h =
+                x.wrapping_mul(73856093) ^ y.wrapping_mul(19349663) ^ seed.wrapping_mul(83492791);
+            image.push(base + h % range);
+        }
+    }
+    image
+}
+
+fn reference(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/data")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {e}, generate it with tests/data/opencv_reference.py",
+            path.display()
+        )
+    })
+}
+
+/// Deterministic input of `make_image` and CLAHE parameters of a reference vector
+struct ParityCase {
+    width: u32,
+    height: u32,
+    seed: u32,
+    base: u32,
+    range: u32,
+    clip_limit: f32,
+    grid_size: ClaheGridSize,
+}
+
+fn check_u8(name: &str, case: ParityCase) {
+    let ParityCase {
+        width,
+        height,
+        seed,
+        base,
+        range,
+        clip_limit,
+        grid_size,
+    } = case;
+    let expected = reference(name);
+    let src: Vec<u8> = make_image(width as usize, height as usize, seed, base, range)
+        .iter()
+        .map(|&x| x as u8)
+        .collect();
+    let mut dst = vec![0u8; src.len()];
+    clahe_gray_opencv(
+        &src, width, &mut dst, width, width, height, clip_limit, grid_size,
+    )
+    .unwrap();
+    for (y, (row, expected_row)) in dst
+        .chunks_exact(width as usize)
+        .zip(expected.chunks_exact(width as usize))
+        .enumerate()
+    {
+        assert_eq!(row, expected_row, "row {}", y);
+    }
+}
+
+#[test]
+#[ignore = "needs tests/data/opencv_u8_37x29_4x3.bin from tests/data/opencv_reference.py"]
+fn opencv_parity_u8_reflected_with_residual() {
+    // 37x29 is padded by reflect-101 to 40x30, clip limit of 2 clips its 10x10 tiles at a
+    // single sample per bin and leaves a residual
+    check_u8(
+        "opencv_u8_37x29_4x3.bin",
+        ParityCase {
+            width: 37,
+            height: 29,
+            seed: 2,
+            base: 0,
+            range: 256,
+            clip_limit: 2f32,
+            grid_size: ClaheGridSize::new(4, 3),
+        },
+    );
+}
+
+#[test]
+#[ignore = "needs tests/data/opencv_u8_64x64_8x8.bin from tests/data/opencv_reference.py"]
+fn opencv_parity_u8_divisible() {
+    check_u8(
+        "opencv_u8_64x64_8x8.bin",
+        ParityCase {
+            width: 64,
+            height: 64,
+            seed: 1,
+            base: 40,
+            range: 120,
+            clip_limit: 40f32,
+            grid_size: ClaheGridSize::new(8, 8),
+        },
+    );
+}
+
+#[test]
+#[ignore = "needs tests/data/opencv_u16_29x23_3x2.bin from tests/data/opencv_reference.py"]
+fn opencv_parity_u16_reflected() {
+    let (width, height) = (29u32, 23u32);
+    let expected: Vec<u16> = reference("opencv_u16_29x23_3x2.bin")
+        .chunks_exact(2)
+        .map(|x| u16::from_le_bytes([x[0], x[1]]))
+        .collect();
+    let src: Vec<u16> = make_image(width as usize, height as usize, 5, 1000, 3000)
+        .iter()
+        .map(|&x| x as u16)
+        .collect();
+    let mut dst = vec![0u16; src.len()];
+    clahe_gray_opencv_u16(
+        &src,
+        width,
+        &mut dst,
+        width,
+        width,
+        height,
+        4f32,
+        ClaheGridSize::new(3, 2),
+    )
+    .unwrap();
+    assert_eq!(dst, expected);
+}
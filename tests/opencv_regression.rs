@@ -0,0 +1,152 @@
+//! Regression vectors of `ClaheMode::OpenCv`.
+//!
+//! Vectors `tests/data/clahe_*.bin` are produced by `tests/data/clahe_opencv_port.py`, a Python
+//! port of `cv::CLAHE::apply`, from the same deterministic inputs as below. They pin current
+//! output and aren't produced by OpenCV itself, parity with OpenCV is checked by
+//! `tests/opencv_parity.rs`.
+
+use histogram_equalization::{clahe_gray_opencv, clahe_gray_opencv_u16, ClaheGridSize};
+
+fn make_image(width: usize, height: usize, seed: u32, base: u32, range: u32) -> Vec<u32> {
+    let mut image = Vec::with_capacity(width * height);
+    for y in 0..height as u32 {
+        for x in 0..width as u32 {
+            let h =
+                x.wrapping_mul(73856093) ^ y.wrapping_mul(19349663) ^ seed.wrapping_mul(83492791);
+            image.push(base + h % range);
+        }
+    }
+    image
+}
+
+/// Deterministic input of `make_image` and CLAHE parameters of a regression vector
+struct RegressionCase {
+    width: u32,
+    height: u32,
+    seed: u32,
+    base: u32,
+    range: u32,
+    clip_limit: f32,
+    grid_size: ClaheGridSize,
+}
+
+fn check_u8(expected: &[u8], case: RegressionCase) {
+    let RegressionCase {
+        width,
+        height,
+        seed,
+        base,
+        range,
+        clip_limit,
+        grid_size,
+    } = case;
+    let src: Vec<u8> = make_image(width as usize, height as usize, seed, base, range)
+        .iter()
+        .map(|&x| x as u8)
+        .collect();
+    // Padded stride to make sure row padding is not touched
+    let dst_stride = width + 3;
+    let mut dst = vec![0u8; dst_stride as usize * height as usize];
+    clahe_gray_opencv(
+        &src, width, &mut dst, dst_stride, width, height, clip_limit, grid_size,
+    )
+    .unwrap();
+    for (y, (dst_row, expected_row)) in dst
+        .chunks_exact(dst_stride as usize)
+        .zip(expected.chunks_exact(width as usize))
+        .enumerate()
+    {
+        assert_eq!(&dst_row[..width as usize], expected_row, "row {}", y);
+        assert!(dst_row[width as usize..].iter().all(|&x| x == 0));
+    }
+}
+
+#[test]
+fn opencv_regression_u8_divisible() {
+    check_u8(
+        include_bytes!("data/clahe_u8_64x64_8x8.bin"),
+        RegressionCase {
+            width: 64,
+            height: 64,
+            seed: 1,
+            base: 40,
+            range: 120,
+            clip_limit: 40f32,
+            grid_size: ClaheGridSize::new(8, 8),
+        },
+    );
+}
+
+#[test]
+fn opencv_regression_u8_padded() {
+    check_u8(
+        include_bytes!("data/clahe_u8_37x29_4x3.bin"),
+        RegressionCase {
+            width: 37,
+            height: 29,
+            seed: 2,
+            base: 0,
+            range: 256,
+            clip_limit: 2f32,
+            grid_size: ClaheGridSize::new(4, 3),
+        },
+    );
+}
+
+#[test]
+fn opencv_regression_u8_padded_one_dimension() {
+    check_u8(
+        include_bytes!("data/clahe_u8_48x30_4x4.bin"),
+        RegressionCase {
+            width: 48,
+            height: 30,
+            seed: 3,
+            base: 90,
+            range: 60,
+            clip_limit: 3f32,
+            grid_size: ClaheGridSize::new(4, 4),
+        },
+    );
+}
+
+#[test]
+fn opencv_regression_u8_no_clip() {
+    check_u8(
+        include_bytes!("data/clahe_u8_20x20_2x2_no_clip.bin"),
+        RegressionCase {
+            width: 20,
+            height: 20,
+            seed: 4,
+            base: 10,
+            range: 200,
+            clip_limit: 0f32,
+            grid_size: ClaheGridSize::new(2, 2),
+        },
+    );
+}
+
+#[test]
+fn opencv_regression_u16() {
+    let (width, height) = (24u32, 16u32);
+    let expected: Vec<u16> = include_bytes!("data/clahe_u16_24x16_3x2.bin")
+        .chunks_exact(2)
+        .map(|x| u16::from_le_bytes([x[0], x[1]]))
+        .collect();
+    let src: Vec<u16> = make_image(width as usize, height as usize, 5, 1000, 3000)
+        .iter()
+        .map(|&x| x as u16)
+        .collect();
+    let mut dst = vec![0u16; width as usize * height as usize];
+    clahe_gray_opencv_u16(
+        &src,
+        width,
+        &mut dst,
+        width,
+        width,
+        height,
+        4f32,
+        ClaheGridSize::new(3, 2),
+    )
+    .unwrap();
+    assert_eq!(dst, expected);
+}
@@ -0,0 +1,225 @@
+use histogram_equalization::{
+    ahe_gray_sliding, ahe_gray_sliding_u16, clahe_gray_sliding, clahe_gray_sliding_u16,
+    ClaheGridSize, ClaheMode, EqualizationColorSpace, EqualizationMethod, Equalizer,
+};
+
+const WIDTH: usize = 23;
+const HEIGHT: usize = 17;
+
+fn test_image() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .map(|i| ((i * 7919 + (i / WIDTH) * 31) % 251) as u8)
+        .collect()
+}
+
+/// Clips `histogram` and spreads the excess as iterative redistribution of the crate does
+fn clip_iterative(histogram: &mut [u64], clip_limit: u64) {
+    let mut excess = 0u64;
+    for bin in histogram.iter_mut() {
+        if *bin > clip_limit {
+            excess += *bin - clip_limit;
+            *bin = clip_limit;
+        }
+    }
+    while excess > 0 {
+        let free_bins = histogram.iter().filter(|&&x| x < clip_limit).count() as u64;
+        if free_bins == 0 {
+            break;
+        }
+        let batch = excess / free_bins;
+        if batch == 0 {
+            let step = (free_bins / excess).max(1) as usize;
+            for bin in histogram
+                .iter_mut()
+                .filter(|x| **x < clip_limit)
+                .step_by(step)
+                .take(excess as usize)
+            {
+                *bin += 1;
+            }
+            return;
+        }
+        for bin in histogram.iter_mut().filter(|x| **x < clip_limit) {
+            let added = batch.min(clip_limit - *bin);
+            *bin += added;
+            excess -= added;
+        }
+    }
+    if excess > 0 {
+        let n = histogram.len() as u64;
+        let residual = (excess % n) as usize;
+        let step = (histogram.len() / residual.max(1)).max(1);
+        for bin in histogram.iter_mut() {
+            *bin += excess / n;
+        }
+        for bin in histogram.iter_mut().step_by(step).take(residual) {
+            *bin += 1;
+        }
+    }
+}
+
+/// Maps every pixel by a histogram of `bins` bins of its window built from scratch, clipped
+/// at `threshold` when it is set
+fn brute_force_bins(
+    src: &[u16],
+    bins: usize,
+    radius_x: usize,
+    radius_y: usize,
+    threshold: Option<f32>,
+) -> Vec<u16> {
+    let max_bin = (bins - 1) as f64;
+    let mut dst = vec![0u16; src.len()];
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let mut histogram = vec![0u64; bins];
+            let (left, right) = (x.saturating_sub(radius_x), (x + radius_x).min(WIDTH - 1));
+            let (top, bottom) = (y.saturating_sub(radius_y), (y + radius_y).min(HEIGHT - 1));
+            for yy in top..=bottom {
+                for xx in left..=right {
+                    histogram[src[yy * WIDTH + xx] as usize] += 1;
+                }
+            }
+            let area = ((right - left + 1) * (bottom - top + 1)) as f64;
+            if let Some(threshold) = threshold {
+                let clip_limit = ((threshold as f64 * area / bins as f64) as u64).max(1);
+                clip_iterative(&mut histogram, clip_limit);
+            }
+            let value = src[y * WIDTH + x] as usize;
+            let cdf_value = histogram[..=value].iter().sum::<u64>() as f64;
+            let cdf_first = histogram[0] as f64;
+            dst[y * WIDTH + x] = if area > cdf_first {
+                (max_bin * (cdf_value - cdf_first) / (area - cdf_first))
+                    .round()
+                    .clamp(0f64, max_bin) as u16
+            } else {
+                0
+            };
+        }
+    }
+    dst
+}
+
+/// Maps every pixel by a histogram of its window built from scratch, clipped at `threshold`
+/// when it is set
+fn brute_force(src: &[u8], radius_x: usize, radius_y: usize, threshold: Option<f32>) -> Vec<u8> {
+    let src: Vec<u16> = src.iter().map(|&v| v as u16).collect();
+    brute_force_bins(&src, 256, radius_x, radius_y, threshold)
+        .iter()
+        .map(|&v| v as u8)
+        .collect()
+}
+
+#[test]
+fn ahe_matches_brute_force() {
+    let src = test_image();
+    let dst_stride = WIDTH + 3;
+    let mut dst = vec![0u8; dst_stride * HEIGHT];
+    ahe_gray_sliding(
+        &src,
+        WIDTH as u32,
+        &mut dst,
+        dst_stride as u32,
+        WIDTH as u32,
+        HEIGHT as u32,
+        ClaheGridSize::new(4, 3),
+    )
+    .unwrap();
+
+    // Tiles are 5x5, so the window radius is 2 on both axes
+    let expected = brute_force(&src, 2, 2, None);
+    for (dst, expected) in dst.chunks(dst_stride).zip(expected.chunks(WIDTH)) {
+        assert_eq!(&dst[..WIDTH], expected);
+    }
+}
+
+#[test]
+fn clahe_matches_brute_force() {
+    let src = test_image();
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    // Low limits give back the excess to all bins, higher ones leave a residual to spread
+    for threshold in [0.5f32, 1.5, 3., 8.] {
+        let mut dst = vec![0u8; src.len()];
+        clahe_gray_sliding(
+            &src,
+            w,
+            &mut dst,
+            w,
+            w,
+            h,
+            threshold,
+            ClaheGridSize::new(2, 2),
+        )
+        .unwrap();
+        // Tiles are 11x8, so the window radius is 5 by 4
+        assert_eq!(
+            dst,
+            brute_force(&src, 5, 4, Some(threshold)),
+            "threshold {threshold}"
+        );
+    }
+}
+
+#[test]
+fn clahe_without_clipping_matches_ahe() {
+    let src = test_image();
+    let grid = ClaheGridSize::new(3, 2);
+
+    let mut ahe = vec![0u8; src.len()];
+    let mut clahe = vec![0u8; src.len()];
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    ahe_gray_sliding(&src, w, &mut ahe, w, w, h, grid).unwrap();
+    clahe_gray_sliding(&src, w, &mut clahe, w, w, h, 1e6, grid).unwrap();
+    assert_eq!(ahe, clahe);
+
+    let src16: Vec<u16> = src.iter().map(|&v| (v as u16) << 4).collect();
+    let mut ahe16 = vec![0u16; src.len()];
+    let mut clahe16 = vec![0u16; src.len()];
+    ahe_gray_sliding_u16(&src16, w, &mut ahe16, w, w, h, 12, grid).unwrap();
+    clahe_gray_sliding_u16(&src16, w, &mut clahe16, w, w, h, 12, 1e6, grid).unwrap();
+    assert_eq!(ahe16, clahe16);
+
+    let rgb: Vec<u8> = src.iter().flat_map(|&v| [v, v / 2, 255 - v]).collect();
+    let mut dst_rgb = vec![0u8; rgb.len()];
+    for color_space in [EqualizationColorSpace::Hsv, EqualizationColorSpace::Yuv] {
+        Equalizer::new()
+            .with_color_space(color_space)
+            .with_method(EqualizationMethod::Clahe)
+            .with_clahe_mode(ClaheMode::SlidingWindow)
+            .apply(&rgb, w * 3, &mut dst_rgb, w * 3, w, h)
+            .unwrap();
+    }
+}
+
+#[test]
+fn clahe_u16_noise_matches_brute_force() {
+    // Neighbour values are far apart, so ranks are summed from blocks of bins, and repeat
+    // often enough to be clipped
+    let src: Vec<u16> = (0..WIDTH * HEIGHT)
+        .map(|i| (((i as u32).wrapping_mul(2654435761) >> 26) << 6) as u16)
+        .collect();
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let mut dst = vec![0u16; src.len()];
+    ahe_gray_sliding_u16(&src, w, &mut dst, w, w, h, 12, ClaheGridSize::new(2, 2)).unwrap();
+    assert_eq!(dst, brute_force_bins(&src, 4096, 5, 4, None));
+
+    // Limits stay at a few samples per bin, so the excess is replayed on clipped levels
+    for threshold in [40f32, 100., 200.] {
+        clahe_gray_sliding_u16(
+            &src,
+            w,
+            &mut dst,
+            w,
+            w,
+            h,
+            12,
+            threshold,
+            ClaheGridSize::new(2, 2),
+        )
+        .unwrap();
+        assert_eq!(
+            dst,
+            brute_force_bins(&src, 4096, 5, 4, Some(threshold)),
+            "threshold {threshold}"
+        );
+    }
+}
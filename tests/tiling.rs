@@ -0,0 +1,53 @@
+use histogram_equalization::{
+    clahe_gray, clahe_gray_u16, ClaheGridSize, EqualizationColorSpace, Equalizer, HistogramError,
+};
+
+#[test]
+fn grid_larger_than_image() {
+    let (width, height) = (3u32, 2u32);
+    let src = [10u8, 200, 30, 40, 50, 60];
+    let mut dst = [0u8; 6];
+    clahe_gray(
+        &src,
+        width,
+        &mut dst,
+        width,
+        width,
+        height,
+        2f32,
+        ClaheGridSize::new(8, 8),
+    )
+    .unwrap();
+
+    let src16 = [1000u16, 20, 3000, 4000, 5, 600];
+    let mut dst16 = [0u16; 6];
+    clahe_gray_u16(
+        &src16,
+        width,
+        &mut dst16,
+        width,
+        width,
+        height,
+        12,
+        2f32,
+        ClaheGridSize::new(16, 16),
+    )
+    .unwrap();
+
+    let rgb: Vec<u8> = src.iter().flat_map(|&v| [v, v / 2, 255 - v]).collect();
+    let mut dst_rgb = vec![0u8; rgb.len()];
+    for color_space in [EqualizationColorSpace::Hsv, EqualizationColorSpace::Yuv] {
+        Equalizer::new()
+            .with_color_space(color_space)
+            .apply(&rgb, width * 3, &mut dst_rgb, width * 3, width, height)
+            .unwrap();
+    }
+}
+
+#[test]
+fn zero_grid_is_rejected() {
+    let src = [0u8; 16];
+    let mut dst = [0u8; 16];
+    let result = clahe_gray(&src, 4, &mut dst, 4, 4, 4, 2f32, ClaheGridSize::new(0, 2));
+    assert!(matches!(result, Err(HistogramError::InvalidGridSize(_))));
+}
@@ -0,0 +1,123 @@
+use colorutils_rs::{Jzazbz, Lab, Oklab, Rgb, Xyz, SRGB_TO_XYZ_D65, XYZ_TO_SRGB_D65};
+use histogram_equalization::{
+    ahe_jzazbz_rgb_f32, ahe_lab_rgb_f32, ahe_oklab_rgb_f32, ClaheGridSize, TransferFunction,
+};
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 4;
+
+/// Gray ramp with lightness spread uniformly over every bin, so its histogram is already
+/// equalized and a single tile maps every bin onto itself
+fn ramp(lightness: impl Fn(f32) -> Rgb<f32>) -> Vec<f32> {
+    (0..WIDTH * HEIGHT)
+        .flat_map(|i| {
+            let rgb = lightness((i % WIDTH) as f32 / (WIDTH - 1) as f32);
+            [rgb.r, rgb.g, rgb.b]
+        })
+        .collect()
+}
+
+fn max_error(src: &[f32], dst: &[f32]) -> f32 {
+    src.iter()
+        .zip(dst)
+        .map(|(&a, &b)| (a - b).abs())
+        .fold(0f32, f32::max)
+}
+
+#[test]
+fn pq_ramp_round_trip() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    // Lightness of the brightest PQ value, 10000 nits
+    let peak = Jzazbz::from_linear_rgb_with_luminance(
+        Rgb::<f32>::new(1., 1., 1.).linearize(TransferFunction::Pq),
+        200.,
+    )
+    .jz;
+    let src = ramp(|l| {
+        Jzazbz::new(l * peak, 0., 0.)
+            .to_linear_rgb()
+            .gamma(TransferFunction::Pq)
+    });
+    let mut dst = vec![0f32; src.len()];
+    ahe_jzazbz_rgb_f32(
+        &src,
+        w * 3,
+        &mut dst,
+        w * 3,
+        w,
+        h,
+        ClaheGridSize::new(1, 1),
+        256,
+        TransferFunction::Pq,
+    )
+    .unwrap();
+    let error = max_error(&src, &dst);
+    assert!(error < 1e-3, "{error}");
+}
+
+#[test]
+fn linear_ramp_round_trip() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let src = ramp(|l| Oklab::new(l, 0., 0.).to_linear_rgb());
+    let mut dst = vec![0f32; src.len()];
+    ahe_oklab_rgb_f32(
+        &src,
+        w * 3,
+        &mut dst,
+        w * 3,
+        w,
+        h,
+        ClaheGridSize::new(1, 1),
+        256,
+        TransferFunction::Linear,
+    )
+    .unwrap();
+    let error = max_error(&src, &dst);
+    assert!(error < 1e-3, "{error}");
+}
+
+/// Lightness above reference white is equalized up to the peak of the image rather than
+/// clipped into the last bin
+#[test]
+fn hdr_ramp_round_trip() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let white = Rgb::<f32>::new(4.01, 4.01, 4.01);
+
+    let peak = Oklab::from_linear_rgb(white).l;
+    let src = ramp(|l| Oklab::new(l * peak, 0., 0.).to_linear_rgb());
+    let mut dst = vec![0f32; src.len()];
+    ahe_oklab_rgb_f32(
+        &src,
+        w * 3,
+        &mut dst,
+        w * 3,
+        w,
+        h,
+        ClaheGridSize::new(1, 1),
+        256,
+        TransferFunction::Linear,
+    )
+    .unwrap();
+    let error = max_error(&src, &dst);
+    assert!(error < 1e-2, "{error}");
+    let brightest = dst.iter().fold(0f32, |a, &b| a.max(b));
+    assert!((brightest - 4.01).abs() < 1e-2, "{brightest}");
+
+    let peak = Lab::from_xyz(Xyz::from_linear_rgb(white, &SRGB_TO_XYZ_D65)).l;
+    let src = ramp(|l| Lab::new(l * peak, 0., 0.).to_linear_rgb(&XYZ_TO_SRGB_D65));
+    let mut dst = vec![0f32; src.len()];
+    ahe_lab_rgb_f32(
+        &src,
+        w * 3,
+        &mut dst,
+        w * 3,
+        w,
+        h,
+        ClaheGridSize::new(1, 1),
+        256,
+        TransferFunction::Linear,
+    )
+    .unwrap();
+    let error = max_error(&src, &dst);
+    assert!(error < 1e-2, "{error}");
+}
@@ -0,0 +1,146 @@
+#![allow(deprecated)]
+
+use histogram_equalization::{
+    clahe_gray_u16, clahe_lab_rgb, hist_equal_hsv_rgb, ClaheGridSize, HistogramError,
+    MismatchedSize,
+};
+
+const WIDTH: u32 = 20;
+const HEIGHT: u32 = 10;
+const STRIDE: u32 = WIDTH * 3;
+
+fn clahe(
+    src: &[u8],
+    src_stride: u32,
+    dst: &mut [u8],
+    dst_stride: u32,
+    grid_size: ClaheGridSize,
+    bins_count: usize,
+) -> Result<(), HistogramError> {
+    clahe_lab_rgb(
+        src, src_stride, dst, dst_stride, WIDTH, HEIGHT, 2f32, grid_size, bins_count,
+    )
+}
+
+#[test]
+fn short_stride() {
+    let src = vec![0u8; (STRIDE * HEIGHT) as usize];
+    let mut dst = vec![0u8; src.len()];
+    let grid = ClaheGridSize::new(2, 2);
+    assert!(matches!(
+        clahe(&src, STRIDE - 1, &mut dst, STRIDE, grid, 128),
+        Err(HistogramError::SourceStrideMismatch(MismatchedSize {
+            expected: 60,
+            received: 59
+        }))
+    ));
+    assert!(matches!(
+        clahe(&src, STRIDE, &mut dst, STRIDE - 1, grid, 128),
+        Err(HistogramError::DestinationStrideMismatch(MismatchedSize {
+            expected: 60,
+            received: 59
+        }))
+    ));
+}
+
+#[test]
+fn short_buffer() {
+    let src = vec![0u8; (STRIDE * HEIGHT) as usize];
+    let mut dst = vec![0u8; src.len()];
+    let grid = ClaheGridSize::new(2, 2);
+    assert!(matches!(
+        clahe(&src[1..], STRIDE, &mut dst, STRIDE, grid, 128),
+        Err(HistogramError::SourceSizeMismatch(MismatchedSize {
+            expected: 600,
+            received: 599
+        }))
+    ));
+    assert!(matches!(
+        clahe(&src, STRIDE, &mut dst[1..], STRIDE, grid, 128),
+        Err(HistogramError::DestinationSizeMismatch(MismatchedSize {
+            expected: 600,
+            received: 599
+        }))
+    ));
+    // Padded stride requires the padding of every row
+    assert!(matches!(
+        hist_equal_hsv_rgb(&src, STRIDE + 4, &mut dst, STRIDE, WIDTH, HEIGHT, 128),
+        Err(HistogramError::SourceSizeMismatch(MismatchedSize {
+            expected: 640,
+            received: 600
+        }))
+    ));
+}
+
+#[test]
+fn bins_out_of_range() {
+    let src = vec![0u8; (STRIDE * HEIGHT) as usize];
+    let mut dst = vec![0u8; src.len()];
+    let grid = ClaheGridSize::new(2, 2);
+    for bins_count in [0, 1, 65537] {
+        assert!(matches!(
+            clahe(&src, STRIDE, &mut dst, STRIDE, grid, bins_count),
+            Err(HistogramError::InvalidBinsCount(bins)) if bins == bins_count
+        ));
+        assert!(matches!(
+            hist_equal_hsv_rgb(&src, STRIDE, &mut dst, STRIDE, WIDTH, HEIGHT, bins_count),
+            Err(HistogramError::InvalidBinsCount(bins)) if bins == bins_count
+        ));
+    }
+    assert!(clahe(&src, STRIDE, &mut dst, STRIDE, grid, 2).is_ok());
+    assert!(clahe(&src, STRIDE, &mut dst, STRIDE, grid, 65536).is_ok());
+}
+
+#[test]
+fn bad_bit_depth() {
+    let src = vec![0u16; (WIDTH * HEIGHT) as usize];
+    let mut dst = vec![0u16; src.len()];
+    let grid = ClaheGridSize::new(2, 2);
+    for bit_depth in [0, 17] {
+        assert!(matches!(
+            clahe_gray_u16(&src, WIDTH, &mut dst, WIDTH, WIDTH, HEIGHT, bit_depth, 2f32, grid),
+            Err(HistogramError::UnsupportedBitDepth(depth)) if depth == bit_depth
+        ));
+    }
+    for bit_depth in [1, 16] {
+        assert!(
+            clahe_gray_u16(&src, WIDTH, &mut dst, WIDTH, WIDTH, HEIGHT, bit_depth, 2f32, grid)
+                .is_ok()
+        );
+    }
+}
+
+#[test]
+fn zero_grid() {
+    let src = vec![0u8; (STRIDE * HEIGHT) as usize];
+    let mut dst = vec![0u8; src.len()];
+    for grid in [ClaheGridSize::new(0, 2), ClaheGridSize::new(2, 0)] {
+        assert!(matches!(
+            clahe(&src, STRIDE, &mut dst, STRIDE, grid, 128),
+            Err(HistogramError::InvalidGridSize(size)) if size == grid
+        ));
+    }
+}
+
+#[test]
+fn zero_size() {
+    let mut dst = [0u8; 3];
+    assert!(matches!(
+        clahe_lab_rgb(
+            &[],
+            0,
+            &mut dst,
+            3,
+            0,
+            1,
+            2f32,
+            ClaheGridSize::new(2, 2),
+            128
+        ),
+        Err(HistogramError::ZeroBaseSize)
+    ));
+    assert!(matches!(
+        hist_equal_hsv_rgb(&[], 3, &mut dst, 3, 1, 0, 128),
+        Err(HistogramError::ZeroBaseSize)
+    ));
+}
@@ -0,0 +1,148 @@
+use histogram_equalization::{
+    ClaheMode, EqualizationColorSpace, EqualizationMethod, Equalizer, HistogramError,
+    VideoEqualizer, YuvChromaSubsampling, YuvPlanarImageMut,
+};
+
+const WIDTH: usize = 48;
+const HEIGHT: usize = 32;
+
+fn frame(brightness: usize) -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .flat_map(|i| {
+            let (x, y) = (i % WIDTH, i / WIDTH);
+            let v = ((x * 3 + y * 2 + (i * 7919) % 41) * brightness / 100).min(255);
+            [v as u8, (v * 3 / 4) as u8, (v / 2) as u8]
+        })
+        .collect()
+}
+
+fn equalize(equalizer: Equalizer, src: &[u8]) -> Vec<u8> {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let mut dst = vec![0u8; src.len()];
+    equalizer.apply(src, w * 3, &mut dst, w * 3, w, h).unwrap();
+    dst
+}
+
+fn equalize_frame(video: &mut VideoEqualizer, src: &[u8]) -> Vec<u8> {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let mut dst = vec![0u8; src.len()];
+    video.apply(src, w * 3, &mut dst, w * 3, w, h).unwrap();
+    dst
+}
+
+/// Repeated frame is equalized as by [Equalizer], a changed one approaches its own
+/// equalization over the following frames
+#[test]
+fn luts_are_smoothed_across_frames() {
+    for color_space in [EqualizationColorSpace::Yuv, EqualizationColorSpace::Oklab] {
+        let equalizer = Equalizer::new()
+            .with_color_space(color_space)
+            .with_method(EqualizationMethod::Clahe);
+        let mut video = VideoEqualizer::new(equalizer)
+            .with_smoothing(0.7f32)
+            .with_scene_cut_threshold(1f32);
+        let (first, second) = (frame(100), frame(70));
+        let (expected_first, expected_second) =
+            (equalize(equalizer, &first), equalize(equalizer, &second));
+
+        for _ in 0..3 {
+            assert_eq!(equalize_frame(&mut video, &first), expected_first);
+        }
+
+        let mut distances = Vec::new();
+        for _ in 0..40 {
+            let dst = equalize_frame(&mut video, &second);
+            assert!(!video.is_scene_cut());
+            distances.push(
+                dst.iter()
+                    .zip(expected_second.iter())
+                    .map(|(&a, &b)| a.abs_diff(b) as u64)
+                    .sum::<u64>(),
+            );
+        }
+        assert!(distances[0] > 0, "{color_space:?}");
+        assert!(
+            distances.windows(2).all(|w| w[1] <= w[0]),
+            "{color_space:?}"
+        );
+        assert!(
+            *distances.last().unwrap() <= distances[0] / 10,
+            "{color_space:?}"
+        );
+
+        // Without smoothing every frame is equalized on its own
+        let mut video = video.with_smoothing(0f32);
+        assert_eq!(equalize_frame(&mut video, &first), expected_first);
+    }
+}
+
+#[test]
+fn scene_cut_resets_history() {
+    let equalizer = Equalizer::new().with_method(EqualizationMethod::HistogramEqualization);
+    let mut video = VideoEqualizer::new(equalizer);
+    let dark = frame(20);
+    let expected_dark = equalize_frame(&mut VideoEqualizer::new(equalizer), &dark);
+
+    equalize_frame(&mut video, &frame(100));
+    equalize_frame(&mut video, &frame(95));
+    assert!(!video.is_scene_cut());
+
+    assert_eq!(equalize_frame(&mut video, &dark), expected_dark);
+    assert!(video.is_scene_cut());
+
+    equalize_frame(&mut video, &frame(100));
+    video.reset();
+    assert_eq!(equalize_frame(&mut video, &dark), expected_dark);
+    assert!(!video.is_scene_cut());
+}
+
+#[test]
+fn planar_luma_is_smoothed() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let luma =
+        |brightness: usize| -> Vec<u8> { frame(brightness).iter().step_by(3).copied().collect() };
+    let equalizer = Equalizer::new().with_method(EqualizationMethod::Ahe);
+    let mut video = VideoEqualizer::new(equalizer).with_scene_cut_threshold(1f32);
+
+    let equalize_planar = |video: Option<&mut VideoEqualizer>, luma: &[u8]| {
+        let mut image = YuvPlanarImageMut::<u8>::alloc(w, h, YuvChromaSubsampling::Yuv420);
+        image.y_plane.borrow_mut().copy_from_slice(luma);
+        match video {
+            Some(video) => video.apply_yuv_planar(&mut image, YuvChromaSubsampling::Yuv420),
+            None => equalizer.apply_yuv_planar(&mut image, YuvChromaSubsampling::Yuv420),
+        }
+        .unwrap();
+        image.y_plane.borrow().to_vec()
+    };
+
+    let (first, second) = (luma(100), luma(60));
+    assert_eq!(
+        equalize_planar(Some(&mut video), &first),
+        equalize_planar(None, &first)
+    );
+    assert_ne!(
+        equalize_planar(Some(&mut video), &second),
+        equalize_planar(None, &second)
+    );
+}
+
+#[test]
+fn unsupported_configurations_are_rejected() {
+    let src = frame(100);
+    let mut dst = vec![0u8; src.len()];
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+
+    for mode in [ClaheMode::OpenCv, ClaheMode::SlidingWindow] {
+        let mut video = VideoEqualizer::new(Equalizer::new().with_clahe_mode(mode));
+        assert!(matches!(
+            video.apply(&src, w * 3, &mut dst, w * 3, w, h),
+            Err(HistogramError::UnsupportedVideoMode(m)) if m == mode
+        ));
+    }
+
+    let mut video = VideoEqualizer::default().with_smoothing(1f32);
+    assert!(matches!(
+        video.apply(&src, w * 3, &mut dst, w * 3, w, h),
+        Err(HistogramError::InvalidSmoothing(_))
+    ));
+}
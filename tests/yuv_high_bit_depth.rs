@@ -0,0 +1,307 @@
+#![allow(deprecated)]
+
+use histogram_equalization::{
+    ahe_yuv_bgra_u16, ahe_yuv_rgba_u16, clahe_gray_u16, clahe_yuv_bgr_u16, clahe_yuv_planar_u16,
+    clahe_yuv_rgb_u16, clahe_yuv_rgba_u16, hist_equal_gray_u16, hist_equal_yuv_bgra_u16,
+    hist_equal_yuv_biplanar_u16, hist_equal_yuv_planar_u16, hist_equal_yuv_rgb_u16,
+    hist_equal_yuv_rgba_u16, ClaheGridSize, HistogramError, YuvBiPlanarImageMut,
+    YuvChromaSubsampling, YuvMatrix, YuvPlanarImageMut, YuvRange,
+};
+
+const WIDTH: usize = 33;
+const HEIGHT: usize = 19;
+
+fn plane(bit_depth: u32, seed: usize) -> Vec<u16> {
+    let levels = 1usize << bit_depth;
+    (0..WIDTH * HEIGHT)
+        .map(|i| ((i * 7919 + (i / WIDTH) * 31 + seed) % (levels * 3 / 4) + levels / 8) as u16)
+        .collect()
+}
+
+#[test]
+fn planar_luma_matches_gray() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let grid = ClaheGridSize::new(3, 2);
+    for bit_depth in [10, 12] {
+        let source = plane(bit_depth, bit_depth as usize);
+
+        let mut expected = vec![0u16; source.len()];
+        clahe_gray_u16(&source, w, &mut expected, w, w, h, bit_depth, 2f32, grid).unwrap();
+        let mut image = YuvPlanarImageMut::<u16>::alloc(w, h, YuvChromaSubsampling::Yuv420);
+        image.y_plane.borrow_mut().copy_from_slice(&source);
+        let chroma = plane(bit_depth, 7)[..image.u_plane.borrow().len()].to_vec();
+        image.u_plane.borrow_mut().copy_from_slice(&chroma);
+        clahe_yuv_planar_u16(
+            &mut image,
+            YuvChromaSubsampling::Yuv420,
+            YuvRange::Full,
+            bit_depth,
+            2f32,
+            grid,
+        )
+        .unwrap();
+        assert_eq!(image.y_plane.borrow(), expected.as_slice());
+        assert_eq!(image.u_plane.borrow(), chroma.as_slice());
+
+        let mut expected = vec![0u16; source.len()];
+        hist_equal_gray_u16(&source, w, &mut expected, w, w, h, bit_depth).unwrap();
+        let mut image = YuvBiPlanarImageMut::<u16>::alloc(w, h, YuvChromaSubsampling::Yuv420);
+        image.y_plane.borrow_mut().copy_from_slice(&source);
+        hist_equal_yuv_biplanar_u16(
+            &mut image,
+            YuvChromaSubsampling::Yuv420,
+            YuvRange::Full,
+            bit_depth,
+        )
+        .unwrap();
+        assert_eq!(image.y_plane.borrow(), expected.as_slice());
+    }
+}
+
+#[test]
+fn limited_range_stays_within_ten_bit_levels() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let mut image = YuvPlanarImageMut::<u16>::alloc(w, h, YuvChromaSubsampling::Yuv444);
+    image.y_plane.borrow_mut().copy_from_slice(&plane(10, 3));
+    hist_equal_yuv_planar_u16(
+        &mut image,
+        YuvChromaSubsampling::Yuv444,
+        YuvRange::Limited,
+        10,
+    )
+    .unwrap();
+    let luma = image.y_plane.borrow();
+    assert!(luma.iter().all(|v| (64..=940).contains(v)));
+    assert_eq!(*luma.iter().max().unwrap(), 940);
+}
+
+/// Gray stays gray and is stretched over all levels of the bit depth
+#[test]
+fn rgb_matrices_and_bit_depths() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    for (matrix, bit_depth) in [
+        (YuvMatrix::Bt709, 10),
+        (YuvMatrix::Bt2020, 12),
+        (YuvMatrix::Bt601, 14),
+        (YuvMatrix::Bt709, 16),
+        (YuvMatrix::YCgCo, 10),
+        (YuvMatrix::YCgCo, 12),
+    ] {
+        let gray: Vec<u16> = plane(bit_depth, 1)
+            .iter()
+            .flat_map(|&v| [v, v, v])
+            .collect();
+        let mut dst = vec![0u16; gray.len()];
+        hist_equal_yuv_rgb_u16(
+            &gray,
+            w * 3,
+            &mut dst,
+            w * 3,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            YuvRange::Full,
+        )
+        .unwrap();
+        let tolerance = 1 << (bit_depth - 8);
+        for pixel in dst.chunks_exact(3) {
+            assert!(
+                pixel[0].abs_diff(pixel[1]) <= tolerance,
+                "{matrix:?} {bit_depth}"
+            );
+            assert!(
+                pixel[1].abs_diff(pixel[2]) <= tolerance,
+                "{matrix:?} {bit_depth}"
+            );
+        }
+        let max = *dst.iter().max().unwrap() as u32;
+        assert!(
+            max + tolerance as u32 >= (1 << bit_depth) - 1,
+            "{matrix:?} {bit_depth}"
+        );
+
+        // Alpha is kept, including 16 bit which has no converter with alpha
+        let rgba: Vec<u16> = gray
+            .chunks_exact(3)
+            .enumerate()
+            .flat_map(|(i, p)| [p[0], p[1], p[2], (i % 5) as u16])
+            .collect();
+        let mut dst = vec![0u16; rgba.len()];
+        clahe_yuv_rgba_u16(
+            &rgba,
+            w * 4,
+            &mut dst,
+            w * 4,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            YuvRange::Limited,
+            2f32,
+            ClaheGridSize::new(2, 2),
+        )
+        .unwrap();
+        for (i, pixel) in dst.chunks_exact(4).enumerate() {
+            assert_eq!(pixel[3], (i % 5) as u16, "{matrix:?} {bit_depth}");
+        }
+    }
+}
+
+/// Swaps red and blue of every pixel of `CHANNELS` image
+fn swap_rb<const CHANNELS: usize>(image: &[u16]) -> Vec<u16> {
+    let mut swapped = image.to_vec();
+    swapped
+        .chunks_exact_mut(CHANNELS)
+        .for_each(|p| p.swap(0, 2));
+    swapped
+}
+
+/// Blue first layouts equalize the same as RGB and RGBA, rows are wider than a reordered
+/// segment so they are converted in parts
+#[test]
+fn bgr_matches_rgb() {
+    const WIDE: usize = 300;
+    let (w, h) = (WIDE as u32, 3u32);
+    for (matrix, bit_depth) in [
+        (YuvMatrix::Bt709, 10),
+        (YuvMatrix::Bt601, 14),
+        (YuvMatrix::Bt2020, 16),
+        (YuvMatrix::YCgCo, 10),
+        (YuvMatrix::YCgCo, 12),
+    ] {
+        let levels = 1usize << bit_depth;
+        let rgb: Vec<u16> = (0..WIDE * 3 * 3)
+            .map(|i| ((i * 7919 + i / 7 * 31) % levels) as u16)
+            .collect();
+        let rgba: Vec<u16> = rgb
+            .chunks_exact(3)
+            .enumerate()
+            .flat_map(|(i, p)| [p[0], p[1], p[2], (i % 7) as u16])
+            .collect();
+        let grid = ClaheGridSize::new(4, 1);
+        let (range, threshold) = (YuvRange::Limited, 2f32);
+
+        let mut expected = vec![0u16; rgb.len()];
+        clahe_yuv_rgb_u16(
+            &rgb,
+            w * 3,
+            &mut expected,
+            w * 3,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            range,
+            threshold,
+            grid,
+        )
+        .unwrap();
+        let mut dst = vec![0u16; rgb.len()];
+        clahe_yuv_bgr_u16(
+            &swap_rb::<3>(&rgb),
+            w * 3,
+            &mut dst,
+            w * 3,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            range,
+            threshold,
+            grid,
+        )
+        .unwrap();
+        assert_eq!(swap_rb::<3>(&dst), expected, "{matrix:?} {bit_depth}");
+
+        let mut expected = vec![0u16; rgba.len()];
+        let mut dst = vec![0u16; rgba.len()];
+        hist_equal_yuv_rgba_u16(
+            &rgba,
+            w * 4,
+            &mut expected,
+            w * 4,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            range,
+        )
+        .unwrap();
+        hist_equal_yuv_bgra_u16(
+            &swap_rb::<4>(&rgba),
+            w * 4,
+            &mut dst,
+            w * 4,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            range,
+        )
+        .unwrap();
+        assert_eq!(swap_rb::<4>(&dst), expected, "{matrix:?} {bit_depth}");
+
+        ahe_yuv_rgba_u16(
+            &rgba,
+            w * 4,
+            &mut expected,
+            w * 4,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            range,
+            grid,
+        )
+        .unwrap();
+        ahe_yuv_bgra_u16(
+            &swap_rb::<4>(&rgba),
+            w * 4,
+            &mut dst,
+            w * 4,
+            w,
+            h,
+            bit_depth,
+            matrix,
+            range,
+            grid,
+        )
+        .unwrap();
+        assert_eq!(swap_rb::<4>(&dst), expected, "{matrix:?} {bit_depth}");
+    }
+}
+
+#[test]
+fn unsupported_bit_depths_are_rejected() {
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let src = vec![0u16; WIDTH * HEIGHT * 3];
+    let mut dst = vec![0u16; src.len()];
+    for (matrix, bit_depth) in [
+        (YuvMatrix::YCgCo, 16),
+        (YuvMatrix::YCgCo, 14),
+        (YuvMatrix::Bt709, 11),
+        (YuvMatrix::Bt601, 8),
+    ] {
+        assert!(matches!(
+            hist_equal_yuv_rgb_u16(
+                &src,
+                w * 3,
+                &mut dst,
+                w * 3,
+                w,
+                h,
+                bit_depth,
+                matrix,
+                YuvRange::Full
+            ),
+            Err(HistogramError::UnsupportedYuvBitDepth(_, Some(_)))
+        ));
+    }
+
+    let mut image = YuvPlanarImageMut::<u16>::alloc(w, h, YuvChromaSubsampling::Yuv420);
+    assert!(matches!(
+        hist_equal_yuv_planar_u16(&mut image, YuvChromaSubsampling::Yuv420, YuvRange::Full, 17),
+        Err(HistogramError::UnsupportedYuvBitDepth(17, None))
+    ));
+}
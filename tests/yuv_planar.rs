@@ -0,0 +1,122 @@
+#![allow(deprecated)]
+
+use histogram_equalization::{
+    clahe_gray, clahe_gray_sliding, clahe_yuv_planar, hist_equal_gray, hist_equal_yuv_biplanar,
+    BufferStoreMut, ClaheGridSize, ClaheMode, EqualizationMethod, Equalizer, YuvBiPlanarImageMut,
+    YuvChromaSubsampling, YuvPlanarImageMut, YuvRange,
+};
+
+const WIDTH: usize = 37;
+const HEIGHT: usize = 21;
+
+fn plane(width: usize, height: usize, seed: usize) -> Vec<u8> {
+    (0..width * height)
+        .map(|i| ((i * 7919 + (i / width) * 31 + seed) % 181 + 30) as u8)
+        .collect()
+}
+
+fn gray_reference(y_plane: &[u8], y_stride: usize, equalize: impl Fn(&[u8], &mut [u8])) -> Vec<u8> {
+    let mut dst = vec![0u8; y_plane.len()];
+    equalize(y_plane, &mut dst);
+    for (dst, src) in dst.chunks_mut(y_stride).zip(y_plane.chunks(y_stride)) {
+        dst[WIDTH..].copy_from_slice(&src[WIDTH..]);
+    }
+    dst
+}
+
+#[test]
+fn i420_luma_matches_gray() {
+    let y_stride = WIDTH + 3;
+    let chroma_width = WIDTH.div_ceil(2);
+    let chroma_height = HEIGHT.div_ceil(2);
+    let mut y_plane = plane(y_stride, HEIGHT, 0);
+    let mut u_plane = plane(chroma_width, chroma_height, 1);
+    let mut v_plane = plane(chroma_width, chroma_height, 2);
+    let (u_source, v_source) = (u_plane.clone(), v_plane.clone());
+    let grid = ClaheGridSize::new(4, 3);
+
+    let expected = gray_reference(&y_plane, y_stride, |src, dst| {
+        let (w, h, s) = (WIDTH as u32, HEIGHT as u32, y_stride as u32);
+        clahe_gray(src, s, dst, s, w, h, 3f32, grid).unwrap();
+    });
+
+    let mut image = YuvPlanarImageMut {
+        y_plane: BufferStoreMut::Borrowed(&mut y_plane),
+        y_stride: y_stride as u32,
+        u_plane: BufferStoreMut::Borrowed(&mut u_plane),
+        u_stride: chroma_width as u32,
+        v_plane: BufferStoreMut::Borrowed(&mut v_plane),
+        v_stride: chroma_width as u32,
+        width: WIDTH as u32,
+        height: HEIGHT as u32,
+    };
+    clahe_yuv_planar(
+        &mut image,
+        YuvChromaSubsampling::Yuv420,
+        YuvRange::Full,
+        3f32,
+        grid,
+    )
+    .unwrap();
+
+    assert_eq!(y_plane, expected);
+    assert_eq!(u_plane, u_source);
+    assert_eq!(v_plane, v_source);
+}
+
+#[test]
+fn nv12_luma_matches_gray() {
+    let uv_stride = WIDTH.div_ceil(2) * 2;
+    let mut y_plane = plane(WIDTH, HEIGHT, 3);
+    let mut uv_plane = plane(uv_stride, HEIGHT.div_ceil(2), 4);
+    let uv_source = uv_plane.clone();
+
+    let expected = gray_reference(&y_plane, WIDTH, |src, dst| {
+        let (w, h) = (WIDTH as u32, HEIGHT as u32);
+        hist_equal_gray(src, w, dst, w, w, h).unwrap();
+    });
+
+    let mut image = YuvBiPlanarImageMut {
+        y_plane: BufferStoreMut::Borrowed(&mut y_plane),
+        y_stride: WIDTH as u32,
+        uv_plane: BufferStoreMut::Borrowed(&mut uv_plane),
+        uv_stride: uv_stride as u32,
+        width: WIDTH as u32,
+        height: HEIGHT as u32,
+    };
+    hist_equal_yuv_biplanar(&mut image, YuvChromaSubsampling::Yuv420, YuvRange::Full).unwrap();
+
+    assert_eq!(y_plane, expected);
+    assert_eq!(uv_plane, uv_source);
+}
+
+#[test]
+fn i444_equalizer_uses_clahe_mode() {
+    let grid = ClaheGridSize::new(3, 2);
+    let mut image =
+        YuvPlanarImageMut::<u8>::alloc(WIDTH as u32, HEIGHT as u32, YuvChromaSubsampling::Yuv444);
+    let y_source = plane(WIDTH, HEIGHT, 5);
+    image.y_plane.borrow_mut().copy_from_slice(&y_source);
+
+    let expected = gray_reference(&y_source, WIDTH, |src, dst| {
+        let (w, h) = (WIDTH as u32, HEIGHT as u32);
+        clahe_gray_sliding(src, w, dst, w, w, h, 2f32, grid).unwrap();
+    });
+
+    let equalizer = Equalizer::new()
+        .with_method(EqualizationMethod::Clahe)
+        .with_threshold(2f32)
+        .with_grid_size(grid)
+        .with_clahe_mode(ClaheMode::SlidingWindow);
+    equalizer
+        .apply_yuv_planar(&mut image, YuvChromaSubsampling::Yuv444)
+        .unwrap();
+    assert_eq!(image.y_plane.borrow(), expected.as_slice());
+
+    // Chroma planes of 4:2:0 size are too small for 4:4:4
+    let mut image =
+        YuvPlanarImageMut::<u8>::alloc(WIDTH as u32, HEIGHT as u32, YuvChromaSubsampling::Yuv420);
+    assert!(equalizer
+        .apply_yuv_planar(&mut image, YuvChromaSubsampling::Yuv444)
+        .is_err());
+}
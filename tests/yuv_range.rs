@@ -0,0 +1,183 @@
+#![allow(deprecated)]
+
+use histogram_equalization::{
+    hist_equal_yuv_planar, hist_match_yuv_bgra, hist_match_yuv_rgb, lightness_histogram,
+    lightness_histogram_yuv, EqualizationColorSpace, EqualizationMethod, Equalizer, HistogramError,
+    ImageConfiguration, YuvChromaSubsampling, YuvMatrix, YuvPlanarImageMut, YuvRange,
+};
+
+const WIDTH: usize = 29;
+const HEIGHT: usize = 19;
+
+/// Luma within the limited range, levels are spaced so limited range keeps them distinct
+fn limited_luma() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .map(|i| ((i * 7919 + (i / WIDTH) * 31) % 50 * 3 + 40) as u8)
+        .collect()
+}
+
+#[test]
+fn limited_range_luma_spans_only_limited_levels() {
+    let source = limited_luma();
+    for (range, (first, last)) in [(YuvRange::Limited, (16, 235)), (YuvRange::Full, (0, 255))] {
+        let mut image = YuvPlanarImageMut::<u8>::alloc(
+            WIDTH as u32,
+            HEIGHT as u32,
+            YuvChromaSubsampling::Yuv420,
+        );
+        image.y_plane.borrow_mut().copy_from_slice(&source);
+        hist_equal_yuv_planar(&mut image, YuvChromaSubsampling::Yuv420, range).unwrap();
+
+        let luma = image.y_plane.borrow();
+        assert!(luma.iter().all(|&v| (first..=last).contains(&v)));
+        assert_eq!(*luma.iter().max().unwrap(), last);
+    }
+
+    // Clahe keeps luma within the limited range as well
+    let mut image =
+        YuvPlanarImageMut::<u8>::alloc(WIDTH as u32, HEIGHT as u32, YuvChromaSubsampling::Yuv444);
+    image.y_plane.borrow_mut().copy_from_slice(&source);
+    Equalizer::new()
+        .with_yuv_range(YuvRange::Limited)
+        .apply_yuv_planar(&mut image, YuvChromaSubsampling::Yuv444)
+        .unwrap();
+    assert!(image
+        .y_plane
+        .borrow()
+        .iter()
+        .all(|&v| (16..=235).contains(&v)));
+}
+
+/// Limited range luma is equalized over its own levels, so gray spans the whole RGB range
+/// as with full range
+#[test]
+fn rgb_matrices_and_ranges() {
+    let gray: Vec<u8> = limited_luma().iter().flat_map(|&v| [v, v, v]).collect();
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+
+    for matrix in [
+        YuvMatrix::Bt601,
+        YuvMatrix::Bt709,
+        YuvMatrix::Bt2020,
+        YuvMatrix::YCgCo,
+    ] {
+        let equalize = |range: YuvRange| {
+            let mut dst = vec![0u8; gray.len()];
+            Equalizer::new()
+                .with_color_space(EqualizationColorSpace::Yuv)
+                .with_method(EqualizationMethod::HistogramEqualization)
+                .with_yuv_matrix(matrix)
+                .with_yuv_range(range)
+                .apply(&gray, w * 3, &mut dst, w * 3, w, h)
+                .unwrap();
+            for pixel in dst.chunks_exact(3) {
+                assert!(pixel[0].abs_diff(pixel[1]) <= 1 && pixel[1].abs_diff(pixel[2]) <= 1);
+            }
+            assert_eq!(*dst.iter().max().unwrap(), 255, "{matrix:?} {range:?}");
+            dst
+        };
+        let limited = equalize(YuvRange::Limited);
+        let full = equalize(YuvRange::Full);
+        let max_difference = limited
+            .iter()
+            .zip(full.iter())
+            .map(|(&a, &b)| a.abs_diff(b))
+            .max()
+            .unwrap();
+        assert!(max_difference <= 2, "{matrix:?}");
+    }
+}
+
+#[test]
+fn matching_follows_matrix_and_range() {
+    let gray: Vec<u8> = limited_luma().iter().flat_map(|&v| [v, v, v]).collect();
+    let (w, h) = (WIDTH as u32, HEIGHT as u32);
+    let histogram = |matrix: YuvMatrix, range: YuvRange| {
+        lightness_histogram_yuv(
+            &gray,
+            w * 3,
+            w,
+            h,
+            ImageConfiguration::Rgb,
+            matrix,
+            range,
+            None,
+        )
+        .unwrap()
+    };
+
+    // Default YUV lightness is full range YCgCo
+    let default = lightness_histogram(
+        &gray,
+        w * 3,
+        w,
+        h,
+        ImageConfiguration::Rgb,
+        EqualizationColorSpace::Yuv,
+        256,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        default.bins,
+        histogram(YuvMatrix::YCgCo, YuvRange::Full).bins
+    );
+
+    let limited = histogram(YuvMatrix::Bt709, YuvRange::Limited);
+    assert_eq!(limited.bins.len(), 220);
+    assert_eq!(limited.total(), (WIDTH * HEIGHT) as u64);
+
+    // Matching to its own histogram leaves the image as it is up to conversion rounding
+    let mut dst = vec![0u8; gray.len()];
+    hist_match_yuv_rgb(
+        &gray,
+        w * 3,
+        &mut dst,
+        w * 3,
+        w,
+        h,
+        &limited,
+        YuvMatrix::Bt709,
+        YuvRange::Limited,
+    )
+    .unwrap();
+    let max_difference = gray
+        .iter()
+        .zip(dst.iter())
+        .map(|(&a, &b)| a.abs_diff(b))
+        .max()
+        .unwrap();
+    assert!(max_difference <= 2, "{max_difference}");
+
+    // Full range target doesn't fit limited range levels
+    let full = histogram(YuvMatrix::Bt709, YuvRange::Full);
+    let bgra: Vec<u8> = limited_luma().iter().flat_map(|&v| [v, v, v, 77]).collect();
+    let mut dst = vec![0u8; bgra.len()];
+    assert!(matches!(
+        hist_match_yuv_bgra(
+            &bgra,
+            w * 4,
+            &mut dst,
+            w * 4,
+            w,
+            h,
+            &full,
+            YuvMatrix::Bt709,
+            YuvRange::Limited,
+        ),
+        Err(HistogramError::HistogramBinsMismatch(_))
+    ));
+    hist_match_yuv_bgra(
+        &bgra,
+        w * 4,
+        &mut dst,
+        w * 4,
+        w,
+        h,
+        &full,
+        YuvMatrix::Bt709,
+        YuvRange::Full,
+    )
+    .unwrap();
+    assert!(dst.iter().skip(3).step_by(4).all(|&a| a == 77));
+}